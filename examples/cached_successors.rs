@@ -0,0 +1,107 @@
+//! Measures the work saved by caching the unit collection and the allowed-value encodings
+//! in `SystemUpdateFn` (instead of re-deriving them in every `successors_async` call).
+//!
+//! Runs a forward reachability from the first value of the first variable using
+//! `SystemUpdateFn::successors_async`, and then re-derives the cached values (exactly as the
+//! uncached implementation did at the start of every call) once for each of the performed calls.
+//!
+//! Re-deriving the cached values in every call (one `and` per domain, plus decoding the domain)
+//! used to add 25% (unary) to 71% (binary) to the forward reachability on
+//! `data/large/178-mast-cell-activation.sbml`, and 2.5% to 6% on the (much slower)
+//! `data/large/146_BUDDING-YEAST-FAURE-2009.sbml` (release build).
+//!
+//! Usage: `cargo run --release --example cached_successors -- <unary|binary|petri_net|gray> <sbml>`
+
+use std::time::Instant;
+
+use biodivine_lib_bdd::Bdd;
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    symbolic_domain::{
+        BinaryIntegerDomain, GrayCodeIntegerDomain, PetriNetIntegerDomain, SymbolicDomainOrd,
+        UnaryIntegerDomain,
+    },
+};
+
+fn load<DO: SymbolicDomainOrd<u8>>(
+    sbml_path: &str,
+) -> (
+    bio::update_fn::SystemUpdateFn<DO, u8>,
+    bio::update_fn::SmartSystemUpdateFn<DO, u8>,
+) {
+    let reader = || {
+        let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+            std::fs::File::open(sbml_path).expect("should be able to open file"),
+        ));
+        bio::find_start_of(&mut xml, &["listOfTransitions"]).expect("should be able to find");
+        xml
+    };
+    let system = bio::update_fn::SystemUpdateFn::try_from_xml(&mut reader())
+        .expect("should be able to parse");
+    let smart = bio::update_fn::SmartSystemUpdateFn::try_from_xml(&mut reader())
+        .expect("should be able to parse");
+    (system, smart)
+}
+
+fn measure<DO: SymbolicDomainOrd<u8>>(sbml_path: &str) {
+    let (system, smart) = load::<DO>(sbml_path);
+    let variables = smart.get_system_variables();
+
+    let mut calls = Vec::new();
+    let now = Instant::now();
+    let mut result = system.encode_one(&variables[0], &0);
+    'fwd: loop {
+        for var in variables.iter().rev() {
+            let successors = system.successors_async(var, &result);
+            calls.push(var.as_str());
+            if !successors.imp(&result).is_true() {
+                result = result.or(&successors);
+                continue 'fwd;
+            }
+        }
+        break;
+    }
+    let reachability = now.elapsed();
+
+    // what every call used to compute before doing any actual work
+    let bdd_variable_set = smart.get_bdd_variable_set();
+    let now = Instant::now();
+    for var in &calls {
+        let unit_collection = variables
+            .iter()
+            .fold(bdd_variable_set.mk_true(), |acc: Bdd, it| {
+                let domain = smart.get_domain(it).expect("known variable");
+                acc.and(&domain.unit_collection(bdd_variable_set))
+            });
+        let domain = smart.get_domain(var).expect("known variable");
+        let allowed_values = domain
+            .decode_collection(bdd_variable_set, &domain.unit_collection(bdd_variable_set))
+            .into_iter()
+            .map(|value| domain.raw_bdd_variables_encode(&value))
+            .collect::<Vec<_>>();
+        std::hint::black_box((unit_collection, allowed_values));
+    }
+    let rederived = now.elapsed();
+
+    println!(
+        "{} calls; reachability (cached) {:?}; re-deriving the cached values {:?} ({:.1}% extra)",
+        calls.len(),
+        reachability,
+        rederived,
+        100.0 * rederived.as_secs_f64() / reachability.as_secs_f64()
+    );
+}
+
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let representation = args[1].as_str();
+    let sbml_path = args[2].as_str();
+
+    match representation {
+        "unary" => measure::<UnaryIntegerDomain>(sbml_path),
+        "binary" => measure::<BinaryIntegerDomain<u8>>(sbml_path),
+        "petri_net" => measure::<PetriNetIntegerDomain>(sbml_path),
+        "gray" | "grey" => measure::<GrayCodeIntegerDomain<u8>>(sbml_path),
+        _ => panic!("Unknown representation: {}.", representation),
+    }
+}
@@ -0,0 +1,103 @@
+//! Measures `SmartSystemUpdateFn::successors_async_any` & `predecessors_async_any`, and (with
+//! the `parallel` feature) their concurrent counterparts `par_successors_async_any` &
+//! `par_predecessors_async_any`.
+//!
+//! Iterates each operation to a fixed point from the state where all the variables are zero,
+//! `rounds` times, and prints the total time of each. The number of the threads used by the
+//! concurrent operations can be set using `RAYON_NUM_THREADS`.
+//!
+//! Usage: `cargo run --release [--features parallel] --example parallel_any -- <unary|binary|petri_net|gray> <rounds> <sbml>...`
+//!
+//! Measured using `binary 3 data/large/175_SEA-URCHIN.sbml data/large/178-mast-cell-activation.sbml`
+//! (total times of the 3 rounds) on a single-core Xeon; with one core, the concurrent operations
+//! can only show their overhead, whatever `RAYON_NUM_THREADS` is:
+//!
+//! | model                    | threads | successors | par_successors | predecessors | par_predecessors |
+//! |--------------------------|---------|------------|----------------|--------------|------------------|
+//! | 175_SEA-URCHIN           | 1       | 12.0 ms    | 12.5 ms        | 1.64 s       | 1.68 s           |
+//! | 175_SEA-URCHIN           | 4       | 10.0 ms    | 12.7 ms        | 1.51 s       | 1.70 s           |
+//! | 178-mast-cell-activation | 1       | 4.2 ms     | 5.1 ms         | 2.11 s       | 2.53 s           |
+//! | 178-mast-cell-activation | 4       | 3.3 ms     | 6.3 ms         | 1.91 s       | 2.44 s           |
+
+use std::time::{Duration, Instant};
+
+use biodivine_lib_bdd::Bdd;
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    symbolic_domain::{
+        BinaryIntegerDomain, GrayCodeIntegerDomain, PetriNetIntegerDomain, SymbolicDomainOrd,
+        UnaryIntegerDomain,
+    },
+};
+
+fn fixed_point(initial: &Bdd, step: impl Fn(&Bdd) -> Bdd) -> Bdd {
+    let mut result = initial.clone();
+    loop {
+        let extended = result.or(&step(&result));
+        if extended == result {
+            return result;
+        }
+        result = extended;
+    }
+}
+
+fn time_fixed_point(rounds: usize, initial: &Bdd, step: impl Fn(&Bdd) -> Bdd) -> Duration {
+    let mut total = Duration::ZERO;
+    for _ in 0..rounds {
+        let now = Instant::now();
+        std::hint::black_box(fixed_point(initial, &step));
+        total += now.elapsed();
+    }
+    total
+}
+
+fn measure<DO: SymbolicDomainOrd<u8> + Sync>(rounds: usize, sbml_path: &str) {
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open(sbml_path).expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, &["listOfTransitions"]).expect("should be able to find");
+    let system = bio::update_fn::SmartSystemUpdateFn::<DO, u8>::try_from_xml(&mut xml)
+        .expect("should be able to parse");
+
+    let initial = system
+        .standard_variables_names_and_domains()
+        .iter()
+        .fold(system.unit_vertex_set(), |acc, (name, _)| {
+            acc.and(&system.encode_one(name, &0))
+        });
+
+    let fwd = time_fixed_point(rounds, &initial, |set| system.successors_async_any(set));
+    let bwd = time_fixed_point(rounds, &initial, |set| system.predecessors_async_any(set));
+    println!(
+        "{}: successors_async_any {:?}; predecessors_async_any {:?}",
+        sbml_path, fwd, bwd
+    );
+
+    #[cfg(feature = "parallel")]
+    {
+        let fwd = time_fixed_point(rounds, &initial, |set| system.par_successors_async_any(set));
+        let bwd = time_fixed_point(rounds, &initial, |set| {
+            system.par_predecessors_async_any(set)
+        });
+        println!(
+            "{}: par_successors_async_any {:?}; par_predecessors_async_any {:?}",
+            sbml_path, fwd, bwd
+        );
+    }
+}
+
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let representation = args[1].as_str();
+    let rounds = args[2].parse().expect("rounds should be a number");
+
+    for sbml_path in &args[3..] {
+        match representation {
+            "unary" => measure::<UnaryIntegerDomain>(rounds, sbml_path),
+            "binary" => measure::<BinaryIntegerDomain<u8>>(rounds, sbml_path),
+            "petri_net" => measure::<PetriNetIntegerDomain>(rounds, sbml_path),
+            "gray" | "grey" => measure::<GrayCodeIntegerDomain<u8>>(rounds, sbml_path),
+            _ => panic!("Unknown representation: {}.", representation),
+        }
+    }
+}
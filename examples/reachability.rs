@@ -1,26 +1,43 @@
-use biodivine_lib_logical_models::benchmarks::reachability::reachability_benchmark;
+use biodivine_lib_logical_models::benchmarks::{
+    reachability::reachability_benchmark_with_picker,
+    state_picker::{RandomStatePicker, SatWitnessPicker},
+};
 // use biodivine_lib_logical_models::prelude::old_symbolic_domain::{
 //     BinaryIntegerDomain, GrayCodeIntegerDomain, PetriNetIntegerDomain, UnaryIntegerDomain,
 // };
 
 use biodivine_lib_logical_models::prelude::symbolic_domain::{
-    BinaryIntegerDomain, GrayCodeIntegerDomain, PetriNetIntegerDomain, UnaryIntegerDomain,
+    BinaryIntegerDomain, GrayCodeIntegerDomain, PetriNetIntegerDomain, SymbolicDomainOrd,
+    UnaryIntegerDomain,
 };
 
 fn main() {
     let args = std::env::args().collect::<Vec<_>>();
     let representation = args[1].clone();
     let sbml_path = args[2].clone();
+    // optional; if given, the SCC seeds are picked randomly using this seed
+    let seed = args
+        .get(3)
+        .map(|seed| seed.parse::<u64>().expect("The seed should be a number."));
 
     let now = std::time::Instant::now();
 
     match representation.as_str() {
-        "unary" => reachability_benchmark::<UnaryIntegerDomain>(sbml_path.as_str()),
-        "binary" => reachability_benchmark::<BinaryIntegerDomain<u8>>(sbml_path.as_str()),
-        "petri_net" => reachability_benchmark::<PetriNetIntegerDomain>(sbml_path.as_str()),
-        "gray" | "grey" => reachability_benchmark::<GrayCodeIntegerDomain<u8>>(sbml_path.as_str()),
+        "unary" => run::<UnaryIntegerDomain>(sbml_path.as_str(), seed),
+        "binary" => run::<BinaryIntegerDomain<u8>>(sbml_path.as_str(), seed),
+        "petri_net" => run::<PetriNetIntegerDomain>(sbml_path.as_str(), seed),
+        "gray" | "grey" => run::<GrayCodeIntegerDomain<u8>>(sbml_path.as_str(), seed),
         _ => panic!("Unknown representation: {}.", representation),
     }
 
     println!("Time: {}s", now.elapsed().as_secs());
 }
+
+fn run<DO: SymbolicDomainOrd<u8> + std::fmt::Debug>(sbml_path: &str, seed: Option<u64>) {
+    match seed {
+        Some(seed) => {
+            reachability_benchmark_with_picker::<DO, _>(sbml_path, &RandomStatePicker::new(seed))
+        }
+        None => reachability_benchmark_with_picker::<DO, _>(sbml_path, &SatWitnessPicker),
+    }
+}
@@ -1,2 +1,10 @@
+//! Drivers of the reachability benchmarks (used by the examples).
+//!
+//! Unlike the rest of the library, which never writes to stdout, the benchmark drivers report
+//! their progress using `println!`. The reachability procedures themselves only report it to
+//! the (optional) [observer::ReachabilityObserver].
+
+pub mod observer;
 pub mod reachability;
 pub mod rewritten_reachability;
+pub mod state_picker;
@@ -0,0 +1,71 @@
+use biodivine_lib_bdd::Bdd;
+
+use crate::symbolic_domains::symbolic_domain::SymbolicDomain;
+use crate::update::update_fn::SmartSystemUpdateFn;
+use crate::utils::log_percent;
+
+/// Receives the progress of the prototype reachability procedures (`reach_fwd` & `reach_bwd`),
+/// e.g. to collect convergence statistics.
+pub trait ReachabilityObserver {
+    /// Called with the initial set (`step == 0`) and after every step that extended the result.
+    fn on_iteration(&mut self, step: usize, result: &Bdd, bdd_size: usize);
+    /// Called once the result converged, after `total_steps` steps that extended it.
+    fn on_done(&mut self, total_steps: usize);
+    /// Called every time the successors (or predecessors) of a set under a single `variable`
+    /// are computed, i.e. for every image computation, extending the result or not.
+    fn on_image(&mut self, variable: &str) {
+        let _ = variable;
+    }
+}
+
+/// Prints the progress of the reachability to stdout (this is what the reachability
+/// benchmarks use).
+pub struct StdoutReachabilityObserver<'a, D>
+where
+    D: SymbolicDomain<u8>,
+{
+    system: &'a SmartSystemUpdateFn<D, u8>,
+    /// the progress is reported relative to the size of this set
+    universe: &'a Bdd,
+    /// state count & bdd size of the last reported result
+    last: Option<(f64, usize)>,
+}
+
+impl<'a, D> StdoutReachabilityObserver<'a, D>
+where
+    D: SymbolicDomain<u8>,
+{
+    pub fn new(system: &'a SmartSystemUpdateFn<D, u8>, universe: &'a Bdd) -> Self {
+        Self {
+            system,
+            universe,
+            last: None,
+        }
+    }
+}
+
+impl<D> ReachabilityObserver for StdoutReachabilityObserver<'_, D>
+where
+    D: SymbolicDomain<u8>,
+{
+    fn on_iteration(&mut self, step: usize, result: &Bdd, bdd_size: usize) {
+        let states = self.system.count_states(result);
+        if step == 0 {
+            println!("Start reachability: (states={}, size={})", states, bdd_size);
+        } else {
+            println!(
+                " >> (progress={:.2}%%, states={}, size={})",
+                log_percent(result, self.universe),
+                states,
+                bdd_size
+            );
+        }
+        self.last = Some((states, bdd_size));
+    }
+
+    fn on_done(&mut self, _total_steps: usize) {
+        if let Some((states, bdd_size)) = self.last.take() {
+            println!(" >> Done. (states={}, size={})", states, bdd_size);
+        }
+    }
+}
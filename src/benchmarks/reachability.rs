@@ -6,18 +6,30 @@ use std::fmt::Debug;
 //     prototype::{count_states, find_start_of, log_percent, pick_state_bdd, SmartSystemUpdateFn},
 // };
 
+use crate::benchmarks::observer::{ReachabilityObserver, StdoutReachabilityObserver};
+use crate::benchmarks::state_picker::{SatWitnessPicker, StatePicker};
 use crate::symbolic_domains::symbolic_domain::SymbolicDomainOrd;
 use crate::update::update_fn::SmartSystemUpdateFn;
-use crate::utils::{count_states, log_percent, pick_state_bdd};
+use crate::utils::count_states;
 use crate::xml_parsing::utils::find_start_of;
 
 pub fn reachability_benchmark<D: SymbolicDomainOrd<u8> + Debug>(sbml_path: &str) {
+    reachability_benchmark_with_picker::<D, _>(sbml_path, &SatWitnessPicker)
+}
+
+/// Like [reachability_benchmark], but the seed of each weak SCC is chosen by the `picker`
+/// (instead of always being the [SatWitnessPicker] state).
+pub fn reachability_benchmark_with_picker<D, P>(sbml_path: &str, picker: &P)
+where
+    D: SymbolicDomainOrd<u8> + Debug,
+    P: StatePicker<D, u8>,
+{
     let smart_system_update_fn = {
         let file = std::fs::File::open(sbml_path).expect("Cannot open SBML file.");
         let reader = std::io::BufReader::new(file);
         let mut xml = xml::reader::EventReader::new(reader);
 
-        find_start_of(&mut xml, "listOfTransitions")
+        find_start_of(&mut xml, &["listOfTransitions"])
             .expect("Cannot find transitions in the SBML file.");
 
         SmartSystemUpdateFn::<D, u8>::try_from_xml(&mut xml)
@@ -37,10 +49,24 @@ pub fn reachability_benchmark<D: SymbolicDomainOrd<u8> + Debug>(sbml_path: &str)
     );
     let mut universe = unit.clone();
     while !universe.is_false() {
-        let mut weak_scc = pick_state_bdd(&smart_system_update_fn, &universe);
+        let mut weak_scc = picker.pick(&smart_system_update_fn, &universe);
         loop {
-            let bwd_reachable = reach_bwd(&smart_system_update_fn, &weak_scc, &universe);
-            let fwd_bwd_reachable = reach_fwd(&smart_system_update_fn, &bwd_reachable, &universe);
+            let bwd_reachable = reach_bwd(
+                &smart_system_update_fn,
+                &weak_scc,
+                Some(&mut StdoutReachabilityObserver::new(
+                    &smart_system_update_fn,
+                    &universe,
+                )),
+            );
+            let fwd_bwd_reachable = reach_fwd(
+                &smart_system_update_fn,
+                &bwd_reachable,
+                Some(&mut StdoutReachabilityObserver::new(
+                    &smart_system_update_fn,
+                    &universe,
+                )),
+            );
 
             // FWD/BWD reachable set is not a subset of weak SCC, meaning the SCC can be expanded.
             if !fwd_bwd_reachable.imp(&weak_scc).is_true() {
@@ -73,43 +99,41 @@ pub fn reachability_benchmark<D: SymbolicDomainOrd<u8> + Debug>(sbml_path: &str)
 ///
 /// The result BDD contains a vertex `x` if and only if there is a (possibly zero-length) path
 /// from some vertex `x' \in initial` into `x`, i.e. `x' -> x`.
+///
+/// The progress is reported to the `observer` (if any); use [StdoutReachabilityObserver]
+/// to print it.
 pub fn reach_fwd<D: SymbolicDomainOrd<u8> + Debug>(
     system: &SmartSystemUpdateFn<D, u8>,
     initial: &Bdd,
-    universe: &Bdd,
+    mut observer: Option<&mut dyn ReachabilityObserver>,
 ) -> Bdd {
     // The list of system variables, sorted in descending order (i.e. opposite order compared
     // to the ordering inside BDDs).
-    let sorted_variables = system.get_system_variables();
+    let sorted_variables = system.variables_in_bdd_order();
     let mut result = initial.clone();
-    println!(
-        "Start forward reachability: (states={}, size={})",
-        count_states(system, &result),
-        result.size()
-    );
+    let mut steps = 0;
+    if let Some(observer) = observer.as_mut() {
+        observer.on_iteration(steps, &result, result.size());
+    }
     'fwd: loop {
         for var in sorted_variables.iter().rev() {
-            let successors = system.successors_async(var.as_str(), &result);
+            let successors = system.transition_under_variable(var.as_str(), &result);
 
             // Should be equivalent to "successors \not\subseteq result".
             if !successors.imp(&result).is_true() {
                 result = result.or(&successors);
-                println!(
-                    " >> (progress={:.2}%%, states={}, size={})",
-                    log_percent(&result, universe),
-                    count_states(system, &result),
-                    result.size()
-                );
+                steps += 1;
+                if let Some(observer) = observer.as_mut() {
+                    observer.on_iteration(steps, &result, result.size());
+                }
                 continue 'fwd;
             }
         }
 
         // No further successors were computed across all variables. We are done.
-        println!(
-            " >> Done. (states={}, size={})",
-            count_states(system, &result),
-            result.size()
-        );
+        if let Some(observer) = observer {
+            observer.on_done(steps);
+        }
         return result;
     }
 }
@@ -118,41 +142,39 @@ pub fn reach_fwd<D: SymbolicDomainOrd<u8> + Debug>(
 ///
 /// The result BDD contains a vertex `x` if and only if there is a (possibly zero-length) path
 /// from `x` into some vertex `x' \in initial`, i.e. `x -> x'`.
+///
+/// The progress is reported to the `observer` (if any); use [StdoutReachabilityObserver]
+/// to print it.
 pub fn reach_bwd<D: SymbolicDomainOrd<u8> + Debug>(
     system: &SmartSystemUpdateFn<D, u8>,
     initial: &Bdd,
-    universe: &Bdd,
+    mut observer: Option<&mut dyn ReachabilityObserver>,
 ) -> Bdd {
-    let sorted_variables = system.get_system_variables();
+    let sorted_variables = system.variables_in_bdd_order();
     let mut result = initial.clone();
-    println!(
-        "Start backward reachability: (states={}, size={})",
-        count_states(system, &result),
-        result.size()
-    );
+    let mut steps = 0;
+    if let Some(observer) = observer.as_mut() {
+        observer.on_iteration(steps, &result, result.size());
+    }
     'bwd: loop {
         for var in sorted_variables.iter().rev() {
-            let predecessors = system.predecessors_async(var.as_str(), result.to_owned());
+            let predecessors = system.predecessors_under_variable(var.as_str(), &result);
 
             // Should be equivalent to "predecessors \not\subseteq result".
             if !predecessors.imp(&result).is_true() {
                 result = result.or(&predecessors);
-                println!(
-                    " >> (progress={:.2}%%, states={}, size={})",
-                    log_percent(&result, universe),
-                    count_states(system, &result),
-                    result.size()
-                );
+                steps += 1;
+                if let Some(observer) = observer.as_mut() {
+                    observer.on_iteration(steps, &result, result.size());
+                }
                 continue 'bwd;
             }
         }
 
         // No further predecessors were computed across all variables. We are done.
-        println!(
-            " >> Done. (states={}, size={})",
-            count_states(system, &result),
-            result.size()
-        );
+        if let Some(observer) = observer {
+            observer.on_done(steps);
+        }
         return result;
     }
 }
@@ -2,21 +2,36 @@ use biodivine_lib_bdd::Bdd;
 use std::fmt::Debug;
 
 use crate::{
-    prelude::find_start_of, symbolic_domains::symbolic_domain::SymbolicDomainOrd,
+    benchmarks::{
+        observer::{ReachabilityObserver, StdoutReachabilityObserver},
+        state_picker::{SatWitnessPicker, StatePicker},
+    },
+    prelude::find_start_of,
+    symbolic_domains::symbolic_domain::SymbolicDomainOrd,
     update::update_fn::SmartSystemUpdateFn as RewrittenSmartSystemUpdateFn,
 };
 
 pub fn log_percent(set: &Bdd, universe: &Bdd) -> f64 {
-    set.cardinality().log2() / universe.cardinality().log2() * 100.0
+    crate::utils::log_percent(set, universe)
 }
 
 pub fn reachability_benchmark<DO: SymbolicDomainOrd<u8> + Debug>(sbml_path: &str) {
+    reachability_benchmark_with_picker::<DO, _>(sbml_path, &SatWitnessPicker)
+}
+
+/// Like [reachability_benchmark], but the seed of each weak SCC is chosen by the `picker`
+/// (instead of always being the [SatWitnessPicker] state).
+pub fn reachability_benchmark_with_picker<DO, P>(sbml_path: &str, picker: &P)
+where
+    DO: SymbolicDomainOrd<u8> + Debug,
+    P: StatePicker<DO, u8>,
+{
     let smart_system_update_fn = {
         let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
             std::fs::File::open(sbml_path).expect("should be able to open file"),
         ));
 
-        find_start_of(&mut xml, "listOfTransitions")
+        find_start_of(&mut xml, &["listOfTransitions"])
             .expect("Cannot find transitions in the SBML file.");
 
         RewrittenSmartSystemUpdateFn::<DO, u8>::try_from_xml(&mut xml)
@@ -36,10 +51,24 @@ pub fn reachability_benchmark<DO: SymbolicDomainOrd<u8> + Debug>(sbml_path: &str
     );
     let mut universe = unit.clone();
     while !universe.is_false() {
-        let mut weak_scc = smart_system_update_fn.pick_state_bdd(&universe);
+        let mut weak_scc = picker.pick(&smart_system_update_fn, &universe);
         loop {
-            let bwd_reachable = reach_bwd(&smart_system_update_fn, &weak_scc, &universe);
-            let fwd_bwd_reachable = reach_fwd(&smart_system_update_fn, &bwd_reachable, &universe);
+            let bwd_reachable = reach_bwd(
+                &smart_system_update_fn,
+                &weak_scc,
+                Some(&mut StdoutReachabilityObserver::new(
+                    &smart_system_update_fn,
+                    &universe,
+                )),
+            );
+            let fwd_bwd_reachable = reach_fwd(
+                &smart_system_update_fn,
+                &bwd_reachable,
+                Some(&mut StdoutReachabilityObserver::new(
+                    &smart_system_update_fn,
+                    &universe,
+                )),
+            );
 
             // FWD/BWD reachable set is not a subset of weak SCC, meaning the SCC can be expanded.
             if !fwd_bwd_reachable.imp(&weak_scc).is_true() {
@@ -72,86 +101,195 @@ pub fn reachability_benchmark<DO: SymbolicDomainOrd<u8> + Debug>(sbml_path: &str
 ///
 /// The result BDD contains a vertex `x` if and only if there is a (possibly zero-length) path
 /// from some vertex `x' \in initial` into `x`, i.e. `x' -> x`.
+///
+/// The progress is reported to the `observer` (if any); use [StdoutReachabilityObserver]
+/// to print it.
 pub fn reach_fwd<D: SymbolicDomainOrd<u8> + Debug>(
     system: &RewrittenSmartSystemUpdateFn<D, u8>,
     initial: &Bdd,
-    universe: &Bdd,
+    observer: Option<&mut dyn ReachabilityObserver>,
+) -> Bdd {
+    reach_fwd_ordered(system, initial, &default_order(system), observer)
+}
+
+/// The same as [reach_fwd], but the variables are tried in the given `order` (instead of
+/// the opposite of the ordering inside BDDs, i.e. the reversed
+/// [RewrittenSmartSystemUpdateFn::variables_in_bdd_order]).
+/// After every variable that extends the result, the next step starts again from the first
+/// variable of the `order`, so putting the "productive" variables first can reduce the number
+/// of the steps considerably. The result does not depend on the `order`.
+///
+/// # Panics
+///
+/// Panics if the `order` does not contain every variable of the system, or contains
+/// an unknown one.
+pub fn reach_fwd_ordered<D: SymbolicDomainOrd<u8> + Debug>(
+    system: &RewrittenSmartSystemUpdateFn<D, u8>,
+    initial: &Bdd,
+    order: &[String],
+    mut observer: Option<&mut dyn ReachabilityObserver>,
 ) -> Bdd {
-    // The list of system variables, sorted in descending order (i.e. opposite order compared
-    // to the ordering inside BDDs).
-    let sorted_variables = system.get_system_variables();
+    check_order(system, order);
     let mut result = initial.clone();
-    println!(
-        "Start forward reachability: (states={}, size={})",
-        system.count_states(&result),
-        result.size()
-    );
+    let mut steps = 0;
+    if let Some(observer) = observer.as_mut() {
+        observer.on_iteration(steps, &result, result.size());
+    }
     'fwd: loop {
-        for var in sorted_variables.iter().rev() {
-            let successors = system.successors_async(var.as_str(), &result);
+        for var in order {
+            let successors = system.transition_under_variable(var.as_str(), &result);
+            if let Some(observer) = observer.as_mut() {
+                observer.on_image(var);
+            }
 
             // Should be equivalent to "successors \not\subseteq result".
             if !successors.imp(&result).is_true() {
                 result = result.or(&successors);
-                println!(
-                    " >> (progress={:.2}%%, states={}, size={})",
-                    log_percent(&result, universe),
-                    system.count_states(&result),
-                    result.size()
-                );
+                steps += 1;
+                if let Some(observer) = observer.as_mut() {
+                    observer.on_iteration(steps, &result, result.size());
+                }
                 continue 'fwd;
             }
         }
 
         // No further successors were computed across all variables. We are done.
-        println!(
-            " >> Done. (states={}, size={})",
-            system.count_states(&result),
-            result.size()
-        );
+        if let Some(observer) = observer {
+            observer.on_done(steps);
+        }
         return result;
     }
 }
 
+/// The same as [reach_fwd], but the successors are only computed for the frontier, i.e. the
+/// states discovered in the previous round, instead of for the whole accumulated result.
+///
+/// Every round computes the successors of the frontier under all variables and the states
+/// that are not in the result yet become the next frontier. Within a round, every variable
+/// also sees the states discovered by the variables before it (chaining), which reduces
+/// the number of the rounds, and hence of the image computations, considerably. The observer
+/// is notified once per round (not once per variable as in [reach_fwd]).
+pub fn reach_fwd_frontier<D: SymbolicDomainOrd<u8> + Debug>(
+    system: &RewrittenSmartSystemUpdateFn<D, u8>,
+    initial: &Bdd,
+    mut observer: Option<&mut dyn ReachabilityObserver>,
+) -> Bdd {
+    let sorted_variables = system.variables_in_bdd_order();
+    let mut result = initial.clone();
+    let mut frontier = initial.clone();
+    let mut steps = 0;
+    if let Some(observer) = observer.as_mut() {
+        observer.on_iteration(steps, &result, result.size());
+    }
+    while !frontier.is_false() {
+        let mut next_frontier = system.get_bdd_variable_set().mk_false();
+        for var in sorted_variables.iter().rev() {
+            let successors =
+                system.transition_under_variable(var.as_str(), &frontier.or(&next_frontier));
+            if let Some(observer) = observer.as_mut() {
+                observer.on_image(var);
+            }
+            let new_states = successors.and_not(&result);
+            if !new_states.is_false() {
+                result = result.or(&new_states);
+                next_frontier = next_frontier.or(&new_states);
+            }
+        }
+
+        frontier = next_frontier;
+        if !frontier.is_false() {
+            steps += 1;
+            if let Some(observer) = observer.as_mut() {
+                observer.on_iteration(steps, &result, result.size());
+            }
+        }
+    }
+
+    if let Some(observer) = observer {
+        observer.on_done(steps);
+    }
+    result
+}
+
 /// Compute the set of vertices that are backward-reachable from the `initial` set.
 ///
 /// The result BDD contains a vertex `x` if and only if there is a (possibly zero-length) path
 /// from `x` into some vertex `x' \in initial`, i.e. `x -> x'`.
+///
+/// The progress is reported to the `observer` (if any); use [StdoutReachabilityObserver]
+/// to print it.
 pub fn reach_bwd<D: SymbolicDomainOrd<u8> + Debug>(
     system: &RewrittenSmartSystemUpdateFn<D, u8>,
     initial: &Bdd,
-    universe: &Bdd,
+    observer: Option<&mut dyn ReachabilityObserver>,
+) -> Bdd {
+    reach_bwd_ordered(system, initial, &default_order(system), observer)
+}
+
+/// The same as [reach_bwd], but the variables are tried in the given `order`;
+/// see [reach_fwd_ordered].
+///
+/// # Panics
+///
+/// Panics if the `order` does not contain every variable of the system, or contains
+/// an unknown one.
+pub fn reach_bwd_ordered<D: SymbolicDomainOrd<u8> + Debug>(
+    system: &RewrittenSmartSystemUpdateFn<D, u8>,
+    initial: &Bdd,
+    order: &[String],
+    mut observer: Option<&mut dyn ReachabilityObserver>,
 ) -> Bdd {
-    let sorted_variables = system.get_system_variables();
+    check_order(system, order);
     let mut result = initial.clone();
-    println!(
-        "Start backward reachability: (states={}, size={})",
-        system.count_states(&result),
-        result.size()
-    );
+    let mut steps = 0;
+    if let Some(observer) = observer.as_mut() {
+        observer.on_iteration(steps, &result, result.size());
+    }
     'bwd: loop {
-        for var in sorted_variables.iter().rev() {
-            let predecessors = system.predecessors_async(var.as_str(), result.clone());
+        for var in order {
+            let predecessors = system.predecessors_under_variable(var.as_str(), &result);
+            if let Some(observer) = observer.as_mut() {
+                observer.on_image(var);
+            }
 
             // Should be equivalent to "predecessors \not\subseteq result".
             if !predecessors.imp(&result).is_true() {
                 result = result.or(&predecessors);
-                println!(
-                    " >> (progress={:.2}%%, states={}, size={})",
-                    log_percent(&result, universe),
-                    system.count_states(&result),
-                    result.size()
-                );
+                steps += 1;
+                if let Some(observer) = observer.as_mut() {
+                    observer.on_iteration(steps, &result, result.size());
+                }
                 continue 'bwd;
             }
         }
 
         // No further predecessors were computed across all variables. We are done.
-        println!(
-            " >> Done. (states={}, size={})",
-            system.count_states(&result),
-            result.size()
-        );
+        if let Some(observer) = observer {
+            observer.on_done(steps);
+        }
         return result;
     }
 }
+
+/// The system variables in the opposite order compared to the ordering inside BDDs.
+fn default_order<D: SymbolicDomainOrd<u8>>(
+    system: &RewrittenSmartSystemUpdateFn<D, u8>,
+) -> Vec<String> {
+    let mut order = system.variables_in_bdd_order();
+    order.reverse();
+    order
+}
+
+/// Ensures the reachability does not miss the transitions of some variable.
+fn check_order<D: SymbolicDomainOrd<u8>>(
+    system: &RewrittenSmartSystemUpdateFn<D, u8>,
+    order: &[String],
+) {
+    if let Some(missing) = system
+        .get_system_variables()
+        .into_iter()
+        .find(|var| !order.contains(var))
+    {
+        panic!("variable `{}` is missing from the order", missing)
+    }
+}
@@ -0,0 +1,68 @@
+use std::cell::RefCell;
+
+use biodivine_lib_bdd::{Bdd, BddPartialValuation};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::symbolic_domains::symbolic_domain::SymbolicDomain;
+use crate::update::update_fn::SmartSystemUpdateFn;
+
+/// Chooses the state from which the SCC search of the reachability benchmarks continues
+/// (the seed of the next weak SCC).
+pub trait StatePicker<D, T>
+where
+    D: SymbolicDomain<T>,
+{
+    /// Returns a [Bdd] representing a single (un-primed) state of the non-empty `set`.
+    fn pick(&self, system: &SmartSystemUpdateFn<D, T>, set: &Bdd) -> Bdd;
+}
+
+/// Picks the state given by [Bdd::sat_witness], i.e. the "lowest corner" of the set;
+/// see [SmartSystemUpdateFn::pick_state_bdd]. This is the default of the benchmarks.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SatWitnessPicker;
+
+impl<D, T> StatePicker<D, T> for SatWitnessPicker
+where
+    D: SymbolicDomain<T>,
+{
+    fn pick(&self, system: &SmartSystemUpdateFn<D, T>, set: &Bdd) -> Bdd {
+        system.pick_state_bdd(set)
+    }
+}
+
+/// Picks a random state of the set, using a generator initialized with the given seed, so that
+/// the runs are reproducible. The states are not necessarily picked uniformly (the distribution
+/// depends on the structure of the set; see [Bdd::random_valuation]).
+#[derive(Debug)]
+pub struct RandomStatePicker {
+    rng: RefCell<StdRng>,
+}
+
+impl RandomStatePicker {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: RefCell::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+}
+
+impl<D, T> StatePicker<D, T> for RandomStatePicker
+where
+    D: SymbolicDomain<T>,
+{
+    fn pick(&self, system: &SmartSystemUpdateFn<D, T>, set: &Bdd) -> Bdd {
+        // only the standard variables are picked randomly, so that the states with more
+        // (primed or parameter) valuations within the set are not favored
+        let valuation = system
+            .project_to_states(set)
+            .random_valuation(&mut *self.rng.borrow_mut())
+            .expect("Cannot pick state from an empty set.");
+        let mut state_data = BddPartialValuation::empty();
+        for var in system.standard_variables() {
+            state_data.set_value(var, valuation.value(var))
+        }
+        system
+            .get_bdd_variable_set()
+            .mk_conjunctive_clause(&state_data)
+    }
+}
@@ -1,6 +1,8 @@
 #![allow(dead_code)]
 
-use super::proposition::Proposition;
+use std::{collections::HashSet, fmt::Display};
+
+use super::proposition::{Proposition, VariableComparison};
 
 /// Represents a recursive expression. Leaf nodes are propositions. Join `Expression::Terminal`s
 /// into more complex expressions using other `Expression` variants.
@@ -8,25 +10,252 @@ use super::proposition::Proposition;
 /// Available variants:
 ///
 /// - `Expression::Terminal` - a leaf node, containing a proposition
+/// - `Expression::VariableComparison` - a leaf node, comparing the values of two variables
 /// - `Expression::Not` - a negation of the inner expression
 /// - `Expression::And` - a conjunction of the inner expressions. The inner expressions are
-/// stored inside a `Vec<_>`, to allow for an arbitrary number of conjuncts useful for
-/// creating CNF formulas. `Expression::And` with an empty `Vec<_>` is equivalent to
-/// constant `true`.
+///   stored inside a `Vec<_>`, to allow for an arbitrary number of conjuncts useful for
+///   creating CNF formulas. `Expression::And` with an empty `Vec<_>` is equivalent to
+///   constant `true`.
 /// - `Expression::Or` - a disjunction of the inner expressions. The inner expressions are
-/// stored inside a `Vec<_>`, to allow for an arbitrary number of disjuncts useful for
-/// creating DNF formulas. `Expression::Or` with an empty `Vec<_>` is equivalent to
-/// constant `false`.
+///   stored inside a `Vec<_>`, to allow for an arbitrary number of disjuncts useful for
+///   creating DNF formulas. `Expression::Or` with an empty `Vec<_>` is equivalent to
+///   constant `false`.
 /// - `Expression::Xor` - an exclusive disjunction of the inner expressions.
 /// - `Expression::Implies` - an implication of the inner expressions. The order of the
-/// operands follows conventional notation, i.e. `Expression::Implies(lhs, rhs)` is
-/// equivalent to `lhs => rhs`.
-#[derive(Debug)]
+///   operands follows conventional notation, i.e. `Expression::Implies(lhs, rhs)` is
+///   equivalent to `lhs => rhs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Expression<T> {
     Terminal(Proposition<T>),
+    VariableComparison(VariableComparison),
     Not(Box<Expression<T>>),
     And(Vec<Expression<T>>),
     Or(Vec<Expression<T>>),
     Xor(Box<Expression<T>>, Box<Expression<T>>),
     Implies(Box<Expression<T>>, Box<Expression<T>>),
 }
+
+impl<T> Expression<T> {
+    /// Returns the names of all the variables that appear in some proposition
+    /// (or some variable comparison) of this expression.
+    pub fn referenced_variables(&self) -> HashSet<&str> {
+        let mut acc = HashSet::new();
+        self.collect_referenced_variables(&mut acc);
+        acc
+    }
+
+    /// Returns all the propositions of this expression, in the order in which they appear.
+    pub fn propositions(&self) -> Vec<&Proposition<T>> {
+        let mut acc = Vec::new();
+        self.collect_propositions(&mut acc);
+        acc
+    }
+
+    /// Apply `f` to the value of every proposition of this expression, e.g. to convert
+    /// an `Expression<u8>` into an `Expression<u16>`, or to remap the levels of some variable.
+    /// The structure of the expression stays the same.
+    pub fn map_values<U>(self, f: impl Fn(T) -> U) -> Expression<U> {
+        self.map_values_with(&f)
+    }
+
+    fn map_values_with<U, F: Fn(T) -> U>(self, f: &F) -> Expression<U> {
+        match self {
+            Expression::Terminal(proposition) => Expression::Terminal(proposition.map_values(f)),
+            Expression::VariableComparison(comparison) => {
+                Expression::VariableComparison(comparison)
+            }
+            Expression::Not(inner) => Expression::Not(Box::new(inner.map_values_with(f))),
+            Expression::And(clauses) => Expression::And(
+                clauses
+                    .into_iter()
+                    .map(|clause| clause.map_values_with(f))
+                    .collect(),
+            ),
+            Expression::Or(clauses) => Expression::Or(
+                clauses
+                    .into_iter()
+                    .map(|clause| clause.map_values_with(f))
+                    .collect(),
+            ),
+            Expression::Xor(lhs, rhs) => Expression::Xor(
+                Box::new(lhs.map_values_with(f)),
+                Box::new(rhs.map_values_with(f)),
+            ),
+            Expression::Implies(lhs, rhs) => Expression::Implies(
+                Box::new(lhs.map_values_with(f)),
+                Box::new(rhs.map_values_with(f)),
+            ),
+        }
+    }
+
+    /// Merge the nested clauses of the same operator into a single clause vector
+    /// (e.g. `And([a, And([b, c])])` becomes `And([a, b, c])`) and replace every single-clause
+    /// `And`/`Or` with the clause itself. The result is logically equivalent.
+    pub fn flatten(self) -> Expression<T> {
+        match self {
+            Expression::Terminal(_) | Expression::VariableComparison(_) => self,
+            Expression::Not(inner) => Expression::Not(Box::new(inner.flatten())),
+            Expression::And(clauses) => Self::flatten_clauses(clauses, true),
+            Expression::Or(clauses) => Self::flatten_clauses(clauses, false),
+            Expression::Xor(lhs, rhs) => {
+                Expression::Xor(Box::new(lhs.flatten()), Box::new(rhs.flatten()))
+            }
+            Expression::Implies(lhs, rhs) => {
+                Expression::Implies(Box::new(lhs.flatten()), Box::new(rhs.flatten()))
+            }
+        }
+    }
+
+    /// Flatten the clauses of an `And` (if `is_and`) or an `Or` (otherwise).
+    fn flatten_clauses(clauses: Vec<Expression<T>>, is_and: bool) -> Expression<T> {
+        let mut flat = Vec::with_capacity(clauses.len());
+        for clause in clauses {
+            match clause.flatten() {
+                Expression::And(inner) if is_and => flat.extend(inner),
+                Expression::Or(inner) if !is_and => flat.extend(inner),
+                other => flat.push(other),
+            }
+        }
+
+        if flat.len() == 1 {
+            return flat.pop().expect("exactly one clause");
+        }
+        if is_and {
+            Expression::And(flat)
+        } else {
+            Expression::Or(flat)
+        }
+    }
+
+    /// Transform the expression into the negation normal form, i.e. an equivalent expression
+    /// that contains no `Not`, `Xor` or `Implies` nodes. Negations are pushed down to the leaves
+    /// using De Morgan's laws, where they are absorbed by negating the comparison operators
+    /// (e.g. `Not(x < 2)` becomes `x >= 2`).
+    pub fn to_nnf(self) -> Expression<T>
+    where
+        T: Clone,
+    {
+        match self {
+            Expression::Terminal(_) | Expression::VariableComparison(_) => self,
+            Expression::Not(inner) => inner.negated_nnf(),
+            Expression::And(clauses) => {
+                Expression::And(clauses.into_iter().map(Expression::to_nnf).collect())
+            }
+            Expression::Or(clauses) => {
+                Expression::Or(clauses.into_iter().map(Expression::to_nnf).collect())
+            }
+            Expression::Xor(lhs, rhs) => Expression::Or(vec![
+                Expression::And(vec![lhs.clone().to_nnf(), rhs.clone().negated_nnf()]),
+                Expression::And(vec![lhs.negated_nnf(), rhs.to_nnf()]),
+            ]),
+            Expression::Implies(lhs, rhs) => Expression::Or(vec![lhs.negated_nnf(), rhs.to_nnf()]),
+        }
+    }
+
+    /// The negation normal form of `Not(self)`.
+    fn negated_nnf(self) -> Expression<T>
+    where
+        T: Clone,
+    {
+        match self {
+            Expression::Terminal(proposition) => Expression::Terminal(Proposition {
+                comparison_operator: proposition.comparison_operator.negate(),
+                ..proposition
+            }),
+            Expression::VariableComparison(comparison) => {
+                Expression::VariableComparison(VariableComparison {
+                    comparison_operator: comparison.comparison_operator.negate(),
+                    ..comparison
+                })
+            }
+            Expression::Not(inner) => inner.to_nnf(),
+            Expression::And(clauses) => {
+                Expression::Or(clauses.into_iter().map(Expression::negated_nnf).collect())
+            }
+            Expression::Or(clauses) => {
+                Expression::And(clauses.into_iter().map(Expression::negated_nnf).collect())
+            }
+            Expression::Xor(lhs, rhs) => Expression::Or(vec![
+                Expression::And(vec![lhs.clone().to_nnf(), rhs.clone().to_nnf()]),
+                Expression::And(vec![lhs.negated_nnf(), rhs.negated_nnf()]),
+            ]),
+            Expression::Implies(lhs, rhs) => Expression::And(vec![lhs.to_nnf(), rhs.negated_nnf()]),
+        }
+    }
+
+    fn collect_propositions<'a>(&'a self, acc: &mut Vec<&'a Proposition<T>>) {
+        match self {
+            Expression::Terminal(proposition) => acc.push(proposition),
+            Expression::VariableComparison(_) => { /* no proposition */ }
+            Expression::Not(inner) => inner.collect_propositions(acc),
+            Expression::And(clauses) | Expression::Or(clauses) => clauses
+                .iter()
+                .for_each(|clause| clause.collect_propositions(acc)),
+            Expression::Xor(lhs, rhs) | Expression::Implies(lhs, rhs) => {
+                lhs.collect_propositions(acc);
+                rhs.collect_propositions(acc);
+            }
+        }
+    }
+
+    fn collect_referenced_variables<'a>(&'a self, acc: &mut HashSet<&'a str>) {
+        match self {
+            Expression::Terminal(proposition) => {
+                acc.insert(proposition.variable.as_str());
+            }
+            Expression::VariableComparison(comparison) => {
+                acc.insert(comparison.lhs.as_str());
+                acc.insert(comparison.rhs.as_str());
+            }
+            Expression::Not(inner) => inner.collect_referenced_variables(acc),
+            Expression::And(clauses) | Expression::Or(clauses) => clauses
+                .iter()
+                .for_each(|clause| clause.collect_referenced_variables(acc)),
+            Expression::Xor(lhs, rhs) | Expression::Implies(lhs, rhs) => {
+                lhs.collect_referenced_variables(acc);
+                rhs.collect_referenced_variables(acc);
+            }
+        }
+    }
+}
+
+/// Renders the expression using the usual logical symbols (`!`, `&`, `|`, `^`, `=>`), e.g.
+/// `A >= 1 & (B < 3 | !(C == 0))`. Nested compound expressions (and negated expressions) are
+/// enclosed in parentheses, the outermost one is not. Empty conjunctions (disjunctions) render
+/// as `true` (`false`).
+impl<T: Display> Display for Expression<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |f: &mut std::fmt::Formatter<'_>, operands: &[&Self], separator: &str| {
+            for (idx, operand) in operands.iter().enumerate() {
+                if idx > 0 {
+                    write!(f, " {} ", separator)?;
+                }
+                operand.fmt_nested(f)?;
+            }
+            Ok(())
+        };
+
+        match self {
+            Self::Terminal(proposition) => write!(f, "{}", proposition),
+            Self::VariableComparison(comparison) => write!(f, "{}", comparison),
+            Self::Not(inner) => write!(f, "!({})", inner),
+            Self::And(clauses) if clauses.is_empty() => write!(f, "true"),
+            Self::Or(clauses) if clauses.is_empty() => write!(f, "false"),
+            Self::And(clauses) => join(f, &clauses.iter().collect::<Vec<_>>(), "&"),
+            Self::Or(clauses) => join(f, &clauses.iter().collect::<Vec<_>>(), "|"),
+            Self::Xor(lhs, rhs) => join(f, &[lhs, rhs], "^"),
+            Self::Implies(lhs, rhs) => join(f, &[lhs, rhs], "=>"),
+        }
+    }
+}
+
+impl<T: Display> Expression<T> {
+    /// Like `Display::fmt`, but encloses compound expressions in parentheses.
+    fn fmt_nested(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Terminal(_) | Self::VariableComparison(_) | Self::Not(_) => write!(f, "{}", self),
+            Self::And(clauses) | Self::Or(clauses) if clauses.is_empty() => write!(f, "{}", self),
+            _ => write!(f, "({})", self),
+        }
+    }
+}
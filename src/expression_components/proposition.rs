@@ -1,8 +1,8 @@
 #![allow(dead_code)]
 
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ComparisonOperator {
     Eq,
     Neq,
@@ -23,35 +23,74 @@ impl ComparisonOperator {
             Self::Geq => Self::Leq,
         }
     }
+
+    /// The logical negation of the operator, i.e. `!(x op y)` iff `x op.negate() y`.
+    pub fn negate(&self) -> Self {
+        match self {
+            Self::Eq => Self::Neq,
+            Self::Neq => Self::Eq,
+            Self::Lt => Self::Geq,
+            Self::Gt => Self::Leq,
+            Self::Leq => Self::Gt,
+            Self::Geq => Self::Lt,
+        }
+    }
+}
+
+impl ComparisonOperator {
+    /// True iff `lhs comparison_operator rhs` holds, where `ordering` is the result of
+    /// comparing `lhs` with `rhs`.
+    pub fn holds_for(&self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering;
+        match self {
+            Self::Eq => ordering == Ordering::Equal,
+            Self::Neq => ordering != Ordering::Equal,
+            Self::Lt => ordering == Ordering::Less,
+            Self::Gt => ordering == Ordering::Greater,
+            Self::Leq => ordering != Ordering::Greater,
+            Self::Geq => ordering != Ordering::Less,
+        }
+    }
 }
 
+/// Accepts both the MathML names (`eq`, `neq`, ...) and the common symbolic spellings
+/// (`=`, `==`, `!=`, `<>`, `<`, `<=`, `>`, `>=`).
 impl FromStr for ComparisonOperator {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "eq" => Ok(Self::Eq),
-            "neq" => Ok(Self::Neq),
-            "lt" => Ok(Self::Lt),
-            "gt" => Ok(Self::Gt),
-            "leq" => Ok(Self::Leq),
-            "geq" => Ok(Self::Geq),
+            "eq" | "=" | "==" => Ok(Self::Eq),
+            "neq" | "!=" | "<>" => Ok(Self::Neq),
+            "lt" | "<" => Ok(Self::Lt),
+            "gt" | ">" => Ok(Self::Gt),
+            "leq" | "<=" => Ok(Self::Leq),
+            "geq" | ">=" => Ok(Self::Geq),
             _ => Err(()),
         }
     }
 }
 
-impl ToString for ComparisonOperator {
-    fn to_string(&self) -> String {
-        match self {
-            Self::Eq => "eq",
-            Self::Neq => "neq",
-            Self::Lt => "lt",
-            Self::Gt => "gt",
-            Self::Leq => "leq",
-            Self::Geq => "geq",
-        }
-        .to_string()
+/// The canonical form is the MathML name of the operator (as used in SBML), which can be
+/// parsed back using `FromStr`. The alternate form (`{:#}`) is the symbolic spelling
+/// (`==`, `!=`, `<`, `>`, `<=`, `>=`), which can be parsed back as well.
+impl Display for ComparisonOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let as_str = match (self, f.alternate()) {
+            (Self::Eq, false) => "eq",
+            (Self::Neq, false) => "neq",
+            (Self::Lt, false) => "lt",
+            (Self::Gt, false) => "gt",
+            (Self::Leq, false) => "leq",
+            (Self::Geq, false) => "geq",
+            (Self::Eq, true) => "==",
+            (Self::Neq, true) => "!=",
+            (Self::Lt, true) => "<",
+            (Self::Gt, true) => ">",
+            (Self::Leq, true) => "<=",
+            (Self::Geq, true) => ">=",
+        };
+        write!(f, "{}", as_str)
     }
 }
 
@@ -59,13 +98,24 @@ impl ToString for ComparisonOperator {
 ///
 /// This order is fixed. To represent a formula of form `value comparison_operator variable`,
 /// use `comparison_operator.flip()`.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Proposition<T> {
     pub comparison_operator: ComparisonOperator,
     pub variable: String,
     pub value: T,
 }
 
+/// Renders as `variable op value`, using the symbolic spelling of the operator (e.g. `A >= 1`).
+impl<T: Display> Display for Proposition<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {:#} {}",
+            self.variable, self.comparison_operator, self.value
+        )
+    }
+}
+
 impl<T> Proposition<T> {
     pub fn new(comparison_operator: ComparisonOperator, variable: String, value: T) -> Self {
         Self {
@@ -74,6 +124,47 @@ impl<T> Proposition<T> {
             value,
         }
     }
+
+    /// Apply `f` to the value of this proposition, keeping the operator and the variable.
+    pub fn map_values<U>(self, f: impl FnOnce(T) -> U) -> Proposition<U> {
+        Proposition {
+            comparison_operator: self.comparison_operator,
+            variable: self.variable,
+            value: f(self.value),
+        }
+    }
+}
+
+/// Represents a formula in the form of `lhs comparison_operator rhs`, where both `lhs` and `rhs`
+/// are variables (e.g. `A > B`).
+///
+/// To represent the same formula with the operands swapped, use `comparison_operator.flip()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableComparison {
+    pub comparison_operator: ComparisonOperator,
+    pub lhs: String,
+    pub rhs: String,
+}
+
+/// Renders as `lhs op rhs`, using the symbolic spelling of the operator (e.g. `A > B`).
+impl Display for VariableComparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {:#} {}",
+            self.lhs, self.comparison_operator, self.rhs
+        )
+    }
+}
+
+impl VariableComparison {
+    pub fn new(comparison_operator: ComparisonOperator, lhs: String, rhs: String) -> Self {
+        Self {
+            comparison_operator,
+            lhs,
+            rhs,
+        }
+    }
 }
 
 pub struct Person {
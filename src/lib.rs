@@ -5,6 +5,8 @@ pub mod test_utils; // TODO:
                     //   to have it accessible from outside binaries.
 mod expression_components;
 mod symbolic_domains;
+pub mod testing;
 mod update;
 mod utils;
+pub mod validation;
 mod xml_parsing;
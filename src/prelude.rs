@@ -1,4 +1,17 @@
+pub use crate::expression_components::{expression, proposition};
+pub use crate::symbolic_domains::domain_builder;
 pub use crate::symbolic_domains::symbolic_domain;
+#[cfg(feature = "lib-param-bn")]
+pub use crate::update::boolean_network;
+pub use crate::update::reachability;
+pub use crate::update::restricted_system;
+pub use crate::update::transition_system;
+pub use crate::update::unprocessed_variable_update_function;
 pub use crate::update::update_fn;
+pub use crate::update::update_fn_dsl;
 
-pub use crate::xml_parsing::utils::find_start_of;
+pub use crate::xml_parsing::utils::{find_start_of, XmlReadingError};
+pub use crate::xml_parsing::variable_update_fn_parser::{
+    iter_transitions, load_all_update_fns, load_all_update_fns_with_inputs, InputUpdateFn,
+    LoadedUpdateFns,
+};
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use biodivine_lib_bdd::{Bdd, BddVariable, BddVariableSet, BddVariableSetBuilder};
+
+use crate::{
+    symbolic_domains::symbolic_domain::SymbolicDomainOrd, update::update_fn::translate_bdd,
+};
+
+/// Allocates the domains of many variables in a single [BddVariableSetBuilder], computing
+/// the `unit_collection` of each distinct max value only once.
+///
+/// The unit collection of a domain only depends on its max value (and the encoding), up to
+/// the [BddVariable]-s it is built over. The builder thus keeps a template of the collection
+/// for each max value, built over a context holding only that domain, and translates it to
+/// the variables of each domain with that max value (e.g. all the Boolean variables).
+///
+/// Note that the gains are small, since compiling the transition relations dominates
+/// the construction: for the 75 variables of `data/large/InVitro.free-inputs.sbml`, computing
+/// all the unit collections takes `0.6-1.2 ms` (depending on the encoding) out of `70-130 ms`
+/// of [SmartSystemUpdateFn::from_update_fns] (release build). Neither on this model nor on
+/// `Metabolism_demo.free-inputs.sbml` is the difference measurable above the run-to-run noise.
+///
+/// [SmartSystemUpdateFn::from_update_fns]: crate::update::update_fn::SmartSystemUpdateFn::from_update_fns
+pub struct DomainBuilder<D, T> {
+    builder: BddVariableSetBuilder,
+    /// a domain of each distinct max value (allocated in a context of its own) together with
+    /// its unit collection in that context
+    templates: Vec<(D, Bdd)>,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<D, T> Default for DomainBuilder<D, T>
+where
+    D: SymbolicDomainOrd<T>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D, T> DomainBuilder<D, T>
+where
+    D: SymbolicDomainOrd<T>,
+{
+    pub fn new() -> Self {
+        Self {
+            builder: BddVariableSetBuilder::new(),
+            templates: Vec::new(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Allocates a new domain (see [SymbolicDomainOrd::new]); the unit collection of its
+    /// `max_value` is computed if this is the first domain with this max value.
+    pub fn make_domain(&mut self, name: &str, max_value: &T) -> D {
+        if self.find_template(max_value).is_none() {
+            let mut template_builder = BddVariableSetBuilder::new();
+            let template = D::new(&mut template_builder, name, max_value);
+            let unit_collection = template.unit_collection(&template_builder.build());
+            self.templates.push((template, unit_collection));
+        }
+        D::new(&mut self.builder, name, max_value)
+    }
+
+    /// The variable set containing all the domains made so far.
+    pub fn build(&self) -> BddVariableSet {
+        self.builder.clone().build()
+    }
+
+    /// The same as `domain.unit_collection(bdd_variable_set)`, but translated from
+    /// the template of the max value of the `domain`, if there is one (i.e. if some domain
+    /// with this max value was made by this builder).
+    pub fn unit_collection(&self, bdd_variable_set: &BddVariableSet, domain: &D) -> Bdd {
+        match self.find_template(&domain.max_value()) {
+            Some((template, unit_collection)) => {
+                let translation = template
+                    .raw_bdd_variables()
+                    .into_iter()
+                    .zip(domain.raw_bdd_variables())
+                    .collect::<HashMap<BddVariable, BddVariable>>();
+                translate_bdd(unit_collection, &translation, bdd_variable_set.num_vars())
+            }
+            None => domain.unit_collection(bdd_variable_set),
+        }
+    }
+
+    fn find_template(&self, max_value: &T) -> Option<&(D, Bdd)> {
+        self.templates.iter().find(|(template, _)| {
+            D::cmp(&template.max_value(), max_value) == std::cmp::Ordering::Equal
+        })
+    }
+}
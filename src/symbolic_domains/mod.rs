@@ -1 +1,2 @@
+pub mod domain_builder;
 pub mod symbolic_domain;
@@ -1,24 +1,49 @@
-use std::{cell::OnceCell, collections::HashSet};
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    str::FromStr,
+    sync::{Arc, OnceLock},
+};
 
 use biodivine_lib_bdd::{
     Bdd, BddPartialValuation, BddVariable, BddVariableSet, BddVariableSetBuilder,
 };
+use debug_ignore::DebugIgnore;
+use thiserror::Error;
+
+/// Returned when a value that does not belong to a domain is being encoded into it.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("value {value:?} is out of the domain encoded by {variables:?} (max value: {max_value:?})")]
+pub struct ValueOutOfDomain<T> {
+    /// the variables encoding the domain, see [SymbolicDomain::raw_bdd_variables]
+    pub variables: Vec<BddVariable>,
+    pub value: T,
+    pub max_value: T,
+}
 
+/// A set of values of type `T` encoded using a fixed collection of `BddVariable`s.
+///
+/// This is the canonical domain trait of the crate (used by
+/// [SmartSystemUpdateFn](crate::update::update_fn::SmartSystemUpdateFn)). The variables of
+/// the encoding are exposed as [SymbolicDomain::raw_bdd_variables]; the names used by the
+/// older prototype domains (`symbolic_variables` and `symbolic_size`) are only kept as
+/// deprecated aliases.
 pub trait SymbolicDomain<T> {
     /// Encode the given `value` into the provided `BddPartialValuation`.
     ///
     /// *Contract:* This method only modifies the symbolic variables from
-    /// `Self::symbolic_variables`. No other parts of the `BddPartialValuation` are affected.
+    /// `Self::raw_bdd_variables`. No other parts of the `BddPartialValuation` are affected.
     ///
     /// # Panics
     ///
-    /// If and only if the value is not in the domain.
+    /// If and only if the value is not in the domain. See [SymbolicDomainOrd::try_encode_bits]
+    /// for a non-panicking alternative.
     fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &T);
 
     /// Encode a single `value` into a `Bdd` which is satisfied for exactly this value
     /// and no other.
     ///
-    /// *Contract:* The resulting BDD only uses variables from `Self::symbolic_variables`.
+    /// *Contract:* The resulting BDD only uses variables from `Self::raw_bdd_variables`.
     fn encode_one(&self, variables: &BddVariableSet, value: &T) -> Bdd {
         let mut valuation = BddPartialValuation::empty();
         self.encode_bits(&mut valuation, value);
@@ -34,6 +59,44 @@ pub trait SymbolicDomain<T> {
     fn empty_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd;
     fn unit_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd;
 
+    /// Remember the `BddVariableSet` this domain is encoded in, so that the `*_in_context`
+    /// methods, which do not take it as an argument, can be used.
+    ///
+    /// The `variables` must be built from the builder that was used to create this domain
+    /// (see `SymbolicDomainOrd::new`); systems built from update functions set this for all
+    /// their domains.
+    fn set_context(&mut self, variables: Arc<BddVariableSet>);
+
+    /// The `BddVariableSet` given to `Self::set_context`, if any.
+    fn context(&self) -> Option<&BddVariableSet>;
+
+    /// Like `Self::unit_collection`, but in the `Self::context` of the domain.
+    ///
+    /// # Panics
+    ///
+    /// If the context was not set.
+    fn unit_collection_in_context(&self) -> Bdd {
+        self.unit_collection(expect_context(self.context()))
+    }
+
+    /// Like `Self::empty_collection`, but in the `Self::context` of the domain.
+    ///
+    /// # Panics
+    ///
+    /// If the context was not set.
+    fn empty_collection_in_context(&self) -> Bdd {
+        self.empty_collection(expect_context(self.context()))
+    }
+
+    /// Like `Self::encode_one`, but in the `Self::context` of the domain.
+    ///
+    /// # Panics
+    ///
+    /// If the context was not set (or the value is not in the domain).
+    fn encode_one_in_context(&self, value: &T) -> Bdd {
+        self.encode_one(expect_context(self.context()), value)
+    }
+
     /// Like `encode_bits`, but for inspecting how the bits are encoded.
     ///
     /// The order of the bits is the same as the order of the variables in
@@ -80,9 +143,20 @@ pub trait SymbolicDomain<T> {
     }
     fn raw_bdd_variables_unsorted(&self) -> Vec<BddVariable>;
 
+    #[deprecated(note = "use `raw_bdd_variables` instead")]
+    fn symbolic_variables(&self) -> Vec<BddVariable> {
+        self.raw_bdd_variables()
+    }
+
+    /// The number of `BddVariable`s used to encode this domain.
+    #[deprecated(note = "use `raw_bdd_variables().len()` instead")]
+    fn symbolic_size(&self) -> usize {
+        self.raw_bdd_variables_unsorted().len()
+    }
+
     /// Decode a value from the provided `BddPartialValuation`.
     ///
-    /// *Contract:* This method only reads the symbolic variables from `Self::symbolic_variables`.
+    /// *Contract:* This method only reads the symbolic variables from `Self::raw_bdd_variables`.
     /// The result is undefined if `bdd_valuation` does not represent a value that is valid in
     /// the encoding implemented by this `SymbolicDomain` (i.e. if the valuation is not valid
     /// within the `Self::unit_collection` BDD object). In particular, the method can return
@@ -105,16 +179,7 @@ pub trait SymbolicDomain<T> {
         // might not even matter.
 
         let encoding_variables = self.raw_bdd_variables();
-        let mut ignored_variables: HashSet<BddVariable> =
-            variables.variables().into_iter().collect();
-        ignored_variables.retain(|x| !encoding_variables.contains(x));
-        let ignored_variables: Vec<BddVariable> = ignored_variables.into_iter().collect();
-        let collection = collection.exists(&ignored_variables);
-        let fixed_selection = ignored_variables
-            .into_iter()
-            .map(|it| (it, false))
-            .collect::<Vec<_>>();
-        let collection = collection.select(&fixed_selection);
+        let collection = project_onto_encoding(variables, &encoding_variables, collection);
 
         let mut encoded_bits = BddPartialValuation::empty();
         collection
@@ -127,14 +192,99 @@ pub trait SymbolicDomain<T> {
             })
             .collect()
     }
+
+    /// Decode the single value stored in a `Bdd`.
+    ///
+    /// Only the variables of this domain are considered, and only valuations valid within
+    /// `Self::unit_collection` are counted as values. See `Self::decode_one` for a panicking
+    /// alternative.
+    fn try_decode_one(&self, variables: &BddVariableSet, value: &Bdd) -> Result<T, DecodeOneError> {
+        let encoding_variables = self.raw_bdd_variables();
+        let valid = value.and(&self.unit_collection(variables));
+        let projected = project_onto_encoding(variables, &encoding_variables, &valid);
+
+        // all the non-encoding variables are fixed to `false`, so each satisfying
+        // valuation corresponds to exactly one encoded value
+        if projected.is_false() {
+            return Err(DecodeOneError::Empty);
+        }
+        let cardinality = projected.cardinality();
+        if cardinality != 1.0 {
+            return Err(DecodeOneError::NotSingleValue { cardinality });
+        }
+
+        let witness = projected
+            .sat_witness()
+            .expect("the projected bdd has exactly one satisfying valuation");
+        let mut encoded_bits = BddPartialValuation::empty();
+        for bit in &encoding_variables {
+            encoded_bits.set_value(*bit, witness.value(*bit))
+        }
+        Ok(self.decode_bits(&encoded_bits))
+    }
+
+    /// Like `try_decode_one`, but panics if the `Bdd` does not encode exactly one value.
+    fn decode_one(&self, variables: &BddVariableSet, value: &Bdd) -> T {
+        self.try_decode_one(variables, value)
+            .unwrap_or_else(|err| panic!("cannot decode a single value: {}", err))
+    }
+}
+
+/// Returned when a `Bdd` that should hold a single value of a domain holds none or several.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DecodeOneError {
+    #[error("the bdd does not encode any value of the domain")]
+    Empty,
+    #[error("the bdd encodes {cardinality} values of the domain instead of one")]
+    NotSingleValue { cardinality: f64 },
+}
+
+fn expect_context(context: Option<&BddVariableSet>) -> &BddVariableSet {
+    context.expect("the context of the domain is not set; see `SymbolicDomain::set_context`")
+}
+
+/// Eliminates all non-encoding variables from the `collection` BDD and replaces them with
+/// a value `false`, so that each satisfying valuation of the result corresponds to exactly
+/// one valuation of the `encoding_variables`.
+pub(crate) fn project_onto_encoding(
+    variables: &BddVariableSet,
+    encoding_variables: &[BddVariable],
+    collection: &Bdd,
+) -> Bdd {
+    debug_assert!(
+        collection.support_set().into_iter().all(|var| {
+            encoding_variables.contains(&var) || var.to_index() < usize::from(variables.num_vars())
+        }),
+        "the decoded collection depends on variables unknown to the given `BddVariableSet`; \
+        is it a BDD of a different system?"
+    );
+    let mut ignored_variables: HashSet<BddVariable> = variables.variables().into_iter().collect();
+    ignored_variables.retain(|x| !encoding_variables.contains(x));
+    let ignored_variables: Vec<BddVariable> = ignored_variables.into_iter().collect();
+    let collection = collection.exists(&ignored_variables);
+    let fixed_selection = ignored_variables
+        .into_iter()
+        .map(|it| (it, false))
+        .collect::<Vec<_>>();
+    collection.select(&fixed_selection)
 }
 
 pub trait SymbolicDomainOrd<T>: SymbolicDomain<T> {
     fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &T) -> Self;
+    /// The number of `BddVariable`s that `Self::new` creates for a domain with the given
+    /// `max_value`; allows estimating the size of the encoding without building it.
+    fn required_bits(max_value: &T) -> usize;
     /// Encodes the set of values that are strictly less than the given value.
     fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &T) -> Bdd;
     /// Encodes the set of values that are less than or equal to the given value.
+    ///
+    /// The `value` does not need to belong to the domain; for a value above
+    /// [SymbolicDomainOrd::max_value], this is the whole unit collection (and the same holds
+    /// for the other comparisons, e.g. [SymbolicDomainOrd::encode_ge] is then empty).
     fn encode_le(&self, bdd_variable_set: &BddVariableSet, value: &T) -> Bdd {
+        if Self::cmp(value, &self.max_value()) == std::cmp::Ordering::Greater {
+            return self.unit_collection(bdd_variable_set);
+        }
         self.encode_lt(bdd_variable_set, value)
             .or(&self.encode_one(bdd_variable_set, value))
     }
@@ -147,13 +297,121 @@ pub trait SymbolicDomainOrd<T>: SymbolicDomain<T> {
     }
     /// Encodes the set of values that are greater than or equal to the given value.
     fn encode_ge(&self, bdd_variable_set: &BddVariableSet, value: &T) -> Bdd {
+        if Self::cmp(value, &self.max_value()) == std::cmp::Ordering::Greater {
+            return self.empty_collection(bdd_variable_set);
+        }
         self.encode_gt(bdd_variable_set, value)
             .or(&self.encode_one(bdd_variable_set, value))
     }
 
     fn cmp(lhs: &T, rhs: &T) -> std::cmp::Ordering;
 
+    /// Like [SymbolicDomain::encode_bits], but returns an error instead of panicking when
+    /// the `value` is not in the domain. In such case, the `BddPartialValuation` is left
+    /// untouched.
+    ///
+    /// Out-of-domain values are always rejected, since silently truncating/saturating them
+    /// would corrupt the encoded sets. The default implementation looks the `value` up among
+    /// [SymbolicDomainOrd::get_all_possible_values]; the domains of this crate override it
+    /// with a direct check.
+    fn try_encode_bits(
+        &self,
+        bdd_valuation: &mut BddPartialValuation,
+        value: &T,
+    ) -> Result<(), ValueOutOfDomain<T>>
+    where
+        T: Clone,
+    {
+        let is_in_domain = self
+            .get_all_possible_values()
+            .iter()
+            .any(|it| Self::cmp(it, value) == std::cmp::Ordering::Equal);
+        if !is_in_domain {
+            return Err(ValueOutOfDomain {
+                variables: self.raw_bdd_variables(),
+                value: value.clone(),
+                max_value: self.max_value(),
+            });
+        }
+
+        self.encode_bits(bdd_valuation, value);
+        Ok(())
+    }
+
+    /// Like [SymbolicDomain::decode_collection], but the values are sorted in ascending order
+    /// (according to [SymbolicDomainOrd::cmp]) instead of following the encoding.
+    fn decode_collection_sorted(&self, variables: &BddVariableSet, collection: &Bdd) -> Vec<T> {
+        let mut values = self.decode_collection(variables, collection);
+        values.sort_by(Self::cmp);
+        values
+    }
+
     fn get_all_possible_values(&self) -> Vec<T>;
+
+    /// The greatest value that can be encoded in this domain, i.e. the `max_value` the domain
+    /// was created with (see [SymbolicDomainOrd::new]).
+    fn max_value(&self) -> T;
+
+    /// All the values of an integer domain, i.e. `0..=max_value`, in ascending order.
+    /// Unlike decoding the unit collection, this does not touch the BDDs at all.
+    fn all_values(&self) -> Vec<T>
+    where
+        T: Copy + PartialOrd + From<u8> + std::ops::Add<Output = T>,
+    {
+        let max_value = self.max_value();
+        let mut values = Vec::new();
+        let mut value = T::from(0);
+        while value <= max_value {
+            values.push(value);
+            if value == max_value {
+                break; // the maximum might be the greatest representable value
+            }
+            value = value + T::from(1);
+        }
+        values
+    }
+}
+
+/// The integer encoding used by a symbolic domain, available at runtime (see
+/// [DomainEncodingKind::encoding_kind]), e.g. to record the encoding next to a serialized
+/// result or to mention it in logs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EncodingKind {
+    Unary,
+    Binary,
+    GrayCode,
+    PetriNet,
+}
+
+impl Display for EncodingKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            EncodingKind::Unary => "unary",
+            EncodingKind::Binary => "binary",
+            EncodingKind::GrayCode => "gray",
+            EncodingKind::PetriNet => "petri_net",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for EncodingKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "unary" => Ok(EncodingKind::Unary),
+            "binary" => Ok(EncodingKind::Binary),
+            "gray" | "grey" => Ok(EncodingKind::GrayCode),
+            "petri_net" => Ok(EncodingKind::PetriNet),
+            _ => Err(format!("unknown encoding kind: {s}")),
+        }
+    }
+}
+
+/// Domains whose encoding is known at the type level; exposes that encoding as a runtime tag.
+pub trait DomainEncodingKind {
+    fn encoding_kind() -> EncodingKind;
 }
 
 /// Implementation of a `SymbolicDomain` using unary integer encoding, i.e. each integer domain
@@ -165,31 +423,15 @@ pub trait SymbolicDomainOrd<T>: SymbolicDomain<T> {
 pub struct UnaryIntegerDomain {
     /// invariant: sorted
     variables: Vec<BddVariable>, // todo maybe Rc<[BddVariable]>
-    unit_collection_cell: OnceCell<Bdd>,
+    unit_collection_cell: OnceLock<Bdd>,
+    context: DebugIgnore<Option<Arc<BddVariableSet>>>,
 }
 
 // implementation author: Samuel Pastva
 impl SymbolicDomain<u8> for UnaryIntegerDomain {
     fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &u8) {
-        if value > &(self.variables.len() as u8) {
-            let vars = self
-                .variables
-                .iter()
-                .map(|var| format!("{:?}", var))
-                .collect::<Vec<_>>()
-                .join(", ");
-
-            panic!(
-                "Value is too big for domain {}; value: {}, domain size: {}",
-                vars,
-                value,
-                self.variables.len()
-            )
-        }
-
-        self.variables.iter().enumerate().for_each(|(i, var)| {
-            bdd_valuation.set_value(*var, i < (*value as usize));
-        });
+        self.try_encode_bits(bdd_valuation, value)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 
     fn empty_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
@@ -218,6 +460,14 @@ impl SymbolicDomain<u8> for UnaryIntegerDomain {
         self.variables.clone() // already sorted
     }
 
+    fn set_context(&mut self, variables: Arc<BddVariableSet>) {
+        self.context = Some(variables).into();
+    }
+
+    fn context(&self) -> Option<&BddVariableSet> {
+        self.context.as_deref()
+    }
+
     fn raw_bdd_variables_unsorted(&self) -> Vec<BddVariable> {
         self.raw_bdd_variables() // already the optimal performance
     }
@@ -242,7 +492,7 @@ impl SymbolicDomain<u8> for UnaryIntegerDomain {
 
 impl SymbolicDomainOrd<u8> for UnaryIntegerDomain {
     fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &u8) -> Self {
-        let variables = (0..*max_value)
+        let variables = (0..Self::required_bits(max_value))
             .map(|var_idx| {
                 let name = format!("{name}_v{}", var_idx + 1);
                 builder.make_variable(name.as_str())
@@ -251,11 +501,45 @@ impl SymbolicDomainOrd<u8> for UnaryIntegerDomain {
 
         Self {
             variables,
-            unit_collection_cell: OnceCell::new(),
+            unit_collection_cell: OnceLock::new(),
+            context: None.into(),
         }
     }
 
+    fn try_encode_bits(
+        &self,
+        bdd_valuation: &mut BddPartialValuation,
+        value: &u8,
+    ) -> Result<(), ValueOutOfDomain<u8>> {
+        let max_value = self.variables.len() as u8;
+        if value > &max_value {
+            return Err(ValueOutOfDomain {
+                variables: self.variables.clone(),
+                value: *value,
+                max_value,
+            });
+        }
+
+        self.variables.iter().enumerate().for_each(|(i, var)| {
+            bdd_valuation.set_value(*var, i < (*value as usize));
+        });
+
+        Ok(())
+    }
+
+    fn required_bits(max_value: &u8) -> usize {
+        *max_value as usize
+    }
+
     fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &u8) -> Bdd {
+        if *exclusive_upper_bound == 0 {
+            return self.empty_collection(bdd_variable_set);
+        }
+        if *exclusive_upper_bound as usize > self.variables.len() {
+            // every value of the domain (including the only value of a zero-bit domain)
+            return self.unit_collection(bdd_variable_set);
+        }
+
         // forbid values greater than or equal to the upper bound by forbidding upper_bound_bit
         let not_upper_bound_bit =
             bdd_variable_set.mk_not_var(self.variables[(*exclusive_upper_bound - 1) as usize]);
@@ -271,38 +555,39 @@ impl SymbolicDomainOrd<u8> for UnaryIntegerDomain {
     fn get_all_possible_values(&self) -> Vec<u8> {
         (0..=self.variables.len() as u8).collect() // notice the inclusive range; n values is represented by n-1 bdd variables
     }
+
+    fn max_value(&self) -> u8 {
+        self.variables.len() as u8
+    }
 }
 
-#[derive(Debug)]
+impl DomainEncodingKind for UnaryIntegerDomain {
+    fn encoding_kind() -> EncodingKind {
+        EncodingKind::Unary
+    }
+}
+
+/// Implementation of a `SymbolicDomain` using the "Petri net" (one-hot) integer encoding, i.e.
+/// each integer domain `D = { 0 ... max }` is encoded using `max + 1` symbolic variables.
+///
+/// In this encoding, to represent value `k \in D`, we set the `k`-th symbolic variable
+/// to `true` and all the remaining ones to `false` (hence the name: a token is present in
+/// exactly one of the places representing the individual levels). Consequently, the unit
+/// collection consists of the valuations with exactly one `true` variable.
+///
+/// Within the unit collection, `value < k` holds if and only if all the variables
+/// `k ... max` are `false`, which is how the comparisons are encoded.
+#[derive(Clone, Debug)]
 pub struct PetriNetIntegerDomain {
+    context: DebugIgnore<Option<Arc<BddVariableSet>>>,
     /// invariant: sorted
     variables: Vec<BddVariable>,
 }
 
 impl SymbolicDomain<u8> for PetriNetIntegerDomain {
     fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &u8) {
-        if value > &(self.variables.len() as u8) {
-            let vars = self
-                .variables
-                .iter()
-                .map(|var| format!("{:?}", var))
-                .collect::<Vec<_>>()
-                .join(", ");
-
-            panic!(
-                "Value is too big for domain {}; value: {}, domain size: {}",
-                vars,
-                value,
-                self.variables.len()
-            )
-        }
-
-        self.variables
-            .iter()
-            .enumerate()
-            .for_each(|(var_idx_within_sym_var, var)| {
-                bdd_valuation.set_value(*var, var_idx_within_sym_var == (*value as usize));
-            });
+        self.try_encode_bits(bdd_valuation, value)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 
     fn empty_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
@@ -317,6 +602,14 @@ impl SymbolicDomain<u8> for PetriNetIntegerDomain {
         self.variables.clone() // already sorted
     }
 
+    fn set_context(&mut self, variables: Arc<BddVariableSet>) {
+        self.context = Some(variables).into();
+    }
+
+    fn context(&self) -> Option<&BddVariableSet> {
+        self.context.as_deref()
+    }
+
     fn raw_bdd_variables_unsorted(&self) -> Vec<BddVariable> {
         self.raw_bdd_variables() // already the optimal performance
     }
@@ -341,20 +634,62 @@ impl SymbolicDomain<u8> for PetriNetIntegerDomain {
 
 impl SymbolicDomainOrd<u8> for PetriNetIntegerDomain {
     fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &u8) -> Self {
-        let variables = (0..=*max_value) // notice the inclusive range
+        let variables = (0..Self::required_bits(max_value))
             .map(|var_idx| {
                 let name = format!("{name}_v{}", var_idx + 1);
                 builder.make_variable(name.as_str())
             })
             .collect();
 
-        Self { variables }
+        Self {
+            context: None.into(),
+            variables,
+        }
+    }
+
+    fn try_encode_bits(
+        &self,
+        bdd_valuation: &mut BddPartialValuation,
+        value: &u8,
+    ) -> Result<(), ValueOutOfDomain<u8>> {
+        // n values are represented by n bdd variables
+        let max_value = (self.variables.len() - 1) as u8;
+        if value > &max_value {
+            return Err(ValueOutOfDomain {
+                variables: self.variables.clone(),
+                value: *value,
+                max_value,
+            });
+        }
+
+        self.variables
+            .iter()
+            .enumerate()
+            .for_each(|(var_idx_within_sym_var, var)| {
+                bdd_valuation.set_value(*var, var_idx_within_sym_var == (*value as usize));
+            });
+
+        Ok(())
+    }
+
+    fn required_bits(max_value: &u8) -> usize {
+        *max_value as usize + 1 // n values are represented by n bdd variables
     }
 
     fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &u8) -> Bdd {
-        (0..*exclusive_upper_bound).fold(self.empty_collection(bdd_variable_set), |acc, val| {
-            acc.or(&self.encode_one(bdd_variable_set, &val))
-        })
+        self.all_false_from(bdd_variable_set, *exclusive_upper_bound as usize)
+    }
+
+    fn encode_le(&self, bdd_variable_set: &BddVariableSet, value: &u8) -> Bdd {
+        self.all_false_from(bdd_variable_set, *value as usize + 1)
+    }
+
+    fn encode_gt(&self, bdd_variable_set: &BddVariableSet, value: &u8) -> Bdd {
+        self.all_false_until(bdd_variable_set, *value as usize + 1)
+    }
+
+    fn encode_ge(&self, bdd_variable_set: &BddVariableSet, value: &u8) -> Bdd {
+        self.all_false_until(bdd_variable_set, *value as usize)
     }
 
     fn cmp(lhs: &u8, rhs: &u8) -> std::cmp::Ordering {
@@ -364,37 +699,60 @@ impl SymbolicDomainOrd<u8> for PetriNetIntegerDomain {
     fn get_all_possible_values(&self) -> Vec<u8> {
         (0..self.variables.len() as u8).collect() // notice the exclusive range; n values is represented by n bdd variables
     }
+
+    fn max_value(&self) -> u8 {
+        (self.variables.len() - 1) as u8
+    }
+}
+
+impl DomainEncodingKind for PetriNetIntegerDomain {
+    fn encoding_kind() -> EncodingKind {
+        EncodingKind::PetriNet
+    }
 }
 
-#[derive(Debug)]
+impl PetriNetIntegerDomain {
+    /// Valid values whose variables at positions `from..` are all `false`,
+    /// i.e. values less than `from`.
+    fn all_false_from(&self, bdd_variable_set: &BddVariableSet, from: usize) -> Bdd {
+        let from = from.min(self.variables.len());
+        self.all_false(bdd_variable_set, &self.variables[from..])
+    }
+
+    /// Valid values whose variables at positions `..until` are all `false`,
+    /// i.e. values greater than or equal to `until`.
+    fn all_false_until(&self, bdd_variable_set: &BddVariableSet, until: usize) -> Bdd {
+        let until = until.min(self.variables.len());
+        self.all_false(bdd_variable_set, &self.variables[..until])
+    }
+
+    fn all_false(&self, bdd_variable_set: &BddVariableSet, variables: &[BddVariable]) -> Bdd {
+        let valuation = BddPartialValuation::from_values(
+            &variables
+                .iter()
+                .map(|var| (*var, false))
+                .collect::<Vec<_>>(),
+        );
+        bdd_variable_set
+            .mk_conjunctive_clause(&valuation)
+            .and(&self.unit_collection(bdd_variable_set))
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct BinaryIntegerDomain<T> {
     /// invariant: sorted
     variables: Vec<BddVariable>,
     /// in older implementations, this used to be the `max_value`
     /// since we no longer require ordering, no `max_value` -> Bdd of all the possible values
     max_value: T, // todo mb, cannot implemnent BinaryIntegerDomain generically -> it must be SymbolicDomainOrd
+    context: DebugIgnore<Option<Arc<BddVariableSet>>>,
 }
 
 impl SymbolicDomain<u8> for BinaryIntegerDomain<u8> {
     fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &u8) {
-        if value > &(self.max_value) {
-            // this breaks the idea of SymbolicDomain being not bound to the ordering
-            let vars = self
-                .variables
-                .iter()
-                .map(|var| format!("{:?}", var))
-                .collect::<Vec<_>>()
-                .join(", ");
-
-            panic!(
-                "Value is too big for domain {}; value: {}, domain size: {}",
-                vars, value, self.max_value
-            )
-        }
-
-        self.variables.iter().enumerate().for_each(|(idx, var)| {
-            bdd_valuation.set_value(*var, (value & (1 << idx)) != 0);
-        })
+        self.try_encode_bits(bdd_valuation, value)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 
     fn empty_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
@@ -411,6 +769,14 @@ impl SymbolicDomain<u8> for BinaryIntegerDomain<u8> {
         self.variables.clone() // already sorted
     }
 
+    fn set_context(&mut self, variables: Arc<BddVariableSet>) {
+        self.context = Some(variables).into();
+    }
+
+    fn context(&self) -> Option<&BddVariableSet> {
+        self.context.as_deref()
+    }
+
     fn raw_bdd_variables_unsorted(&self) -> Vec<BddVariable> {
         self.raw_bdd_variables() // already the optimal performance
     }
@@ -443,9 +809,7 @@ impl SymbolicDomain<u8> for BinaryIntegerDomain<u8> {
 
 impl SymbolicDomainOrd<u8> for BinaryIntegerDomain<u8> {
     fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &u8) -> Self {
-        let bit_count = 8 - max_value.leading_zeros();
-
-        let variables = (0..bit_count)
+        let variables = (0..Self::required_bits(max_value))
             .map(|it| {
                 let name = format!("{name}_v{}", it + 1);
                 builder.make_variable(name.as_str())
@@ -455,13 +819,41 @@ impl SymbolicDomainOrd<u8> for BinaryIntegerDomain<u8> {
         Self {
             variables,
             max_value: *max_value,
+            context: None.into(),
         }
     }
 
+    fn try_encode_bits(
+        &self,
+        bdd_valuation: &mut BddPartialValuation,
+        value: &u8,
+    ) -> Result<(), ValueOutOfDomain<u8>> {
+        if value > &(self.max_value) {
+            // this breaks the idea of SymbolicDomain being not bound to the ordering
+            return Err(ValueOutOfDomain {
+                variables: self.variables.clone(),
+                value: *value,
+                max_value: self.max_value,
+            });
+        }
+
+        self.variables.iter().enumerate().for_each(|(idx, var)| {
+            bdd_valuation.set_value(*var, (value & (1 << idx)) != 0);
+        });
+
+        Ok(())
+    }
+
+    fn required_bits(max_value: &u8) -> usize {
+        (8 - max_value.leading_zeros()) as usize
+    }
+
     fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &u8) -> Bdd {
-        (0..*exclusive_upper_bound).fold(self.empty_collection(bdd_variable_set), |acc, val| {
-            acc.or(&self.encode_one(bdd_variable_set, &val))
-        })
+        (0..=self.max_value)
+            .take_while(|val| val < exclusive_upper_bound)
+            .fold(self.empty_collection(bdd_variable_set), |acc, val| {
+                acc.or(&self.encode_one(bdd_variable_set, &val))
+            })
     }
 
     fn cmp(lhs: &u8, rhs: &u8) -> std::cmp::Ordering {
@@ -471,15 +863,26 @@ impl SymbolicDomainOrd<u8> for BinaryIntegerDomain<u8> {
     fn get_all_possible_values(&self) -> Vec<u8> {
         (0..=self.max_value).collect()
     }
+
+    fn max_value(&self) -> u8 {
+        self.max_value
+    }
+}
+
+impl DomainEncodingKind for BinaryIntegerDomain<u8> {
+    fn encoding_kind() -> EncodingKind {
+        EncodingKind::Binary
+    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct GrayCodeIntegerDomain<T> {
     /// invariant: sorted
     variables: Vec<BddVariable>,
     /// in older implementations, this used to be the `max_value`
     /// since we no longer require ordering, no `max_value` -> Bdd of all the possible values
     max_value: T, // todo same as in the case of BinaryIntegerDomain
+    context: DebugIgnore<Option<Arc<BddVariableSet>>>,
 }
 
 impl GrayCodeIntegerDomain<u8> {
@@ -502,25 +905,8 @@ impl GrayCodeIntegerDomain<u8> {
 
 impl SymbolicDomain<u8> for GrayCodeIntegerDomain<u8> {
     fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &u8) {
-        if value > &(self.max_value) {
-            // this breaks the idea of SymbolicDomain being not bound to the ordering
-            let vars = self
-                .variables
-                .iter()
-                .map(|var| format!("{:?}", var))
-                .collect::<Vec<_>>()
-                .join(", ");
-
-            panic!(
-                "Value is too big for domain {}; value: {}, domain size: {}",
-                vars, value, self.max_value
-            )
-        }
-
-        let gray_code = Self::binary_to_gray_code(*value);
-        self.variables.iter().enumerate().for_each(|(idx, var)| {
-            bdd_valuation.set_value(*var, (gray_code & (1 << idx)) != 0);
-        })
+        self.try_encode_bits(bdd_valuation, value)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 
     fn empty_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
@@ -537,6 +923,14 @@ impl SymbolicDomain<u8> for GrayCodeIntegerDomain<u8> {
         self.variables.clone() // already sorted
     }
 
+    fn set_context(&mut self, variables: Arc<BddVariableSet>) {
+        self.context = Some(variables).into();
+    }
+
+    fn context(&self) -> Option<&BddVariableSet> {
+        self.context.as_deref()
+    }
+
     fn raw_bdd_variables_unsorted(&self) -> Vec<BddVariable> {
         self.raw_bdd_variables() // already the optimal performance
     }
@@ -571,9 +965,7 @@ impl SymbolicDomain<u8> for GrayCodeIntegerDomain<u8> {
 
 impl SymbolicDomainOrd<u8> for GrayCodeIntegerDomain<u8> {
     fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &u8) -> Self {
-        let bit_count = 8 - max_value.leading_zeros();
-
-        let variables = (0..bit_count)
+        let variables = (0..Self::required_bits(max_value))
             .map(|it| {
                 let name = format!("{name}_v{}", it + 1);
                 builder.make_variable(name.as_str())
@@ -583,13 +975,42 @@ impl SymbolicDomainOrd<u8> for GrayCodeIntegerDomain<u8> {
         Self {
             variables,
             max_value: *max_value,
+            context: None.into(),
         }
     }
 
+    fn try_encode_bits(
+        &self,
+        bdd_valuation: &mut BddPartialValuation,
+        value: &u8,
+    ) -> Result<(), ValueOutOfDomain<u8>> {
+        if value > &(self.max_value) {
+            // this breaks the idea of SymbolicDomain being not bound to the ordering
+            return Err(ValueOutOfDomain {
+                variables: self.variables.clone(),
+                value: *value,
+                max_value: self.max_value,
+            });
+        }
+
+        let gray_code = Self::binary_to_gray_code(*value);
+        self.variables.iter().enumerate().for_each(|(idx, var)| {
+            bdd_valuation.set_value(*var, (gray_code & (1 << idx)) != 0);
+        });
+
+        Ok(())
+    }
+
+    fn required_bits(max_value: &u8) -> usize {
+        (8 - max_value.leading_zeros()) as usize
+    }
+
     fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &u8) -> Bdd {
-        (0..*exclusive_upper_bound).fold(self.empty_collection(bdd_variable_set), |acc, val| {
-            acc.or(&self.encode_one(bdd_variable_set, &val))
-        })
+        (0..=self.max_value)
+            .take_while(|val| val < exclusive_upper_bound)
+            .fold(self.empty_collection(bdd_variable_set), |acc, val| {
+                acc.or(&self.encode_one(bdd_variable_set, &val))
+            })
     }
 
     fn cmp(lhs: &u8, rhs: &u8) -> std::cmp::Ordering {
@@ -599,4 +1020,14 @@ impl SymbolicDomainOrd<u8> for GrayCodeIntegerDomain<u8> {
     fn get_all_possible_values(&self) -> Vec<u8> {
         (0..=self.max_value).collect()
     }
+
+    fn max_value(&self) -> u8 {
+        self.max_value
+    }
+}
+
+impl DomainEncodingKind for GrayCodeIntegerDomain<u8> {
+    fn encoding_kind() -> EncodingKind {
+        EncodingKind::GrayCode
+    }
 }
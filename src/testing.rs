@@ -0,0 +1,71 @@
+//! Helpers for checking the correctness of [SymbolicDomain] implementations, including
+//! the custom ones defined outside of this crate.
+
+use std::fmt::Debug;
+
+use biodivine_lib_bdd::BddVariableSet;
+use thiserror::Error;
+
+use crate::symbolic_domains::symbolic_domain::SymbolicDomain;
+
+/// Describes why a [SymbolicDomain] failed [verify_domain_roundtrip].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RoundtripFailure<T: Debug> {
+    #[error("value {value:?} was decoded as {decoded:?}")]
+    DecodedDifferently { value: T, decoded: Vec<T> },
+    #[error("the encoding of value {value:?} is not within the unit collection")]
+    OutsideUnitCollection { value: T },
+    #[error("the encodings of values {first:?} and {second:?} overlap")]
+    OverlappingEncodings { first: T, second: T },
+}
+
+/// Check that each of the `values` survives encoding followed by decoding unchanged, that its
+/// encoding is a valid member of the `unit_collection` of the `domain`, and that the encodings
+/// of distinct values are disjoint.
+///
+/// The `variables` must be the [BddVariableSet] the `domain` was created in.
+pub fn verify_domain_roundtrip<D, T>(
+    domain: &D,
+    variables: &BddVariableSet,
+    values: &[T],
+) -> Result<(), RoundtripFailure<T>>
+where
+    D: SymbolicDomain<T>,
+    T: Eq + Clone + Debug,
+{
+    let unit_collection = domain.unit_collection(variables);
+    let mut encoded = Vec::with_capacity(values.len());
+
+    for value in values {
+        let bdd = domain.encode_one(variables, value);
+
+        let decoded = domain.decode_collection(variables, &bdd);
+        if decoded.as_slice() != std::slice::from_ref(value) {
+            return Err(RoundtripFailure::DecodedDifferently {
+                value: value.clone(),
+                decoded,
+            });
+        }
+
+        if !bdd.imp(&unit_collection).is_true() {
+            return Err(RoundtripFailure::OutsideUnitCollection {
+                value: value.clone(),
+            });
+        }
+
+        encoded.push((value, bdd));
+    }
+
+    for (index, (first, first_bdd)) in encoded.iter().enumerate() {
+        for (second, second_bdd) in encoded[index + 1..].iter() {
+            if first != second && !first_bdd.and(second_bdd).is_false() {
+                return Err(RoundtripFailure::OverlappingEncodings {
+                    first: (*first).clone(),
+                    second: (*second).clone(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use biodivine_lib_param_bn::{BooleanNetwork, FnUpdate, RegulatoryGraph};
+use thiserror::Error;
+
+use crate::expression_components::expression::Expression;
+use crate::symbolic_domains::symbolic_domain::SymbolicDomainOrd;
+use crate::update::update_fn::{variable_update_fn::condition_to_expression, SmartSystemUpdateFn};
+
+/// Returned when a system cannot be converted to a [BooleanNetwork]; see
+/// [SmartSystemUpdateFn::to_boolean_network].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ConversionError {
+    #[error("variable `{0}` is not Boolean (it has {1} values)")]
+    NotBoolean(String, usize),
+    #[error("parameter `{0}` is not Boolean (it has {1} values)")]
+    NotBooleanParameter(String, usize),
+    #[error("`{0}` is not a valid name of a Boolean network variable (or parameter)")]
+    InvalidName(String),
+}
+
+impl<DO, T> SmartSystemUpdateFn<DO, T>
+where
+    DO: SymbolicDomainOrd<T>,
+    T: PartialEq,
+{
+    /// Converts this system to a `lib-param-bn` [BooleanNetwork], so that the tools built
+    /// on top of it (attractors, bifurcations, ...) can be applied to the model.
+    ///
+    /// All the variables (and parameters) must be Boolean, i.e. their domains must have exactly
+    /// two values; the greater one is `true`. The update function of each variable is recovered
+    /// from its transition relation as an [Expression] (see
+    /// [VariableUpdateFn::to_expression](crate::update::update_fn::variable_update_fn::VariableUpdateFn::to_expression)),
+    /// and each variable it depends on becomes an (observable) regulator of the variable.
+    /// The parameters become parameters of arity zero.
+    pub fn to_boolean_network(&self) -> Result<BooleanNetwork, ConversionError> {
+        let boolean_values = |name: &str, domain: &DO| {
+            let mut values = domain.get_all_possible_values();
+            values.sort_by(DO::cmp);
+            match <[T; 2]>::try_from(values) {
+                Ok(values) => Ok(values),
+                Err(values) => Err((name.to_string(), values.len())),
+            }
+        };
+        // the names `lib-param-bn` can parse; its own `is_valid_name` only checks that the name
+        // contains such a substring
+        let valid_name = |name: &str| {
+            let is_identifier = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|it| it.is_ascii_alphanumeric() || it == '_');
+            if is_identifier {
+                Ok(())
+            } else {
+                Err(ConversionError::InvalidName(name.to_string()))
+            }
+        };
+
+        let variables = self
+            .iter_domains()
+            .map(|(name, domain)| {
+                valid_name(name)?;
+                let values = boolean_values(name, domain)
+                    .map_err(|(name, count)| ConversionError::NotBoolean(name, count))?;
+                Ok((name, domain, values))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let parameters = self
+            .get_parameters()
+            .into_iter()
+            .map(|name| {
+                valid_name(&name)?;
+                let domain = self
+                    .get_parameter_domain(&name)
+                    .expect("parameter has a domain");
+                let values = boolean_values(&name, domain)
+                    .map_err(|(name, count)| ConversionError::NotBooleanParameter(name, count))?;
+                Ok((name, domain, values))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let named_domains = variables
+            .iter()
+            .map(|(name, domain, _)| (*name, *domain))
+            .chain(
+                parameters
+                    .iter()
+                    .map(|(name, domain, _)| (name.as_str(), *domain)),
+            )
+            .collect::<HashMap<_, _>>();
+        let functions = variables
+            .iter()
+            .map(|(name, _, [_, true_value])| {
+                let condition = self
+                    .update_condition(name, true_value)
+                    .expect("variable of the system");
+                condition_to_expression(&condition, self.get_bdd_variable_set(), &named_domains)
+            })
+            .collect::<Vec<_>>();
+
+        let mut graph = RegulatoryGraph::new(
+            variables
+                .iter()
+                .map(|(name, _, _)| name.to_string())
+                .collect(),
+        );
+        for ((target, _, _), function) in variables.iter().zip(&functions) {
+            let mut regulators = function
+                .referenced_variables()
+                .into_iter()
+                .filter(|name| self.get_domain(name).is_some())
+                .collect::<Vec<_>>();
+            regulators.sort_unstable();
+            for regulator in regulators {
+                graph
+                    .add_regulation(regulator, target, true, None)
+                    .expect("regulation is added only once");
+            }
+        }
+
+        let mut network = BooleanNetwork::new(graph);
+        for (name, _, _) in &parameters {
+            network
+                .add_parameter(name, 0)
+                .expect("parameter names are valid and distinct from the variables");
+        }
+        let true_values = variables
+            .iter()
+            .map(|(name, _, [_, true_value])| (*name, true_value))
+            .chain(
+                parameters
+                    .iter()
+                    .map(|(name, _, [_, true_value])| (name.as_str(), true_value)),
+            )
+            .collect::<HashMap<_, _>>();
+        for ((name, _, _), function) in variables.iter().zip(&functions) {
+            let function = to_fn_update(&network, &true_values, function);
+            let variable = network
+                .as_graph()
+                .find_variable(name)
+                .expect("variable of the network");
+            network
+                .set_update_function(variable, Some(function))
+                .expect("the arguments of the function are its regulators");
+        }
+
+        Ok(network)
+    }
+}
+
+/// Translates the `expression` produced by [condition_to_expression] over Boolean variables
+/// (and parameters) of the `network`, whose `true` values are given by `true_values`.
+fn to_fn_update<T: PartialEq>(
+    network: &BooleanNetwork,
+    true_values: &HashMap<&str, &T>,
+    expression: &Expression<T>,
+) -> FnUpdate {
+    let recurse = |inner: &Expression<T>| to_fn_update(network, true_values, inner);
+    match expression {
+        Expression::Terminal(proposition) => {
+            let atom = match network.as_graph().find_variable(&proposition.variable) {
+                Some(variable) => FnUpdate::mk_var(variable),
+                None => {
+                    let parameter = network
+                        .find_parameter(&proposition.variable)
+                        .expect("proposition over a variable or a parameter");
+                    FnUpdate::mk_param(parameter, &[])
+                }
+            };
+            // the expressions only contain the propositions `variable == value`
+            if true_values[proposition.variable.as_str()] == &proposition.value {
+                atom
+            } else {
+                FnUpdate::mk_not(atom)
+            }
+        }
+        Expression::Not(inner) => FnUpdate::mk_not(recurse(inner)),
+        Expression::And(items) => {
+            FnUpdate::mk_conjunction(&items.iter().map(recurse).collect::<Vec<_>>())
+        }
+        Expression::Or(items) => {
+            FnUpdate::mk_disjunction(&items.iter().map(recurse).collect::<Vec<_>>())
+        }
+        Expression::Xor(lhs, rhs) => recurse(lhs).xor(recurse(rhs)),
+        Expression::Implies(lhs, rhs) => recurse(lhs).implies(recurse(rhs)),
+        Expression::VariableComparison(_) => {
+            unreachable!("the recovered expressions do not compare variables")
+        }
+    }
+}
@@ -1,2 +1,8 @@
+#[cfg(feature = "lib-param-bn")]
+pub mod boolean_network;
+pub mod reachability;
+pub mod restricted_system;
+pub mod transition_system;
 pub mod unprocessed_variable_update_function;
 pub mod update_fn;
+pub mod update_fn_dsl;
@@ -0,0 +1,174 @@
+use std::ops::Shr;
+
+use biodivine_lib_bdd::Bdd;
+use num_bigint::BigInt;
+
+use crate::symbolic_domains::symbolic_domain::{SymbolicDomain, SymbolicDomainOrd};
+use crate::update::update_fn::SmartSystemUpdateFn;
+
+/// Drives reachability over a [SmartSystemUpdateFn] one step at a time.
+///
+/// The state space is explored in "waves": [ReachabilitySession::initialize] picks a single
+/// state that was not explored yet, and then [ReachabilitySession::step_fwd] (or
+/// [ReachabilitySession::step_bwd]) is called until the wave converges. This repeats until
+/// the whole state space is explored (see [ReachabilitySession::is_done]).
+///
+/// Since every step is cheap compared to the whole computation, this is convenient for
+/// reporting progress or for interrupting long-running computations.
+pub struct ReachabilitySession<D, T>
+where
+    D: SymbolicDomain<T>,
+{
+    system: SmartSystemUpdateFn<D, T>,
+    /// states not covered by any (finished or ongoing) wave yet
+    unexplored: Bdd,
+    /// result of the current (or the last finished) wave
+    current: Bdd,
+    /// true if no wave is in progress
+    converged: bool,
+    steps: usize,
+}
+
+impl<D, T> ReachabilitySession<D, T>
+where
+    D: SymbolicDomain<T>,
+{
+    pub fn new(system: SmartSystemUpdateFn<D, T>) -> Self {
+        Self {
+            unexplored: system.unit_vertex_set(),
+            current: system.get_bdd_variable_set().mk_false(),
+            converged: true,
+            steps: 0,
+            system,
+        }
+    }
+
+    pub fn system(&self) -> &SmartSystemUpdateFn<D, T> {
+        &self.system
+    }
+
+    /// The result of the current wave; or of the last finished one if no wave is in progress.
+    /// Empty before the first call to [ReachabilitySession::initialize].
+    pub fn current(&self) -> &Bdd {
+        &self.current
+    }
+
+    /// The set of states that were not reached by any wave yet.
+    pub fn unexplored(&self) -> &Bdd {
+        &self.unexplored
+    }
+
+    /// The number of steps performed within the current (or the last finished) wave.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// True if the computation explored all states of the system.
+    pub fn is_done(&self) -> bool {
+        self.converged && self.unexplored.is_false()
+    }
+
+    /// True if the current wave converged (or none was started yet), meaning a new one
+    /// can be started using [ReachabilitySession::initialize].
+    pub fn can_initialize(&self) -> bool {
+        self.converged
+    }
+
+    /// The exact number of states not reached by any wave yet.
+    pub fn remaining(&self) -> BigInt {
+        // `unexplored` does not depend on the primed variables
+        self.unexplored
+            .exact_cardinality()
+            .shr(self.system.primed_variables().len())
+    }
+
+    /// Start a new wave from a single state picked from the unexplored states.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a wave is still in progress or if the computation is already done.
+    pub fn initialize(&mut self) {
+        assert!(!self.is_done(), "all states are already explored");
+        let state = self.system.pick_state_bdd(&self.unexplored);
+        self.initialize_with(state);
+    }
+
+    /// Start a new wave from the given set of `initial_states`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a wave is still in progress.
+    pub fn initialize_with(&mut self, initial_states: Bdd) {
+        assert!(self.can_initialize(), "the current wave has not converged");
+        self.steps = 0;
+        self.unexplored = self.unexplored.and_not(&initial_states);
+        self.current = initial_states;
+        self.converged = false;
+    }
+
+    fn apply_step(&mut self, new_states: Option<Bdd>) -> bool {
+        assert!(!self.converged, "no wave is in progress");
+        self.steps += 1;
+        match new_states {
+            Some(result) => {
+                self.unexplored = self.unexplored.and_not(&result);
+                self.current = result;
+                true
+            }
+            None => {
+                self.converged = true;
+                false
+            }
+        }
+    }
+}
+
+impl<D, T> ReachabilitySession<D, T>
+where
+    D: SymbolicDomainOrd<T>,
+{
+    /// Extend the current wave by the successors under the first variable (in reverse
+    /// order) that produces some new states.
+    ///
+    /// Returns `false` if no new states were found, i.e. the wave converged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no wave is in progress.
+    pub fn step_fwd(&mut self) -> bool {
+        let new_states = self.fwd_step();
+        self.apply_step(new_states)
+    }
+
+    /// The same as [ReachabilitySession::step_fwd], but goes backward, not forward.
+    pub fn step_bwd(&mut self) -> bool {
+        let new_states = self.bwd_step();
+        self.apply_step(new_states)
+    }
+
+    fn fwd_step(&self) -> Option<Bdd> {
+        for var in self.system.variables_in_bdd_order().iter().rev() {
+            let successors = self.system.transition_under_variable(var, &self.current);
+
+            // Should be equivalent to "successors \not\subseteq result".
+            if !successors.imp(&self.current).is_true() {
+                return Some(successors.or(&self.current));
+            }
+        }
+
+        None
+    }
+
+    fn bwd_step(&self) -> Option<Bdd> {
+        for var in self.system.variables_in_bdd_order().iter().rev() {
+            let predecessors = self.system.predecessors_under_variable(var, &self.current);
+
+            // Should be equivalent to "predecessors \not\subseteq result".
+            if !predecessors.imp(&self.current).is_true() {
+                return Some(predecessors.or(&self.current));
+            }
+        }
+
+        None
+    }
+}
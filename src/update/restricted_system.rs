@@ -0,0 +1,60 @@
+use biodivine_lib_bdd::Bdd;
+
+use crate::symbolic_domains::symbolic_domain::{SymbolicDomain, SymbolicDomainOrd};
+use crate::update::update_fn::SmartSystemUpdateFn;
+
+/// A view of a [SmartSystemUpdateFn] restricted to a subspace (`universe`) of its states, as if
+/// the subspace was the whole state space; see [SmartSystemUpdateFn::restrict_to].
+///
+/// Both the source sets and the results of all the operations are intersected with the
+/// `universe`, hence the results never escape it. The view borrows the parent system,
+/// so no transition relations are copied.
+pub struct RestrictedSystem<'a, D, T>
+where
+    D: SymbolicDomain<T>,
+{
+    system: &'a SmartSystemUpdateFn<D, T>,
+    universe: Bdd,
+}
+
+impl<'a, D, T> RestrictedSystem<'a, D, T>
+where
+    D: SymbolicDomain<T>,
+{
+    pub(crate) fn new(system: &'a SmartSystemUpdateFn<D, T>, universe: &Bdd) -> Self {
+        Self {
+            system,
+            universe: system.sanitize(universe),
+        }
+    }
+
+    pub fn system(&self) -> &'a SmartSystemUpdateFn<D, T> {
+        self.system
+    }
+
+    /// The (sanitized) set of states this view is restricted to.
+    pub fn universe(&self) -> &Bdd {
+        &self.universe
+    }
+}
+
+impl<'a, D, T> RestrictedSystem<'a, D, T>
+where
+    D: SymbolicDomainOrd<T>,
+{
+    /// Like [SmartSystemUpdateFn::successors_async], but only considers the states of
+    /// the universe (both as the sources and as the successors).
+    pub fn successors_async(&self, transition_variable_name: &str, source_states: &Bdd) -> Bdd {
+        self.system
+            .successors_async(transition_variable_name, &source_states.and(&self.universe))
+            .and(&self.universe)
+    }
+
+    /// Like [SmartSystemUpdateFn::predecessors_async], but only considers the states of
+    /// the universe (both as the sources and as the predecessors).
+    pub fn predecessors_async(&self, transition_variable_name: &str, source_states: &Bdd) -> Bdd {
+        self.system
+            .predecessors_async(transition_variable_name, source_states.and(&self.universe))
+            .and(&self.universe)
+    }
+}
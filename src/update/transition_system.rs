@@ -0,0 +1,45 @@
+use biodivine_lib_bdd::Bdd;
+
+use crate::symbolic_domains::symbolic_domain::SymbolicDomainOrd;
+use crate::update::update_fn::SmartSystemUpdateFn;
+
+/// The interface of an asynchronous symbolic transition system, in the spirit of the
+/// `AsynchronousGraph` of `biodivine-lib-param-bn` (`post`/`pre`/`unit_colored_vertices`).
+///
+/// Algorithms written against this trait do not depend on the concrete representation
+/// of the system, so they can be shared with (or ported from) the rest of the biodivine tools.
+pub trait SymbolicTransitionSystem {
+    /// All the states reachable from `set` in a single asynchronous step (under any variable).
+    fn post(&self, set: &Bdd) -> Bdd;
+
+    /// All the states from which `set` is reachable in a single asynchronous step
+    /// (under any variable).
+    fn pre(&self, set: &Bdd) -> Bdd;
+
+    /// The set of all the valid states of the system.
+    fn unit(&self) -> Bdd;
+
+    /// The names of the variables of the system.
+    fn variables(&self) -> &[String];
+}
+
+impl<DO, T> SymbolicTransitionSystem for SmartSystemUpdateFn<DO, T>
+where
+    DO: SymbolicDomainOrd<T>,
+{
+    fn post(&self, set: &Bdd) -> Bdd {
+        self.successors_async_any(set)
+    }
+
+    fn pre(&self, set: &Bdd) -> Bdd {
+        self.predecessors_async_any(set)
+    }
+
+    fn unit(&self) -> Bdd {
+        self.unit_vertex_set()
+    }
+
+    fn variables(&self) -> &[String] {
+        self.variable_names()
+    }
+}
@@ -1,3 +1,5 @@
+use std::{collections::HashSet, fmt::Display};
+
 use crate::expression_components::expression::Expression;
 
 #[derive(Debug)]
@@ -22,4 +24,41 @@ impl<T> UnprocessedVariableUpdateFn<T> {
             default,
         }
     }
+
+    /// Flatten the conditions of all the terms (see [Expression::flatten]), so that each wide
+    /// conjunction or disjunction is compiled as a whole.
+    pub fn flatten(self) -> Self {
+        Self {
+            terms: self
+                .terms
+                .into_iter()
+                .map(|(value, condition)| (value, condition.flatten()))
+                .collect(),
+            ..self
+        }
+    }
+
+    /// Returns the names of all the variables referenced in the conditions of the terms.
+    pub fn referenced_variables(&self) -> HashSet<&str> {
+        self.terms
+            .iter()
+            .flat_map(|(_, expression)| expression.referenced_variables())
+            .collect()
+    }
+}
+
+/// Renders the update function as readable pseudo-code, e.g.
+/// `f(target) = 2 if (A >= 1 & B < 3); 1 if (C == 0); else 0`.
+impl<T: Display> Display for UnprocessedVariableUpdateFn<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "f({}) = ", self.target_var_name)?;
+        for (value, condition) in &self.terms {
+            write!(f, "{} if ({}); ", value, condition)?;
+        }
+        if self.terms.is_empty() {
+            write!(f, "{}", self.default)
+        } else {
+            write!(f, "else {}", self.default)
+        }
+    }
 }
@@ -1,19 +1,38 @@
 #![allow(dead_code)]
 
-use std::{collections::HashMap, fmt::Debug};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::{Debug, Display},
+    io::{self, BufRead, BufReader, Read, Write},
+    str::FromStr,
+    sync::Arc,
+};
 
 use biodivine_lib_bdd::{
-    Bdd, BddPartialValuation, BddVariable, BddVariableSet, BddVariableSetBuilder,
+    op_function, Bdd, BddPartialValuation, BddPointer, BddValuation, BddVariable, BddVariableSet,
+    BddVariableSetBuilder,
 };
 
 use crate::{
     expression_components::{expression::Expression, proposition::Proposition},
-    symbolic_domains::symbolic_domain::{SymbolicDomain, SymbolicDomainOrd},
-    update::unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+    symbolic_domains::{
+        domain_builder::DomainBuilder,
+        symbolic_domain::{
+            project_onto_encoding, DomainEncodingKind, EncodingKind, SymbolicDomain,
+            SymbolicDomainOrd, ValueOutOfDomain,
+        },
+    },
+    update::{
+        restricted_system::RestrictedSystem,
+        unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+    },
 };
 
 use self::variable_update_fn::VariableUpdateFn;
 use debug_ignore::DebugIgnore;
+use num_bigint::BigInt;
+use thiserror::Error;
 
 #[derive(Debug)]
 pub struct SystemUpdateFn<D, T>
@@ -21,7 +40,12 @@ where
     D: SymbolicDomain<T>,
 {
     /// ordered by variable name // todo add a method to get the update function by name (hash map or binary search)
-    update_fns: Vec<(String, (VariableUpdateFn, D))>,
+    update_fns: Vec<(String, (VariableUpdateFn<T>, D))>,
+    /// conjunction of the unit collections of all the domains; computed once during construction
+    unit_collection: Bdd,
+    /// for each variable (in the same order as `update_fns`), the bit encodings of all the values
+    /// allowed by its domain; computed once during construction
+    allowed_values_bit_encoded: Vec<Vec<Vec<bool>>>,
     bdd_variable_set: DebugIgnore<BddVariableSet>,
     _marker: std::marker::PhantomData<T>,
 }
@@ -34,13 +58,20 @@ where
         vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
     ) -> Self {
         let named_update_fns_sorted = {
-            let mut to_be_sorted = vars_and_their_update_fns.into_iter().collect::<Vec<_>>();
+            let mut to_be_sorted = vars_and_their_update_fns
+                .into_iter()
+                .map(|(var_name, update_fn)| (var_name, update_fn.flatten()))
+                .collect::<Vec<_>>();
             to_be_sorted.sort_unstable_by_key(|(var_name, _)| var_name.clone());
             to_be_sorted
         };
 
         let (symbolic_domains, bdd_variable_set) = {
-            let max_values = find_max_values::<DO, T>(&named_update_fns_sorted);
+            let max_values = find_max_values::<DO, T, _>(
+                named_update_fns_sorted
+                    .iter()
+                    .map(|(var_name, update_fn)| (var_name, update_fn)),
+            );
             let (symbolic_domains, variable_set_builder) = named_update_fns_sorted.iter().fold(
                 (Vec::new(), BddVariableSetBuilder::new()),
                 |(mut domains, mut variable_set), (var_name, _update_fn)| {
@@ -48,7 +79,7 @@ where
                         .get(var_name.as_str())
                         .expect("max value always present");
 
-                    let domain = DO::new(&mut variable_set, var_name, max_value);
+                    let domain = DO::new(&mut variable_set, &internal_name(var_name), max_value);
                     domains.push(domain);
                     (domains, variable_set)
                 },
@@ -75,6 +106,23 @@ where
             })
             .collect::<Vec<_>>();
 
+        let unit_collection = symbolic_domains
+            .iter()
+            .fold(bdd_variable_set.mk_true(), |acc, domain| {
+                acc.and(&domain.unit_collection(&bdd_variable_set))
+            });
+
+        let allowed_values_bit_encoded = symbolic_domains
+            .iter()
+            .map(|domain| {
+                domain
+                    .get_all_possible_values()
+                    .into_iter()
+                    .map(|value| domain.raw_bdd_variables_encode(&value))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
         let the_triple = named_update_fns_sorted
             .into_iter()
             .zip(update_fns)
@@ -82,30 +130,29 @@ where
             .map(|(((var_name, _), update_fn), domain)| (var_name, (update_fn, domain)))
             .collect::<Vec<_>>();
 
+        let context = Arc::new(bdd_variable_set.clone());
+        let the_triple = the_triple
+            .into_iter()
+            .map(|(var_name, (update_fn, mut domain))| {
+                domain.set_context(context.clone());
+                (var_name, (update_fn, domain))
+            })
+            .collect();
+
         Self {
             update_fns: the_triple,
+            unit_collection,
+            allowed_values_bit_encoded,
             bdd_variable_set: bdd_variable_set.into(),
             _marker: std::marker::PhantomData,
         }
     }
 
-    fn get_update_fn_and_domain_of(&self, variable_name: &str) -> Option<&(VariableUpdateFn, DO)> {
-        // todo optimize using the hashtable mapper
-        self.update_fns
-            .iter()
-            .find(|(maybe_variable_name, _)| maybe_variable_name == variable_name)
-            .map(|(_, update_fn_and_domain)| update_fn_and_domain)
-    }
-
-    /// Returns a BDD that represents the set of states that are successors of
-    /// any state from `source_states` under given transition variable.
-    ///
     /// # Panics
     ///
     /// Panics if variable with given name is not available.
-    pub fn successors_async(&self, transition_variable_name: &str, source_states_set: &Bdd) -> Bdd {
-        let (update_fn, domain) = self
-            .get_update_fn_and_domain_of(transition_variable_name)
+    fn index_of_transition_variable(&self, transition_variable_name: &str) -> usize {
+        self.get_index_of(transition_variable_name)
             .unwrap_or_else(|| {
                 panic!(
                     "no update function for variable {}; only [{}] are available",
@@ -116,31 +163,47 @@ where
                         .collect::<Vec<_>>()
                         .join(", ")
                 )
-            });
+            })
+    }
 
-        let each_allowed_value_bit_encoded = domain
-            .decode_collection(
-                &self.bdd_variable_set,
-                &domain.unit_collection(&self.bdd_variable_set),
-            )
-            .into_iter()
-            .map(|value| domain.raw_bdd_variables_encode(&value))
-            .collect::<Vec<_>>();
+    fn get_update_fn_and_domain_of(
+        &self,
+        variable_name: &str,
+    ) -> Option<&(VariableUpdateFn<T>, DO)> {
+        self.get_index_of(variable_name)
+            .map(|idx| &self.update_fns[idx].1)
+    }
 
-        let unit_collection = self
-            .update_fns
+    fn get_index_of(&self, variable_name: &str) -> Option<usize> {
+        // todo optimize using the hashtable mapper
+        self.update_fns
             .iter()
-            .fold(self.bdd_variable_set.mk_true(), |acc, (_, (_, domain))| {
-                acc.and(&domain.unit_collection(&self.bdd_variable_set))
-            });
+            .position(|(maybe_variable_name, _)| maybe_variable_name == variable_name)
+    }
+
+    /// Returns a BDD that represents the set of states that are successors of
+    /// any state from `source_states` under given transition variable.
+    ///
+    /// The unit collection and the allowed values of the variable are cached during
+    /// construction, so they are not re-derived in every call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if variable with given name is not available.
+    pub fn successors_async(&self, transition_variable_name: &str, source_states_set: &Bdd) -> Bdd {
+        let variable_idx = self.index_of_transition_variable(transition_variable_name);
+
+        let (_, (update_fn, domain)) = &self.update_fns[variable_idx];
+        let each_allowed_value_bit_encoded = &self.allowed_values_bit_encoded[variable_idx];
+        let unit_collection = &self.unit_collection;
 
-        let unpruned_res = each_allowed_value_bit_encoded.into_iter().fold(
+        let unpruned_res = each_allowed_value_bit_encoded.iter().fold(
             self.bdd_variable_set.mk_false(),
             |acc, val_bits| {
                 let any_state_capable_of_transitioning_into_target_value = update_fn
-                    .bit_answering_bdds
+                    .bit_answering_bdds()
                     .iter()
-                    .zip(&val_bits)
+                    .zip(val_bits)
                     .fold(
                         self.bdd_variable_set.mk_true(),
                         |acc, ((_, bdd), val_bit)| {
@@ -151,7 +214,7 @@ where
                             }
                         },
                     )
-                    .and(&unit_collection);
+                    .and(unit_collection);
 
                 let those_from_source_capable_of_transitioning_into_target_value =
                     source_states_set.and(&any_state_capable_of_transitioning_into_target_value);
@@ -164,7 +227,7 @@ where
                     domain
                         .raw_bdd_variables()
                         .into_iter()
-                        .zip(val_bits)
+                        .zip(val_bits.iter().copied())
                         .collect::<Vec<_>>()
                         .as_slice(),
                 );
@@ -173,7 +236,7 @@ where
             },
         );
 
-        unpruned_res.and(&unit_collection)
+        unpruned_res.and(unit_collection)
     }
 
     /// Like `successors_async`, but a state that "transitions" to itself under
@@ -194,75 +257,103 @@ where
 
     // pub fn predecessors_async
     pub fn predecessors_async(&self, transition_variable_name: &str, source_states: &Bdd) -> Bdd {
-        let (update_fn, domain) = self
-            .get_update_fn_and_domain_of(transition_variable_name)
-            .unwrap_or_else(|| {
-                panic!(
-                    "no update function for variable {}; only [{}] are available",
-                    transition_variable_name,
-                    self.update_fns
-                        .iter()
-                        .map(|(var_name, _)| var_name.as_str())
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                )
-            });
+        let variable_idx = self.index_of_transition_variable(transition_variable_name);
+        self.predecessors_async_into(
+            variable_idx,
+            &self.allowed_values_bit_encoded[variable_idx],
+            source_states,
+        )
+    }
 
-        let each_allowed_value_bit_encoded = domain
-            .decode_collection(
-                &self.bdd_variable_set,
-                &domain.unit_collection(&self.bdd_variable_set),
-            )
-            .into_iter()
-            .map(|value| domain.raw_bdd_variables_encode(&value));
+    /// Like `predecessors_async`, but only the transitions that update the variable to one
+    /// of the `target_values` are considered; i.e. computes the states that can transition
+    /// into some of the `source_states` by setting the variable to one of these values.
+    /// The values outside of the domain of the variable are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if variable with given name is not available.
+    pub fn predecessors_async_to_values(
+        &self,
+        variable_name: &str,
+        target_values: &[T],
+        source_states: &Bdd,
+    ) -> Bdd
+    where
+        T: Clone,
+    {
+        let variable_idx = self.index_of_transition_variable(variable_name);
+        let (_, (_, domain)) = &self.update_fns[variable_idx];
+        let target_values_bit_encoded = target_values
+            .iter()
+            .filter_map(|value| {
+                let mut valuation = BddPartialValuation::empty();
+                domain.try_encode_bits(&mut valuation, value).ok()?;
+                let bits = domain
+                    .raw_bdd_variables()
+                    .into_iter()
+                    .map(|var| valuation.get_value(var).expect("encoded bit"))
+                    .collect::<Vec<_>>();
+                Some(bits)
+            })
+            .collect::<Vec<_>>();
 
-        each_allowed_value_bit_encoded.fold(self.bdd_variable_set.mk_false(), |acc, val_bits| {
-            let filter = update_fn
-                .bit_answering_bdds
-                .iter()
-                .zip(&val_bits)
-                .map(|((bdd_variable, _bdd), bit_val)| {
-                    (bdd_variable.to_owned(), bit_val.to_owned())
-                })
-                .collect::<Vec<_>>();
+        self.predecessors_async_into(variable_idx, &target_values_bit_encoded, source_states)
+    }
 
-            let those_from_source_with_target_value = source_states.select(filter.as_slice());
+    /// The predecessors of the `source_states` under the variable at `variable_idx`, such that
+    /// the variable is updated to one of the given (bit-encoded) values.
+    fn predecessors_async_into(
+        &self,
+        variable_idx: usize,
+        target_values_bit_encoded: &[Vec<bool>],
+        source_states: &Bdd,
+    ) -> Bdd {
+        let (_, (update_fn, domain)) = &self.update_fns[variable_idx];
+        let unit_set = &self.unit_collection;
+        let domain_unit_collection = domain.unit_collection(&self.bdd_variable_set);
 
-            let possible_predecessors = those_from_source_with_target_value
-                .exists(
-                    filter
-                        .iter()
-                        .map(|(bdd_var, _)| *bdd_var)
-                        .collect::<Vec<_>>()
-                        .as_slice(),
-                )
-                .and(&domain.unit_collection(&self.bdd_variable_set)); // keep only valid states
+        target_values_bit_encoded
+            .iter()
+            .fold(self.bdd_variable_set.mk_false(), |acc, val_bits| {
+                let filter = update_fn
+                    .bit_answering_bdds()
+                    .iter()
+                    .zip(val_bits)
+                    .map(|((bdd_variable, _bdd), bit_val)| {
+                        (bdd_variable.to_owned(), bit_val.to_owned())
+                    })
+                    .collect::<Vec<_>>();
 
-            let unit_set = self
-                .update_fns
-                .iter()
-                .fold(self.bdd_variable_set.mk_true(), |acc, (_, (_, domain))| {
-                    acc.and(&domain.unit_collection(&self.bdd_variable_set))
-                });
+                let those_from_source_with_target_value = source_states.select(filter.as_slice());
+
+                let possible_predecessors = those_from_source_with_target_value
+                    .exists(
+                        filter
+                            .iter()
+                            .map(|(bdd_var, _)| *bdd_var)
+                            .collect::<Vec<_>>()
+                            .as_slice(),
+                    )
+                    .and(&domain_unit_collection); // keep only valid states
 
-            let any_state_capable_of_transitioning_into_target_value =
-                update_fn.bit_answering_bdds.iter().zip(&val_bits).fold(
-                    // self.bdd_variable_set.mk_true(),
-                    unit_set,
-                    |acc, ((_, bdd), val_bit)| {
+                let any_state_capable_of_transitioning_into_target_value = update_fn
+                    .bit_answering_bdds()
+                    .iter()
+                    .zip(val_bits)
+                    .fold(unit_set.clone(), |acc, ((_, bdd), val_bit)| {
                         if *val_bit {
                             acc.and(bdd)
                         } else {
                             acc.and_not(bdd)
                         }
-                    },
-                );
+                    });
 
-            let predecessors =
-                possible_predecessors.and(&any_state_capable_of_transitioning_into_target_value);
+                let predecessors = possible_predecessors
+                    .and(&any_state_capable_of_transitioning_into_target_value);
 
-            acc.or(&predecessors)
-        })
+                acc.or(&predecessors)
+            })
     }
 
     /// Like `predecessors_async`, but a state that "transitions" to itself under
@@ -303,9 +394,28 @@ where
     domain: D,
     primed_domain: D,
     transition_relation: Bdd,
+    /// the default value of the update function, encoded using the `primed_domain`; empty if
+    /// the value is not in the domain (e.g. after [SmartSystemUpdateFn::tighten_domains])
+    default_output: Bdd,
     _marker: std::marker::PhantomData<T>,
 }
 
+/// A variable of a system re-encoded into new domains; see [SmartSystemUpdateFn::reencode].
+struct ReencodedVariable<'a, D, T> {
+    name: String,
+    primed_name: String,
+    domain: D,
+    primed_domain: D,
+    /// the relation (of the original system) updating the variable
+    relation: &'a Bdd,
+    /// the default output of the update function, in the primed domain of the original system
+    default_output: &'a Bdd,
+    /// the values of the variable, each with the (original) states it stands for
+    values: Vec<(Bdd, T)>,
+    /// like `values`, but for the primed domain
+    primed_values: Vec<(Bdd, T)>,
+}
+
 pub struct SmartSystemUpdateFn<D, T>
 where
     D: SymbolicDomain<T>,
@@ -314,9 +424,192 @@ where
     mapper: HashMap<String, usize>,
     variables_transition_relation_and_domain: Vec<(String, VarInfo<D, T>)>,
     bdd_variable_set: BddVariableSet,
+    /// conjunction of the unit collections of all the (un-primed) domains; computed once during
+    /// construction, since it is needed by most set operations
+    unit_vertex_set: Bdd,
+    /// the [BddVariable]-s of all the (un-primed) domains, in the order of the system variables
+    standard_variables: Vec<BddVariable>,
+    /// names of the system variables, in the order of `variables_transition_relation_and_domain`;
+    /// kept so that they can be borrowed as a slice (see
+    /// [crate::update::transition_system::SymbolicTransitionSystem::variables])
+    variable_names: Vec<String>,
+    /// parameters of the system (sorted by name); these are never updated, so their value is
+    /// fixed along any path, making them the "colours" of the states
+    parameters: Vec<(String, D)>,
     _marker: std::marker::PhantomData<T>,
 }
 
+/// Some update function compares `variable` with a `value` greater than any value the variable
+/// can be updated to; see [SmartSystemUpdateFn::try_from_update_fns_strict].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("variable `{variable}` is compared with {value:?}, which is out of its domain")]
+pub struct OutOfDomainConstant<T> {
+    pub variable: String,
+    pub value: T,
+}
+
+/// Returned when the supplied domains do not fit the update functions;
+/// see [SmartSystemUpdateFn::from_update_fns_with_context].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    #[error("no domain supplied for variable `{0}`")]
+    MissingDomain(String),
+    #[error("no primed domain supplied for variable `{0}`")]
+    MissingPrimedDomain(String),
+    #[error("a domain of variable `{0}` uses variables outside of the supplied `BddVariableSet`")]
+    ForeignDomain(String),
+    #[error("variable `{0}` is referenced, but has no update function")]
+    MissingUpdateFunction(String),
+}
+
+/// Returned when two systems cannot be composed; see [SmartSystemUpdateFn::compose].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ComposeError {
+    /// The variable (or parameter) `{0}` is defined differently by the two systems: it has
+    /// different domains, different update functions, or is a parameter of only one of them.
+    #[error("variable `{0}` is defined differently by the composed systems")]
+    ConflictingDefinition(String),
+}
+
+/// Returned when two variables cannot be merged; see [SmartSystemUpdateFn::merge_variables].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MergeError {
+    #[error("variable `{0}` is not a variable of the system")]
+    UnknownVariable(String),
+    #[error("variable `{0}` cannot be merged with itself")]
+    SameVariable(String),
+    #[error("name `{0}` is already used by another variable or parameter")]
+    NameTaken(String),
+    /// The values of the merged variable are counted using `From<u8>`, so at most 256
+    /// of them are supported.
+    #[error("the merged variable would have {0} values, which is more than 256")]
+    TooManyValues(usize),
+}
+
+/// Returned when a domain cannot be widened; see [SmartSystemUpdateFn::widen_domain].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum WidenError {
+    #[error("variable `{0}` is not a variable of the system")]
+    UnknownVariable(String),
+    /// The new max value of the variable `{0}` is smaller than its current one;
+    /// see [SmartSystemUpdateFn::tighten_domains] for shrinking the domains.
+    #[error("the new max value of variable `{0}` is smaller than its current one")]
+    Narrowing(String),
+}
+
+/// Returned when a variable cannot be renamed; see [SmartSystemUpdateFn::rename_variable].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RenameError {
+    #[error("variable `{0}` is not a variable of the system")]
+    UnknownVariable(String),
+    #[error("name `{0}` is already used by another variable or parameter")]
+    NameTaken(String),
+}
+
+/// Returned when a transition variable is not a variable of the system;
+/// see [SmartSystemUpdateFn::try_successors_async].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("no update function for variable {name}; only [{}] are available", available.join(", "))]
+pub struct UnknownVariable {
+    pub name: String,
+    /// the variables of the system, in the order of
+    /// [SmartSystemUpdateFn::get_system_variables]
+    pub available: Vec<String>,
+}
+
+/// Returned when there are more states than the caller is willing to enumerate;
+/// see [SmartSystemUpdateFn::enumerate_fixed_points].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("there are {count} states, which is more than the limit of {max}")]
+pub struct TooMany {
+    /// the exact number of the states
+    pub count: BigInt,
+    pub max: usize,
+}
+
+/// The number of states of a set; see [SmartSystemUpdateFn::state_count_category].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateCount {
+    Empty,
+    Single,
+    /// the exact number of the states (always at least two)
+    Many(BigInt),
+}
+
+/// Returned when a (partial) assignment of values to variables cannot be encoded;
+/// see [SmartSystemUpdateFn::encode_state].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum StateEncodingError<T> {
+    #[error("variable `{0}` is not assigned any value")]
+    MissingVariable(String),
+    #[error("variable `{0}` is not a variable of the system")]
+    UnknownVariable(String),
+    #[error(transparent)]
+    ValueOutOfDomain(#[from] ValueOutOfDomain<T>),
+}
+
+/// Returned when a set of states cannot be read;
+/// see [SmartSystemUpdateFn::import_states_csv].
+#[derive(Error, Debug)]
+pub enum ImportError<T> {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("the header row is missing")]
+    MissingHeader,
+    #[error("variable `{0}` appears in the header more than once")]
+    DuplicateVariable(String),
+    #[error("line {line}: expected {expected} values, found {found}")]
+    WrongColumnCount {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("line {line}: `{value}` is not a valid value")]
+    InvalidValue { line: usize, value: String },
+    #[error("line {line}: {source}")]
+    Encoding {
+        line: usize,
+        source: StateEncodingError<T>,
+    },
+}
+
+/// Whether a state updated to itself (i.e. a variable whose update function evaluates to its
+/// current value) counts as its own successor (and predecessor) under that variable; see
+/// [SmartSystemUpdateFn::successors_with_policy].
+///
+/// The two policies induce different graphs: with [SelfLoopPolicy::Include], every state has
+/// a successor under every variable (possibly itself), so "has no successor" never holds,
+/// whereas with [SelfLoopPolicy::Exclude], the states without any successor are exactly
+/// the [SmartSystemUpdateFn::deadlock_states]. The sets reachable from (or reaching) a set
+/// are the same under both, since a self-loop never leads to a new state. Mixing
+/// the policies (e.g. loop-including successors with loop-excluding predecessors) yields
+/// a graph that is not consistent, so pick one and pass it everywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SelfLoopPolicy {
+    /// a state is its own successor if its update keeps the value of the variable; the default
+    /// of [SmartSystemUpdateFn::successors_async] & [SmartSystemUpdateFn::predecessors_async]
+    #[default]
+    Include,
+    /// only the transitions changing the value of the variable are considered; see
+    /// [SmartSystemUpdateFn::successors_async_exclude_loops]
+    Exclude,
+}
+
+/// The sign of an edge in the influence graph; see [SmartSystemUpdateFn::signed_influence_graph].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Monotonicity {
+    /// increasing the value of the regulator never decreases the value of the target
+    Activation,
+    /// increasing the value of the regulator never increases the value of the target
+    Inhibition,
+    /// the regulator is activating in some contexts (values of the other variables)
+    /// and inhibiting in others
+    Dual,
+    /// the regulator is not monotonic even within a single context, e.g. the target is only
+    /// active for a middle value of a multi-valued regulator
+    Unknown,
+}
+
 // todo maybe use this newtype pattern to better distinguish between primed and unprimed variables (and their domains)
 // /// Wrapper over a SymbolicDomain type.
 // pub struct PrimedDomain<D, T>(D, std::marker::PhantomData<T>)
@@ -342,24 +635,38 @@ where
     /// Returns a list of [BddVariable]-s corresponding to the encoding of the standard
     /// (i.e. "un-primed") system variables.
     pub fn standard_variables(&self) -> Vec<BddVariable> {
+        self.standard_variables.clone()
+    }
+
+    /// The encoding used by the domains of this system, e.g. for logging which encoding
+    /// produced a result.
+    pub fn encoding_kind(&self) -> EncodingKind
+    where
+        D: DomainEncodingKind,
+    {
+        D::encoding_kind()
+    }
+
+    /// Iterates over the system variables and their domains without allocating.
+    ///
+    /// The variables are visited in the order of [SmartSystemUpdateFn::get_system_variables];
+    /// for the order of their encodings within the BDDs, see
+    /// [SmartSystemUpdateFn::variables_in_bdd_order].
+    pub fn iter_domains(&self) -> impl Iterator<Item = (&str, &D)> {
         self.variables_transition_relation_and_domain
             .iter()
-            .flat_map(|it| it.1.domain.raw_bdd_variables())
-            .collect()
+            .map(|(name, info)| (name.as_str(), &info.domain))
     }
 
+    /// The domains of the system variables, in the order of [SmartSystemUpdateFn::iter_domains].
     pub fn standard_domains(&self) -> Vec<&D> {
-        self.variables_transition_relation_and_domain
-            .iter()
-            .map(|it| &it.1.domain)
-            .collect()
+        self.iter_domains().map(|(_, domain)| domain).collect()
     }
 
+    /// The system variables and their domains, in the order of
+    /// [SmartSystemUpdateFn::iter_domains].
     pub fn standard_variables_names_and_domains(&self) -> Vec<(&str, &D)> {
-        self.variables_transition_relation_and_domain
-            .iter()
-            .map(|(name, info)| (name.as_str(), &info.domain))
-            .collect()
+        self.iter_domains().collect()
     }
 
     /// Returns a list of [BddVariable]-s corresponding to the encoding of the "primed"
@@ -371,68 +678,710 @@ where
             .collect()
     }
 
+    /// Returns the name of the system variable (or parameter) encoded using `bdd_variable`,
+    /// the index of `bdd_variable` within the encoding of that variable (i.e. within its
+    /// `raw_bdd_variables`), and whether `bdd_variable` belongs to the primed copy of the variable.
+    ///
+    /// Returns `None` if `bdd_variable` is not used by this system.
+    pub fn describe_variable(&self, bdd_variable: BddVariable) -> Option<(String, usize, bool)> {
+        let position_in = |domain: &D| {
+            domain
+                .raw_bdd_variables()
+                .into_iter()
+                .position(|it| it == bdd_variable)
+        };
+
+        self.variables_transition_relation_and_domain
+            .iter()
+            .find_map(|(name, info)| {
+                position_in(&info.domain)
+                    .map(|bit_index| (name.clone(), bit_index, false))
+                    .or_else(|| {
+                        position_in(&info.primed_domain)
+                            .map(|bit_index| (name.clone(), bit_index, true))
+                    })
+            })
+            .or_else(|| {
+                self.parameters.iter().find_map(|(name, domain)| {
+                    position_in(domain).map(|bit_index| (name.clone(), bit_index, false))
+                })
+            })
+    }
+
     pub fn get_bdd_variable_set(&self) -> &BddVariableSet {
         &self.bdd_variable_set
     }
 
-    /// The list of system variables, sorted in ascending order (i.e. the order in which they
-    /// also appear within the BDDs).1
+    /// Sets the [BddVariableSet] of this system as the context of all its domains (see
+    /// [SymbolicDomain::set_context]), so that they can be used without passing it around.
+    fn with_shared_context(mut self) -> Self {
+        let context = Arc::new(self.bdd_variable_set.clone());
+        for (_, info) in self.variables_transition_relation_and_domain.iter_mut() {
+            info.domain.set_context(context.clone());
+            info.primed_domain.set_context(context.clone());
+        }
+        for (_, domain) in self.parameters.iter_mut() {
+            domain.set_context(context.clone());
+        }
+        self
+    }
+
+    /// Assembles a system from its variables (in the order of
+    /// [SmartSystemUpdateFn::get_system_variables]) and parameters, whose domains are allocated
+    /// in `bdd_variable_set`; the remaining fields are derived from these.
+    fn from_parts(
+        variables_transition_relation_and_domain: Vec<(String, VarInfo<D, T>)>,
+        bdd_variable_set: BddVariableSet,
+        parameters: Vec<(String, D)>,
+    ) -> Self {
+        let unit_vertex_set = variables_transition_relation_and_domain
+            .iter()
+            .map(|(_, info)| &info.domain)
+            .chain(parameters.iter().map(|(_, domain)| domain))
+            .fold(bdd_variable_set.mk_true(), |acc, domain| {
+                acc.and(&domain.unit_collection(&bdd_variable_set))
+            });
+
+        let mapper = variables_transition_relation_and_domain
+            .iter()
+            .enumerate()
+            .map(|(idx, (var_name, _))| (var_name.clone(), idx))
+            .collect();
+        let standard_variables = variables_transition_relation_and_domain
+            .iter()
+            .flat_map(|(_, info)| info.domain.raw_bdd_variables())
+            .collect();
+        let variable_names = variables_transition_relation_and_domain
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        Self {
+            mapper,
+            variables_transition_relation_and_domain,
+            bdd_variable_set,
+            unit_vertex_set,
+            standard_variables,
+            variable_names,
+            parameters,
+            _marker: std::marker::PhantomData,
+        }
+        .with_shared_context()
+    }
+
+    /// The list of system variables, sorted in ascending order (the order of
+    /// [SmartSystemUpdateFn::iter_domains]).
+    ///
+    /// This is usually also the order in which the variables appear within the BDDs, but not
+    /// for the systems built by [SmartSystemUpdateFn::from_update_fns_with_priorities], and
+    /// a variable renamed by [SmartSystemUpdateFn::rename_variable] keeps its position within
+    /// the BDDs while moving to its new place in this list. Algorithms that depend on
+    /// the position of the variables within the BDDs should use
+    /// [SmartSystemUpdateFn::variables_in_bdd_order] instead.
     pub fn get_system_variables(&self) -> Vec<String> {
-        self.variables_transition_relation_and_domain
+        self.variable_names.clone()
+    }
+
+    /// The list of system variables in the order of their encodings within the BDDs, i.e.
+    /// the variable whose domain starts closest to the root of the BDDs comes first.
+    ///
+    /// Unlike [SmartSystemUpdateFn::get_system_variables], this order is read from
+    /// the [BddVariable]-s of the domains, so it stays correct even if the domains are not
+    /// allocated in the order of the names. Variables whose domains have no bits
+    /// do not appear within the BDDs; these come first.
+    pub fn variables_in_bdd_order(&self) -> Vec<String> {
+        let mut variables = self
+            .iter_domains()
+            .map(|(name, domain)| (domain.raw_bdd_variables().into_iter().min(), name))
+            .collect::<Vec<_>>();
+        // stable, so that the variables without bits keep their relative order
+        variables.sort_by_key(|(first_bit, _)| *first_bit);
+        variables
+            .into_iter()
+            .map(|(_, name)| name.to_string())
+            .collect()
+    }
+
+    /// Like [SmartSystemUpdateFn::get_system_variables], but borrows the names.
+    pub(crate) fn variable_names(&self) -> &[String] {
+        &self.variable_names
+    }
+
+    /// The list of parameters of the system, sorted in ascending order; see
+    /// [SmartSystemUpdateFn::from_update_fns_with_parameters].
+    pub fn get_parameters(&self) -> Vec<String> {
+        self.parameters
+            .iter()
+            .map(|(parameter_name, _)| parameter_name.to_owned())
+            .collect()
+    }
+
+    /// Returns a list of [BddVariable]-s corresponding to the encoding of the parameters.
+    pub fn parameter_variables(&self) -> Vec<BddVariable> {
+        self.parameters
             .iter()
-            .map(|(var_name, _)| var_name.to_owned())
+            .flat_map(|(_, domain)| domain.raw_bdd_variables())
             .collect()
     }
 
+    pub fn get_parameter_domain(&self, parameter_name: &str) -> Option<&D> {
+        self.parameters
+            .iter()
+            .find(|(maybe_parameter_name, _)| maybe_parameter_name == parameter_name)
+            .map(|(_, domain)| domain)
+    }
+
+    /// Encode the set of all the colours (valuations of the parameters) under which
+    /// `parameter_name` has the given `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such parameter.
+    pub fn encode_parameter(&self, parameter_name: &str, value: &T) -> Bdd {
+        self.get_parameter_domain(parameter_name)
+            .expect("unknown parameter")
+            .encode_one(&self.bdd_variable_set, value)
+    }
+
     pub fn get_domain(&self, variable_name: &str) -> Option<&D> {
         self.mapper
             .get(variable_name)
             .map(|idx| &self.variables_transition_relation_and_domain[*idx].1.domain)
     }
 
+    /// The domain of the primed copy of the system variable `variable_name`, i.e. the one
+    /// encoding the value of the variable after a transition; see
+    /// [SmartSystemUpdateFn::get_domains].
+    pub fn get_primed_domain(&self, variable_name: &str) -> Option<&D> {
+        self.get_domains(variable_name)
+            .map(|(_, primed_domain)| primed_domain)
+    }
+
+    /// The `(domain, primed_domain)` of the system variable `variable_name`.
+    ///
+    /// Both domains use the same encoding of the same values, i.e. they have the same number of
+    /// bits, and the `k`-th of the `raw_bdd_variables` of the primed domain corresponds to the
+    /// `k`-th of the un-primed one. Hence, renaming these pairs (zipped) turns a set over one of
+    /// them into the same set over the other, which is how the transition relations are applied.
+    pub fn get_domains(&self, variable_name: &str) -> Option<(&D, &D)> {
+        self.mapper.get(variable_name).map(|idx| {
+            let info = &self.variables_transition_relation_and_domain[*idx].1;
+            (&info.domain, &info.primed_domain)
+        })
+    }
+
+    /// The set of the valid states in which the update function of the system variable
+    /// `variable_name` outputs `value`, or `None` if there is no such variable.
+    pub(crate) fn update_condition(&self, variable_name: &str, value: &T) -> Option<Bdd> {
+        let idx = *self.mapper.get(variable_name)?;
+        let info = &self.variables_transition_relation_and_domain[idx].1;
+        let updated = info
+            .transition_relation
+            .and(&info.primed_domain.encode_one(&self.bdd_variable_set, value));
+        Some(
+            updated
+                .exists(&info.primed_domain.raw_bdd_variables())
+                .and(&self.unit_vertex_set),
+        )
+    }
+
     /// Compute the [Bdd] which represents the set of all vertices admissible in this
     /// [SmartSystemUpdateFn]. Normally, this would just be the `true` BDD, but if the
     /// encoding contains some invalid values, these need to be excluded.
     ///
-    /// Note that this only concerns the "standard" system variables. The resulting BDD
-    /// does not depend on the "primed" system variables.
+    /// Note that this only concerns the "standard" system variables (and the parameters, if any).
+    /// The resulting BDD does not depend on the "primed" system variables.
     pub fn unit_vertex_set(&self) -> Bdd {
-        self.variables_transition_relation_and_domain
-            .iter()
-            .fold(self.bdd_variable_set.mk_true(), |acc, it| {
-                acc.and(&it.1.domain.unit_collection(&self.bdd_variable_set))
-            })
+        self.unit_vertex_set.clone()
     }
 
-    /// Compute an (approximate) count of state in the given `set` using the encoding of `system`.
-    pub fn count_states(&self, set: &Bdd) -> f64 {
-        let symbolic_var_count = self.variables_transition_relation_and_domain.len() as i32;
-        set.cardinality() / 2.0f64.powi(symbolic_var_count)
+    /// Like [SmartSystemUpdateFn::unit_vertex_set], but borrows the set instead of cloning it.
+    ///
+    /// The conjunction of the unit collections of all the domains is computed once, when the
+    /// system is built, so this is free to call in hot loops.
+    pub fn cached_unit(&self) -> &Bdd {
+        &self.unit_vertex_set
     }
 
-    /// Compute a [Bdd] which represents a single (un-primed) state within the given symbolic `set`.
-    pub fn pick_state_bdd(&self, set: &Bdd) -> Bdd {
-        // Unfortunately, this is now a bit more complicated than it needs to be, because
-        // we have to ignore the primed variables, but it shouldn't bottleneck anything outside of
-        // truly extreme cases.
-        let standard_variables = self
+    /// Restrict the given `set` to the valid states of this system, i.e. intersect it with
+    /// [SmartSystemUpdateFn::unit_vertex_set].
+    ///
+    /// Any set produced by raw `lib-bdd` operations (`not`, `or` with foreign sets, `exists`, ...)
+    /// may contain valuations that do not encode any value of some domain (e.g. value `3` of
+    /// a binary domain with max value `2`). Such sets should be sanitized before counting or
+    /// decoding their states.
+    pub fn sanitize(&self, set: &Bdd) -> Bdd {
+        set.and(&self.unit_vertex_set)
+    }
+
+    /// Returns `true` iff the `set` contains no valid state of this system, i.e. it is empty
+    /// once [sanitized](SmartSystemUpdateFn::sanitize).
+    pub fn is_empty(&self, set: &Bdd) -> bool {
+        self.sanitize(set).is_false()
+    }
+
+    /// Returns `true` iff the `set` contains every valid state of this system, i.e. all of
+    /// [SmartSystemUpdateFn::unit_vertex_set]; the invalid encodings are ignored.
+    ///
+    /// Use this instead of `set.is_true()`, which fails for the sets of the encodings having
+    /// invalid valuations (e.g. the binary one), since `unit_vertex_set` is not `true` for them.
+    pub fn is_universe(&self, set: &Bdd) -> bool {
+        self.unit_vertex_set.imp(set).is_true()
+    }
+
+    /// Encode the state given by the `assignment` of values to all the system variables, i.e. a
+    /// [Bdd] containing exactly this single state.
+    ///
+    /// Fails if some variable of the system is not assigned, if the assignment contains
+    /// an unknown variable, or if some value is out of the domain of its variable.
+    pub fn encode_state(
+        &self,
+        assignment: &HashMap<String, T>,
+    ) -> Result<Bdd, StateEncodingError<T>>
+    where
+        D: SymbolicDomainOrd<T>,
+        T: Clone,
+    {
+        if let Some((missing, _)) = self
             .variables_transition_relation_and_domain
             .iter()
-            .flat_map(|transition| transition.1.domain.raw_bdd_variables());
-        let valuation = set
-            .sat_witness()
-            .expect("Cannot pick state from an empty set.");
-        let mut state_data = BddPartialValuation::empty();
-        for var in standard_variables {
-            state_data.set_value(var, valuation.value(var))
+            .find(|(name, _)| !assignment.contains_key(name))
+        {
+            return Err(StateEncodingError::MissingVariable(missing.clone()));
         }
-        self.bdd_variable_set.mk_conjunctive_clause(&state_data)
-    }
 
-    pub fn log_percent(set: &Bdd, universe: &Bdd) -> f64 {
-        set.cardinality().log2() / universe.cardinality().log2() * 100.0
+        self.encode_subspace(assignment)
     }
-}
+
+    /// Encode the subspace in which the variables of `fixed` have the given values, while
+    /// the remaining variables can have any (valid) value. Unlike
+    /// [SmartSystemUpdateFn::encode_state], the assignment does not need to be total.
+    ///
+    /// Fails if the assignment contains an unknown variable, or if some value is out of
+    /// the domain of its variable.
+    pub fn encode_subspace(&self, fixed: &HashMap<String, T>) -> Result<Bdd, StateEncodingError<T>>
+    where
+        D: SymbolicDomainOrd<T>,
+        T: Clone,
+    {
+        // sorted, so that the reported error does not depend on the order of the hash map
+        let mut sorted_fixed = fixed.iter().collect::<Vec<_>>();
+        sorted_fixed.sort_unstable_by_key(|(name, _)| *name);
+
+        let mut valuation = BddPartialValuation::empty();
+        for (name, value) in sorted_fixed {
+            self.get_domain(name)
+                .ok_or_else(|| StateEncodingError::UnknownVariable(name.clone()))?
+                .try_encode_bits(&mut valuation, value)?;
+        }
+
+        Ok(self
+            .bdd_variable_set
+            .mk_conjunctive_clause(&valuation)
+            .and(&self.unit_vertex_set))
+    }
+
+    /// Returns a copy of this system in which only the transitions between the states of
+    /// `safe` are kept, i.e. every transition that would start or end outside of `safe` is
+    /// disabled. Hence, anything reachable from `safe` in the resulting system stays in `safe`.
+    ///
+    /// Unlike [SmartSystemUpdateFn::restrict_to], this modifies the transition relations
+    /// themselves, so the result can be passed to any algorithm working with a system.
+    pub fn constrain_transitions(&self, safe: &Bdd) -> Self
+    where
+        D: Clone,
+    {
+        // a set of states; the primed variables must be free, so that they can be renamed into
+        let safe = self.sanitize(&safe.exists(&self.primed_variables()));
+        let variables_transition_relation_and_domain = self
+            .variables_transition_relation_and_domain
+            .iter()
+            .map(|(var_name, info)| {
+                // the relation only speaks about the primed copy of the updated variable;
+                // the other variables of the target state keep their (un-primed) values
+                let safe_target = self.prime(&safe, info);
+                (
+                    var_name.clone(),
+                    VarInfo {
+                        primed_name: info.primed_name.clone(),
+                        domain: info.domain.clone(),
+                        primed_domain: info.primed_domain.clone(),
+                        transition_relation: info.transition_relation.and(&safe).and(&safe_target),
+                        default_output: info.default_output.clone(),
+                        _marker: std::marker::PhantomData,
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            mapper: self.mapper.clone(),
+            variables_transition_relation_and_domain,
+            bdd_variable_set: self.bdd_variable_set.clone(),
+            unit_vertex_set: self.unit_vertex_set.clone(),
+            standard_variables: self.standard_variables.clone(),
+            variable_names: self.variable_names.clone(),
+            parameters: self.parameters.clone(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Returns the same system, but with the variable `old` called `new`.
+    ///
+    /// Only the names used by the public API change: the BDDs (including the names of the
+    /// [BddVariable]-s, which are internal) are kept as they are, so the sets of this system
+    /// are valid in the renamed one. The variables are then re-sorted by name, so the renamed
+    /// variable may move within [SmartSystemUpdateFn::get_system_variables]; its encoding keeps
+    /// its position within the BDDs (see [SmartSystemUpdateFn::variables_in_bdd_order]).
+    pub fn rename_variable(&self, old: &str, new: &str) -> Result<Self, RenameError>
+    where
+        D: Clone,
+    {
+        let renamed_idx = *self
+            .mapper
+            .get(old)
+            .ok_or_else(|| RenameError::UnknownVariable(old.to_string()))?;
+        let is_taken = (new != old && self.mapper.contains_key(new))
+            || self.parameters.iter().any(|(name, _)| name == new);
+        if is_taken {
+            return Err(RenameError::NameTaken(new.to_string()));
+        }
+
+        let mut variables_transition_relation_and_domain = self
+            .variables_transition_relation_and_domain
+            .iter()
+            .enumerate()
+            .map(|(idx, (var_name, info))| {
+                let (var_name, primed_name) = if idx == renamed_idx {
+                    (new.to_string(), format!("{}'", internal_name(new)))
+                } else {
+                    (var_name.clone(), info.primed_name.clone())
+                };
+                (
+                    var_name,
+                    VarInfo {
+                        primed_name,
+                        domain: info.domain.clone(),
+                        primed_domain: info.primed_domain.clone(),
+                        transition_relation: info.transition_relation.clone(),
+                        default_output: info.default_output.clone(),
+                        _marker: std::marker::PhantomData,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        variables_transition_relation_and_domain.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+
+        Ok(Self::from_parts(
+            variables_transition_relation_and_domain,
+            self.bdd_variable_set.clone(),
+            self.parameters.clone(),
+        ))
+    }
+
+    /// Replaces the (un-primed) variables of the domain of `info` within `set` by their primed
+    /// copies; the remaining variables are left untouched.
+    fn prime(&self, set: &Bdd, info: &VarInfo<D, T>) -> Bdd {
+        rename_domain(
+            set.clone(),
+            &info.domain.raw_bdd_variables(),
+            &info.primed_domain.raw_bdd_variables(),
+        )
+    }
+
+    /// Returns a view of this system restricted to the states of `universe`, i.e. a system
+    /// whose successors/predecessors never leave the `universe`. Useful e.g. for running
+    /// reachability within a trap space without having to intersect every step manually.
+    pub fn restrict_to(&self, universe: &Bdd) -> RestrictedSystem<'_, D, T> {
+        RestrictedSystem::new(self, universe)
+    }
+
+    /// Compute the complement of the given `set` within the valid states of this system, i.e.
+    /// [SmartSystemUpdateFn::unit_vertex_set] minus `set`.
+    ///
+    /// **Always prefer this to [Bdd::not].** Unless the unit collection of every domain is
+    /// the `true` BDD (which is not the case e.g. for binary or gray encodings of a domain whose
+    /// size is not a power of two), the plain negation also contains the invalid valuations,
+    /// which then corrupt any subsequent state counting or decoding.
+    pub fn complement(&self, set: &Bdd) -> Bdd {
+        self.unit_vertex_set.and_not(set)
+    }
+
+    /// Compute the set of the valid states that belong to exactly one of `a` and `b`.
+    ///
+    /// Like with [SmartSystemUpdateFn::complement], prefer this to [Bdd::xor], which may
+    /// contain the invalid valuations.
+    pub fn symmetric_difference(&self, a: &Bdd, b: &Bdd) -> Bdd {
+        a.xor(b).and(&self.unit_vertex_set)
+    }
+
+    /// True iff every valid state of `a` is also a state of `b`. The invalid valuations
+    /// (which are not states of the system) are ignored.
+    pub fn is_subset(&self, a: &Bdd, b: &Bdd) -> bool {
+        a.and(&self.unit_vertex_set).imp(b).is_true()
+    }
+
+    /// Compute the union of all the `sets` (the empty set if there are none). The result is
+    /// sanitized, so it is a valid set of states even if some of the `sets` are not.
+    ///
+    /// The sets are combined pairwise in a balanced manner, which tends to keep the
+    /// intermediate BDDs smaller than a linear fold when there are many of them.
+    pub fn union_all<'a>(&self, sets: impl IntoIterator<Item = &'a Bdd>) -> Bdd {
+        balanced_fold(sets.into_iter().cloned().collect(), &Bdd::or)
+            .map(|union| union.and(&self.unit_vertex_set))
+            .unwrap_or_else(|| self.bdd_variable_set.mk_false())
+    }
+
+    /// Compute the intersection of all the `sets` (the unit set if there are none). The result
+    /// is sanitized, like with [SmartSystemUpdateFn::union_all].
+    pub fn intersect_all<'a>(&self, sets: impl IntoIterator<Item = &'a Bdd>) -> Bdd {
+        balanced_fold(sets.into_iter().cloned().collect(), &Bdd::and)
+            .map(|intersection| intersection.and(&self.unit_vertex_set))
+            .unwrap_or_else(|| self.unit_vertex_set.clone())
+    }
+
+    /// Compute the set of states that agree with some state of `set` on the value of variable
+    /// `variable_name`; i.e. the values of all the other variables (standard or primed) are
+    /// existentially quantified away. The result is sanitized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if variable with the given name is not available.
+    pub fn project_to_variable(&self, set: &Bdd, variable_name: &str) -> Bdd {
+        let kept_variables = self
+            .get_domain(variable_name)
+            .expect("unknown variable")
+            .raw_bdd_variables();
+
+        let other_variables = self
+            .bdd_variable_set
+            .variables()
+            .into_iter()
+            .filter(|var| !kept_variables.contains(var))
+            .collect::<Vec<_>>();
+
+        self.sanitize(&set.exists(&other_variables))
+    }
+
+    /// The (valid) states of `set` with all the other (primed and parameter) variables fixed to
+    /// `false`, so that each satisfying valuation of the result is exactly one state; e.g. for
+    /// enumerating the states using [Bdd::sat_valuations].
+    pub fn project_to_states(&self, set: &Bdd) -> Bdd {
+        project_onto_encoding(
+            &self.bdd_variable_set,
+            &self.standard_variables,
+            &self.sanitize(set),
+        )
+    }
+
+    /// Returns the variables that can never change their value by themselves, in the order
+    /// of [SmartSystemUpdateFn::get_system_variables].
+    ///
+    /// A variable whose update function yields the same value in every (valid) state is
+    /// reported as `Some(value)`. A variable whose update function always yields its current
+    /// value (i.e. the update function is the identity) is reported as `None`.
+    ///
+    /// Such variables can be removed from the system (fixing their value) before running
+    /// more expensive analyses.
+    pub fn constant_variables(&self) -> Vec<(String, Option<T>)> {
+        let unit = &self.unit_vertex_set;
+
+        self.variables_transition_relation_and_domain
+            .iter()
+            .filter_map(|(name, info)| {
+                let all_values = info.domain.decode_collection(
+                    &self.bdd_variable_set,
+                    &info.domain.unit_collection(&self.bdd_variable_set),
+                );
+
+                // the primed bits do not depend on any un-primed bit; there is a single
+                // possible result and the relation allows it from every valid state
+                let constant = all_values.into_iter().find(|value| {
+                    let fixed = info.primed_domain.encode_one(&self.bdd_variable_set, value);
+                    info.transition_relation == unit.and(&fixed)
+                });
+                if constant.is_some() {
+                    return Some((name.clone(), constant));
+                }
+
+                self.is_identity(info).then(|| (name.clone(), None))
+            })
+            .collect()
+    }
+
+    /// Returns the "input" variables of the system, i.e. the variables whose update function
+    /// is the identity (`f(x) = x`), in the order of [SmartSystemUpdateFn::get_system_variables].
+    ///
+    /// Every state is a fixed point under such a variable; the variable keeps whatever value
+    /// it starts with. Note that a variable with a single-value domain is both constant
+    /// and an input.
+    pub fn input_variables(&self) -> Vec<String> {
+        self.variables_transition_relation_and_domain
+            .iter()
+            .filter(|(_, info)| self.is_identity(info))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// True iff the transition relation forces the primed value of the variable to equal
+    /// its un-primed value in every valid state.
+    fn is_identity(&self, info: &VarInfo<D, T>) -> bool {
+        let preserving = info
+            .domain
+            .raw_bdd_variables()
+            .into_iter()
+            .zip(info.primed_domain.raw_bdd_variables())
+            .fold(self.unit_vertex_set.clone(), |acc, (unprimed, primed)| {
+                acc.and(
+                    &self
+                        .bdd_variable_set
+                        .mk_var(unprimed)
+                        .iff(&self.bdd_variable_set.mk_var(primed)),
+                )
+            });
+        info.transition_relation == preserving
+    }
+
+    /// Returns the edges `(regulator, target)` of the influence (regulatory) graph; there is
+    /// an edge `u -> v` iff the transition relation of `v` actually depends on the value
+    /// of `u`. Edges are ordered by the target, then by the regulator (both in the order of
+    /// [SmartSystemUpdateFn::get_system_variables]).
+    ///
+    /// Note that this is a semantic check, so a variable that is declared as an input of
+    /// an update function (or is even mentioned in it), but whose value never influences
+    /// the result, is not considered a regulator.
+    pub fn influence_graph(&self) -> Vec<(String, String)> {
+        self.variables_transition_relation_and_domain
+            .iter()
+            .flat_map(|(target, target_info)| {
+                self.variables_transition_relation_and_domain
+                    .iter()
+                    .filter(|(_, regulator_info)| {
+                        Self::depends_on(
+                            &self.bdd_variable_set,
+                            &target_info.transition_relation,
+                            &regulator_info.domain,
+                        )
+                    })
+                    .map(|(regulator, _)| (regulator.clone(), target.clone()))
+            })
+            .collect()
+    }
+
+    /// True iff `relation` depends on the value encoded by `domain`.
+    fn depends_on(bdd_variable_set: &BddVariableSet, relation: &Bdd, domain: &D) -> bool {
+        // the relation contains the unit set of every variable; that must be restored,
+        // otherwise the invalid values would make it look like a dependency
+        let forgotten = relation
+            .exists(&domain.raw_bdd_variables())
+            .and(&domain.unit_collection(bdd_variable_set));
+        &forgotten != relation
+    }
+
+    /// Compute an (approximate) count of state in the given `set` using the encoding of `system`.
+    pub fn count_states(&self, set: &Bdd) -> f64 {
+        let symbolic_var_count = self.variables_transition_relation_and_domain.len() as i32;
+        set.cardinality() / 2.0f64.powi(symbolic_var_count)
+    }
+
+    /// Classify the (valid) states of `set` as none, exactly one, or many, using the exact
+    /// cardinality rather than the approximation of [SmartSystemUpdateFn::count_states].
+    ///
+    /// If the system has parameters, a state is counted once, regardless of the number
+    /// of parameter valuations it appears with.
+    pub fn state_count_category(&self, set: &Bdd) -> StateCount {
+        let other_variables = self
+            .bdd_variable_set
+            .variables()
+            .into_iter()
+            .filter(|var| !self.standard_variables.contains(var))
+            .collect::<Vec<_>>();
+        let states = self.sanitize(set).exists(&other_variables);
+        if states.is_false() {
+            return StateCount::Empty;
+        }
+
+        // the states do not depend on the other variables, so each of them is counted
+        // once for every valuation of these
+        let count = states.exact_cardinality() >> other_variables.len();
+        if count == BigInt::from(1) {
+            StateCount::Single
+        } else {
+            StateCount::Many(count)
+        }
+    }
+
+    /// Count the valuations of the (un-primed) variables and parameters within `set` that
+    /// do not encode any valid state, i.e. those outside of the
+    /// [SmartSystemUpdateFn::unit_vertex_set]. The primed variables are ignored.
+    ///
+    /// A sanitized set always has zero such valuations; a non-zero count signals that
+    /// the set was produced by an operation that does not sanitize its result (typically
+    /// [Bdd::not]; see [SmartSystemUpdateFn::complement]).
+    pub fn invalid_encoding_count(&self, set: &Bdd) -> BigInt {
+        let primed_variables = self.primed_variables();
+        // each invalid valuation is counted once for every valuation of the primed variables
+        set.and_not(&self.unit_vertex_set)
+            .exists(&primed_variables)
+            .exact_cardinality()
+            >> primed_variables.len()
+    }
+
+    /// Compute a [Bdd] which represents a single (un-primed) state within the given symbolic `set`.
+    pub fn pick_state_bdd(&self, set: &Bdd) -> Bdd {
+        // Unfortunately, this is now a bit more complicated than it needs to be, because
+        // we have to ignore the primed variables, but it shouldn't bottleneck anything outside of
+        // truly extreme cases.
+        let valuation = set
+            .sat_witness()
+            .expect("Cannot pick state from an empty set.");
+        let mut state_data = BddPartialValuation::empty();
+        for var in &self.standard_variables {
+            state_data.set_value(*var, valuation.value(*var))
+        }
+        self.bdd_variable_set.mk_conjunctive_clause(&state_data)
+    }
+
+    /// Explicitly enumerate the `edges` (as produced by [SmartSystemUpdateFn::successor_edges])
+    /// as pairs of (source state, target state). Meant for exporting small models, since
+    /// the number of edges can be exponential in the number of variables.
+    ///
+    /// If the system has parameters, the edges present under any of the parameter valuations
+    /// are enumerated (once).
+    pub fn decode_edges(&self, edges: &Bdd) -> Vec<(HashMap<String, T>, HashMap<String, T>)> {
+        let parameter_variables = self.parameter_variables();
+        let edges = edges.exists(&parameter_variables).select(
+            &parameter_variables
+                .into_iter()
+                .map(|var| (var, false))
+                .collect::<Vec<_>>(),
+        );
+
+        edges
+            .sat_valuations()
+            .map(|valuation| {
+                self.variables_transition_relation_and_domain
+                    .iter()
+                    .map(|(name, info)| {
+                        (
+                            (name.clone(), decode_valuation(&info.domain, &valuation)),
+                            (
+                                name.clone(),
+                                decode_valuation(&info.primed_domain, &valuation),
+                            ),
+                        )
+                    })
+                    .unzip()
+            })
+            .collect()
+    }
+
+    /// The size of `set` relative to `universe` on a logarithmic scale, in percent. The empty
+    /// set is at `0.0`; a non-empty set within a universe of a single valuation is at `100.0`.
+    pub fn log_percent(set: &Bdd, universe: &Bdd) -> f64 {
+        crate::utils::log_percent(set, universe)
+    }
+}
 
 impl<DO, T> SmartSystemUpdateFn<DO, T>
 where
@@ -441,11 +1390,76 @@ where
     pub fn from_update_fns(
         vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
     ) -> Self {
-        vars_and_their_update_fns.iter().for_each(|(name, _)| {
-            if name.contains('\'') {
-                panic!("variable name cannot contain the prime symbol \"'\" (tick) - it is reserved for inner usage")
-            }
-        });
+        Self::from_update_fns_with_parameters(vars_and_their_update_fns, &[])
+    }
+
+    /// Like [SmartSystemUpdateFn::from_update_fns], but the variables named in `parameters` are
+    /// treated as unknown parameters of the update functions rather than as system variables.
+    ///
+    /// A parameter has no update function and no primed counterpart; the propositions referencing
+    /// it are encoded using free [BddVariable]-s of its own domain (its max value is inferred
+    /// from these propositions). Since no transition ever changes a parameter, each set of
+    /// states also tracks the parameter valuations ("colours") under which the states are
+    /// present, allowing parameter synthesis in the style of `lib-param-bn`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some parameter has an update function, or is not referenced by any.
+    pub fn from_update_fns_with_parameters(
+        vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
+        parameters: &[String],
+    ) -> Self {
+        Self::from_update_fns_with_parameters_and_priorities(
+            vars_and_their_update_fns,
+            parameters,
+            &HashMap::new(),
+        )
+    }
+
+    /// Like [SmartSystemUpdateFn::from_update_fns], but the domains of the variables are
+    /// allocated in the order of their `priorities`: the variables with a higher priority are
+    /// allocated earlier, i.e. closer to the root of the BDDs. The variables missing from
+    /// the map have priority `0`, and the variables of the same priority are allocated in
+    /// the ascending order of their names (so an empty map yields the default order).
+    ///
+    /// This is a performance-tuning hook: placing the bits of strongly interacting variables
+    /// close to each other can make the BDDs (much) smaller. The priorities only affect
+    /// the sizes of the BDDs and the running times, never the results of any operation
+    /// (up to the [BddVariable]-s encoding them). Use
+    /// [SmartSystemUpdateFn::variables_in_bdd_order] to observe the resulting order; note that
+    /// [SmartSystemUpdateFn::get_system_variables] stays sorted by name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `priorities` contains a name that is not a variable of the system.
+    pub fn from_update_fns_with_priorities(
+        vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
+        priorities: &HashMap<String, usize>,
+    ) -> Self {
+        Self::from_update_fns_with_parameters_and_priorities(
+            vars_and_their_update_fns,
+            &[],
+            priorities,
+        )
+    }
+
+    fn from_update_fns_with_parameters_and_priorities(
+        vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
+        parameters: &[String],
+        priorities: &HashMap<String, usize>,
+    ) -> Self {
+        if let Some(unknown) = priorities
+            .keys()
+            .find(|var_name| !vars_and_their_update_fns.contains_key(*var_name))
+        {
+            panic!("cannot prioritize `{}`, which is not a variable", unknown)
+        }
+        if let Some(parameter) = parameters
+            .iter()
+            .find(|parameter| vars_and_their_update_fns.contains_key(*parameter))
+        {
+            panic!("parameter `{}` cannot have an update function", parameter)
+        }
 
         let named_update_fns_sorted = {
             let mut to_be_sorted = vars_and_their_update_fns.into_iter().collect::<Vec<_>>();
@@ -453,40 +1467,175 @@ where
             to_be_sorted
         };
 
-        let (named_symbolic_domains, bdd_variable_set) = {
-            let max_values = find_max_values::<DO, T>(&named_update_fns_sorted);
-            let mut bdd_variable_set_builder = BddVariableSetBuilder::new();
+        let (named_symbolic_domains, named_parameter_domains, domain_builder) = {
+            let max_values = find_max_values::<DO, T, _>(
+                named_update_fns_sorted
+                    .iter()
+                    .map(|(var_name, update_fn)| (var_name, update_fn)),
+            );
+            let mut domain_builder = DomainBuilder::<DO, T>::new();
 
-            // let (symbolic_domains, variable_set_builder) =
-            let named_symbolic_domains = named_update_fns_sorted
-                .iter()
+            // allocated in the order of the priorities (stable, so by name within a priority),
+            // but kept in the order of the names
+            let mut allocation_order = named_update_fns_sorted.iter().collect::<Vec<_>>();
+            allocation_order.sort_by_key(|(var_name, _)| {
+                std::cmp::Reverse(priorities.get(var_name).copied().unwrap_or_default())
+            });
+            let mut allocated_domains = allocation_order
+                .into_iter()
                 .map(|(var_name, _)| {
                     let max_value = max_values
                         .get(var_name.as_str())
                         .expect("max value always present");
 
                     let original_name = var_name.clone();
-                    let primed_name = format!("{}'", var_name);
+                    let primed_name = format!("{}'", internal_name(var_name));
 
                     let original =
-                        DO::new(&mut bdd_variable_set_builder, &original_name, max_value);
-                    let primed = DO::new(&mut bdd_variable_set_builder, &primed_name, max_value);
+                        domain_builder.make_domain(&internal_name(&original_name), max_value);
+                    let primed = domain_builder.make_domain(&primed_name, max_value);
 
-                    ((original_name, original), (primed_name, primed))
+                    (
+                        var_name.as_str(),
+                        ((original_name, original), (primed_name, primed)),
+                    )
+                })
+                .collect::<HashMap<_, _>>();
+            let named_symbolic_domains = named_update_fns_sorted
+                .iter()
+                .map(|(var_name, _)| {
+                    allocated_domains
+                        .remove(var_name.as_str())
+                        .expect("domain always allocated")
                 })
                 .collect::<Vec<_>>();
 
-            (named_symbolic_domains, bdd_variable_set_builder.build())
+            let named_parameter_domains = {
+                let mut sorted = parameters.to_vec();
+                sorted.sort_unstable();
+                sorted.dedup();
+                sorted
+            }
+            .into_iter()
+            .map(|parameter_name| {
+                let max_value = max_values.get(parameter_name.as_str()).unwrap_or_else(|| {
+                    panic!(
+                        "parameter `{}` is not referenced by any update function",
+                        parameter_name
+                    )
+                });
+                let domain = domain_builder.make_domain(&internal_name(&parameter_name), max_value);
+                (parameter_name, domain)
+            })
+            .collect::<Vec<_>>();
+
+            (
+                named_symbolic_domains,
+                named_parameter_domains,
+                domain_builder,
+            )
         };
 
-        let named_symbolic_domains_map = named_symbolic_domains
+        Self::from_update_fns_and_domains(
+            named_update_fns_sorted,
+            named_symbolic_domains,
+            named_parameter_domains,
+            domain_builder.build(),
+            &domain_builder,
+        )
+    }
+
+    /// Like [SmartSystemUpdateFn::from_update_fns], but the (un-primed) `domains` and the
+    /// `primed_domains` of the variables are not allocated, but supplied by the caller
+    /// (both keyed by the name of the variable), together with the `bdd_variable_set` they
+    /// were built in. Only the transition relations are compiled.
+    ///
+    /// This allows multiple systems to share the same [BddVariableSet], so that their BDDs
+    /// can be freely combined.
+    pub fn from_update_fns_with_context(
+        vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
+        mut domains: HashMap<String, DO>,
+        mut primed_domains: HashMap<String, DO>,
+        bdd_variable_set: BddVariableSet,
+    ) -> Result<Self, BuildError> {
+        let named_update_fns_sorted = {
+            let mut to_be_sorted = vars_and_their_update_fns.into_iter().collect::<Vec<_>>();
+            to_be_sorted.sort_by_key(|(var_name, _)| var_name.clone());
+            to_be_sorted
+        };
+
+        let mut referenced_variables = named_update_fns_sorted
             .iter()
-            .flat_map(|((var_name, domain), (primed_var_name, primed_domain))| {
-                [
-                    (var_name.as_str(), domain),
-                    (primed_var_name.as_str(), primed_domain),
-                ]
+            .flat_map(|(_, update_fn)| update_fn.referenced_variables())
+            .filter(|var_name| {
+                named_update_fns_sorted
+                    .binary_search_by(|(it, _)| it.as_str().cmp(var_name))
+                    .is_err()
+            })
+            .collect::<Vec<_>>();
+        referenced_variables.sort_unstable();
+        if let Some(var_name) = referenced_variables.first() {
+            return Err(BuildError::MissingUpdateFunction(var_name.to_string()));
+        }
+
+        let is_within_variable_set = |domain: &DO| {
+            domain
+                .raw_bdd_variables()
+                .into_iter()
+                .all(|var| var.to_index() < usize::from(bdd_variable_set.num_vars()))
+        };
+
+        let named_symbolic_domains = named_update_fns_sorted
+            .iter()
+            .map(|(var_name, _)| {
+                let domain = domains
+                    .remove(var_name)
+                    .ok_or_else(|| BuildError::MissingDomain(var_name.clone()))?;
+                let primed_domain = primed_domains
+                    .remove(var_name)
+                    .ok_or_else(|| BuildError::MissingPrimedDomain(var_name.clone()))?;
+                if !is_within_variable_set(&domain) || !is_within_variable_set(&primed_domain) {
+                    return Err(BuildError::ForeignDomain(var_name.clone()));
+                }
+
+                let primed_name = format!("{}'", internal_name(var_name));
+                Ok(((var_name.clone(), domain), (primed_name, primed_domain)))
             })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::from_update_fns_and_domains(
+            named_update_fns_sorted,
+            named_symbolic_domains,
+            Vec::new(),
+            bdd_variable_set,
+            &DomainBuilder::new(),
+        ))
+    }
+
+    /// Compiles the transition relations of the update functions (sorted by the name of their
+    /// variable) using the already allocated domains (in the same order). The unit collections
+    /// of the domains are obtained from the `domain_builder` (which falls back to computing
+    /// them for the domains it did not make).
+    fn from_update_fns_and_domains(
+        named_update_fns_sorted: Vec<(String, UnprocessedVariableUpdateFn<T>)>,
+        named_symbolic_domains: Vec<((String, DO), (String, DO))>,
+        named_parameter_domains: Vec<(String, DO)>,
+        bdd_variable_set: BddVariableSet,
+        domain_builder: &DomainBuilder<DO, T>,
+    ) -> Self {
+        let named_update_fns_sorted = named_update_fns_sorted
+            .into_iter()
+            .map(|(var_name, update_fn)| (var_name, update_fn.flatten()))
+            .collect::<Vec<_>>();
+        // keyed by the names used in the update functions (un-primed variables & parameters)
+        let named_symbolic_domains_map = named_symbolic_domains
+            .iter()
+            .map(|((var_name, domain), _)| (var_name.as_str(), domain))
+            .chain(
+                named_parameter_domains
+                    .iter()
+                    .map(|(parameter_name, domain)| (parameter_name.as_str(), domain)),
+            )
             .collect::<HashMap<_, _>>();
         let update_fns = named_update_fns_sorted.iter().map(|(var_name, update_fn)| {
             (
@@ -502,8 +1651,10 @@ where
 
         let unit_set = named_symbolic_domains
             .iter()
-            .fold(bdd_variable_set.mk_true(), |acc, ((_name, domain), _)| {
-                acc.and(&domain.unit_collection(&bdd_variable_set))
+            .map(|((_name, domain), _)| domain)
+            .chain(named_parameter_domains.iter().map(|(_name, domain)| domain))
+            .fold(bdd_variable_set.mk_true(), |acc, domain| {
+                acc.and(&domain_builder.unit_collection(&bdd_variable_set, domain))
             });
 
         let unprimed_var_names_and_their_primed_unit_collection = named_symbolic_domains
@@ -511,20 +1662,25 @@ where
             .map(|((unprimed_var_name, _), (_, primed_domain))| {
                 (
                     unprimed_var_name,
-                    primed_domain.unit_collection(&bdd_variable_set),
+                    domain_builder.unit_collection(&bdd_variable_set, primed_domain),
                 )
             })
             .collect::<HashMap<_, _>>();
 
+        let unprimed_var_names_and_their_primed_domains = named_symbolic_domains
+            .iter()
+            .map(|((unprimed_var_name, _), (_, primed_domain))| (unprimed_var_name, primed_domain))
+            .collect::<HashMap<_, _>>();
+
         let relations = update_fns
             .into_iter()
             .map(|(target_variable_name, update_fn)| {
-                let target_symbolic_domain_primed = *named_symbolic_domains_map
-                    .get(format!("{}'", target_variable_name).as_str())
+                let target_symbolic_domain_primed = *unprimed_var_names_and_their_primed_domains
+                    .get(target_variable_name)
                     .expect("domain always present");
 
                 let relation = update_fn
-                    .bit_answering_bdds
+                    .bit_answering_bdds()
                     .iter()
                     .zip(target_symbolic_domain_primed.raw_bdd_variables())
                     .fold(
@@ -552,8 +1708,22 @@ where
         let variables_transition_relation_and_domain = named_symbolic_domains
             .into_iter()
             .zip(relations)
+            .zip(&named_update_fns_sorted)
             .map(
-                |(((var_name, domain), (primed_var_name, primed_domain)), relation_bdd)| {
+                |(
+                    (((var_name, domain), (primed_var_name, primed_domain)), relation_bdd),
+                    (_, update_fn),
+                )| {
+                    // the supplied domains do not need to contain the default value
+                    let is_in_domain =
+                        primed_domain.get_all_possible_values().iter().any(|value| {
+                            DO::cmp(value, &update_fn.default) == std::cmp::Ordering::Equal
+                        });
+                    let default_output = if is_in_domain {
+                        primed_domain.encode_one(&bdd_variable_set, &update_fn.default)
+                    } else {
+                        bdd_variable_set.mk_false()
+                    };
                     (
                         var_name,
                         VarInfo {
@@ -561,6 +1731,7 @@ where
                             domain,
                             primed_domain,
                             transition_relation: relation_bdd,
+                            default_output,
                             _marker: std::marker::PhantomData,
                         },
                     )
@@ -576,126 +1747,2064 @@ where
                 acc
             });
 
+        let standard_variables = variables_transition_relation_and_domain
+            .iter()
+            .flat_map(|(_, info)| info.domain.raw_bdd_variables())
+            .collect();
+        let variable_names = variables_transition_relation_and_domain
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect();
+
         Self {
             mapper,
             variables_transition_relation_and_domain,
             bdd_variable_set,
+            unit_vertex_set: unit_set,
+            standard_variables,
+            variable_names,
+            parameters: named_parameter_domains,
             _marker: std::marker::PhantomData,
         }
+        .with_shared_context()
     }
 
-    pub fn successors_async(&self, transition_variable_name: &str, source_states_set: &Bdd) -> Bdd {
-        let VarInfo {
-            transition_relation,
-            domain: target_domain,
-            primed_domain,
-            ..
-        } = self
-            .get_transition_relation_and_domain(transition_variable_name)
-            .expect("unknown variable");
-
-        let source_states_transition_relation = source_states_set.and(transition_relation);
+    /// Re-allocates the domains of this system (including the primed ones and the parameters)
+    /// in the given `builder` and translates all the transition relations into the new context.
+    ///
+    /// The resulting system uses the [BddVariableSet] built from the `builder` at the time of
+    /// the call, so the BDDs of systems rebuilt one after another in the same `builder` share
+    /// the variable names and ordering; the BDDs of the earlier systems can be moved into the
+    /// (larger) context of the later ones using [BddVariableSet::transfer_from].
+    ///
+    /// Panics if the `builder` already contains a variable of the same name as one of the
+    /// variables of this system.
+    pub fn rebuild_in(&self, builder: &mut BddVariableSetBuilder) -> Self {
+        // the domains are re-allocated in the order of their bits within the BDDs (which is
+        // not the order of the names after renaming a variable or when the system was built
+        // with priorities), so that the translation preserves the relative order of the variables
+        let mut domains = self
+            .variables_transition_relation_and_domain
+            .iter()
+            .flat_map(|(var_name, info)| {
+                [
+                    (internal_name(var_name), &info.domain),
+                    (info.primed_name.clone(), &info.primed_domain),
+                ]
+            })
+            .chain(
+                self.parameters
+                    .iter()
+                    .map(|(parameter_name, domain)| (internal_name(parameter_name), domain)),
+            )
+            .collect::<Vec<_>>();
+        domains.sort_by_key(|(_, domain)| domain.raw_bdd_variables().into_iter().min());
+
+        // old `BddVariable` -> new `BddVariable`
+        let mut translation = HashMap::new();
+        let mut reallocated = domains
+            .into_iter()
+            .map(|(bdd_name, domain)| {
+                let reallocated = DO::new(builder, &bdd_name, &domain.max_value());
+                translation.extend(
+                    domain
+                        .raw_bdd_variables()
+                        .into_iter()
+                        .zip(reallocated.raw_bdd_variables()),
+                );
+                (bdd_name, reallocated)
+            })
+            .collect::<HashMap<_, _>>();
+        let mut take = |bdd_name: &str| {
+            reallocated
+                .remove(bdd_name)
+                .expect("every domain is reallocated")
+        };
+
+        let reallocated_variables = self
+            .variables_transition_relation_and_domain
+            .iter()
+            .map(|(var_name, info)| {
+                let domain = take(&internal_name(var_name));
+                let primed_domain = take(&info.primed_name);
+                (var_name, info, domain, primed_domain)
+            })
+            .collect::<Vec<_>>();
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|(parameter_name, _)| {
+                (parameter_name.clone(), take(&internal_name(parameter_name)))
+            })
+            .collect::<Vec<_>>();
 
-        let forgor_old_val =
-            source_states_transition_relation.exists(target_domain.raw_bdd_variables().as_slice());
+        let bdd_variable_set = builder.clone().build();
+        let num_vars = bdd_variable_set.num_vars();
+        let translate = |bdd: &Bdd| translate_bdd(bdd, &translation, num_vars);
 
-        target_domain
-            .raw_bdd_variables()
+        let variables_transition_relation_and_domain = reallocated_variables
             .into_iter()
-            .zip(primed_domain.raw_bdd_variables())
-            .fold(forgor_old_val, |mut acc, (unprimed, primed)| {
-                unsafe { acc.rename_variable(primed, unprimed) };
-                acc
+            .map(|(var_name, info, domain, primed_domain)| {
+                (
+                    var_name.clone(),
+                    VarInfo {
+                        primed_name: info.primed_name.clone(),
+                        domain,
+                        primed_domain,
+                        transition_relation: translate(&info.transition_relation),
+                        default_output: translate(&info.default_output),
+                        _marker: std::marker::PhantomData,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        Self::from_parts(
+            variables_transition_relation_and_domain,
+            bdd_variable_set,
+            parameters,
+        )
+    }
+
+    /// The asynchronous composition of this system with the `other` one.
+    ///
+    /// The variables (and parameters) of the result are the union of those of the two systems,
+    /// allocated in a new [BddVariableSet]. Each variable is updated by the transition relation
+    /// of whichever system defines it. The variables defined by both systems must have the same
+    /// domains and equivalent update functions, otherwise
+    /// [ComposeError::ConflictingDefinition] is returned.
+    pub fn compose(&self, other: &Self) -> Result<Self, ComposeError> {
+        fn variable_info<'a, D: SymbolicDomain<T>, T>(
+            system: &'a SmartSystemUpdateFn<D, T>,
+            var_name: &str,
+        ) -> Option<&'a VarInfo<D, T>> {
+            system
+                .mapper
+                .get(var_name)
+                .map(|idx| &system.variables_transition_relation_and_domain[*idx].1)
+        }
+        fn parameter_domain<'a, D: SymbolicDomain<T>, T>(
+            system: &'a SmartSystemUpdateFn<D, T>,
+            parameter_name: &str,
+        ) -> Option<&'a D> {
+            system
+                .parameters
+                .iter()
+                .find(|(it, _)| it == parameter_name)
+                .map(|(_, domain)| domain)
+        }
+
+        let variable_names = {
+            let mut names = self
+                .variables_transition_relation_and_domain
+                .iter()
+                .chain(&other.variables_transition_relation_and_domain)
+                .map(|(var_name, _)| var_name.as_str())
+                .collect::<Vec<_>>();
+            names.sort_unstable();
+            names.dedup();
+            names
+        };
+        let parameter_names = {
+            let mut names = self
+                .parameters
+                .iter()
+                .chain(&other.parameters)
+                .map(|(parameter_name, _)| parameter_name.as_str())
+                .collect::<Vec<_>>();
+            names.sort_unstable();
+            names.dedup();
+            names
+        };
+
+        let same_domains = |lhs: &DO, rhs: &DO| {
+            DO::cmp(&lhs.max_value(), &rhs.max_value()) == std::cmp::Ordering::Equal
+        };
+
+        if let Some(name) = parameter_names
+            .iter()
+            .find(|name| variable_names.binary_search(name).is_ok())
+        {
+            return Err(ComposeError::ConflictingDefinition(name.to_string()));
+        }
+
+        let mut builder = BddVariableSetBuilder::new();
+        let mut self_translation = HashMap::new();
+        let mut other_translation = HashMap::new();
+        // allocates the domain in the new context & records how to translate the variables of
+        // the original domains (of whichever systems define it)
+        let mut allocate = |name: &str, bdd_name: &str, domains: (Option<&DO>, Option<&DO>)| {
+            let domain = match domains {
+                (Some(lhs), Some(rhs)) if !same_domains(lhs, rhs) => {
+                    return Err(ComposeError::ConflictingDefinition(name.to_string()))
+                }
+                (Some(domain), _) | (None, Some(domain)) => domain,
+                (None, None) => unreachable!("every name comes from one of the systems"),
+            };
+            let allocated = DO::new(&mut builder, bdd_name, &domain.max_value());
+            for (original, translation) in [
+                (domains.0, &mut self_translation),
+                (domains.1, &mut other_translation),
+            ] {
+                if let Some(original) = original {
+                    translation.extend(
+                        original
+                            .raw_bdd_variables()
+                            .into_iter()
+                            .zip(allocated.raw_bdd_variables()),
+                    );
+                }
+            }
+            Ok(allocated)
+        };
+
+        // every domain of the result, as (the name of its variable or parameter, its name
+        // within the BDDs, its domains in the original systems)
+        let mut domains = variable_names
+            .iter()
+            .flat_map(|var_name| {
+                let infos = (
+                    variable_info(self, var_name),
+                    variable_info(other, var_name),
+                );
+                [
+                    (
+                        *var_name,
+                        internal_name(var_name),
+                        (infos.0.map(|it| &it.domain), infos.1.map(|it| &it.domain)),
+                    ),
+                    (
+                        *var_name,
+                        format!("{}'", internal_name(var_name)),
+                        (
+                            infos.0.map(|it| &it.primed_domain),
+                            infos.1.map(|it| &it.primed_domain),
+                        ),
+                    ),
+                ]
+            })
+            .chain(parameter_names.iter().map(|parameter_name| {
+                (
+                    *parameter_name,
+                    internal_name(parameter_name),
+                    (
+                        parameter_domain(self, parameter_name),
+                        parameter_domain(other, parameter_name),
+                    ),
+                )
+            }))
+            .collect::<Vec<_>>();
+        // allocated in the order of their bits within `self` (followed by the domains only
+        // `other` has, in their order within `other`), which is not the order of the names
+        // after renaming a variable or when the systems were built with priorities; the
+        // translation of the BDDs of `self` thus preserves the order of the variables
+        // (see [translate_bdd])
+        domains.sort_by_key(|(_, _, domains)| match domains {
+            (Some(domain), _) => (false, domain.raw_bdd_variables().into_iter().min()),
+            (None, Some(domain)) => (true, domain.raw_bdd_variables().into_iter().min()),
+            (None, None) => unreachable!("every name comes from one of the systems"),
+        });
+        let mut allocated = domains
+            .into_iter()
+            .map(|(name, bdd_name, domains)| {
+                let domain = allocate(name, &bdd_name, domains)?;
+                Ok((bdd_name, domain))
+            })
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        let mut take = |bdd_name: &str| {
+            allocated
+                .remove(bdd_name)
+                .expect("every domain is allocated")
+        };
+
+        let allocated_variables = variable_names
+            .iter()
+            .map(|var_name| {
+                let infos = (
+                    variable_info(self, var_name),
+                    variable_info(other, var_name),
+                );
+                let primed_name = format!("{}'", internal_name(var_name));
+                let domain = take(&internal_name(var_name));
+                let primed_domain = take(&primed_name);
+                (var_name, infos, primed_name, domain, primed_domain)
+            })
+            .collect::<Vec<_>>();
+        let parameters = parameter_names
+            .iter()
+            .map(|parameter_name| {
+                (
+                    parameter_name.to_string(),
+                    take(&internal_name(parameter_name)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let bdd_variable_set = builder.build();
+        let num_vars = bdd_variable_set.num_vars();
+        let unit_vertex_set = allocated_variables
+            .iter()
+            .map(|(_, _, _, domain, _)| domain)
+            .chain(parameters.iter().map(|(_, domain)| domain))
+            .fold(bdd_variable_set.mk_true(), |acc, domain| {
+                acc.and(&domain.unit_collection(&bdd_variable_set))
+            });
+
+        let variables_transition_relation_and_domain = allocated_variables
+            .into_iter()
+            .map(|(var_name, infos, primed_name, domain, primed_domain)| {
+                // the relations of the original systems only ensure the validity of the
+                // variables of the respective system
+                let relations = (
+                    infos.0.map(|info| {
+                        (
+                            translate_bdd(&info.transition_relation, &self_translation, num_vars)
+                                .and(&unit_vertex_set),
+                            translate_bdd(&info.default_output, &self_translation, num_vars),
+                        )
+                    }),
+                    infos.1.map(|info| {
+                        (
+                            translate_bdd(&info.transition_relation, &other_translation, num_vars)
+                                .and(&unit_vertex_set),
+                            translate_bdd(&info.default_output, &other_translation, num_vars),
+                        )
+                    }),
+                );
+                let (transition_relation, default_output) = match relations {
+                    (Some(lhs), Some(rhs)) if lhs.0 != rhs.0 => {
+                        return Err(ComposeError::ConflictingDefinition(var_name.to_string()))
+                    }
+                    (Some(relation), _) | (None, Some(relation)) => relation,
+                    (None, None) => unreachable!("every variable comes from one of the systems"),
+                };
+                Ok((
+                    var_name.to_string(),
+                    VarInfo {
+                        primed_name,
+                        domain,
+                        primed_domain,
+                        transition_relation,
+                        default_output,
+                        _marker: std::marker::PhantomData,
+                    },
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::from_parts(
+            variables_transition_relation_and_domain,
+            bdd_variable_set,
+            parameters,
+        ))
+    }
+
+    /// Re-encodes the system with each variable's domain tightened to only the values that
+    /// the variable takes within the `reachable` states; the parameters are kept as they are.
+    ///
+    /// The values are remapped densely: the `i`-th smallest value taken by a variable becomes
+    /// the value `i` of the tightened variable. The `reachable` set should be closed under
+    /// the transitions (e.g. computed by [SmartSystemUpdateFn::reachable_from]); the tightened
+    /// system then behaves exactly like this one on (the remapped) `reachable` states.
+    /// Transitions into values that do not appear in `reachable` are dropped.
+    ///
+    /// # Panics
+    ///
+    /// If `reachable` contains no (valid) state.
+    pub fn tighten_domains(&self, reachable: &Bdd) -> Self
+    where
+        T: Copy + PartialOrd + From<u8> + std::ops::Add<Output = T>,
+    {
+        let reachable = self.sanitize(reachable);
+        assert!(!reachable.is_false(), "cannot tighten to an empty set");
+
+        let mut builder = BddVariableSetBuilder::new();
+        let variables = self
+            .variables_transition_relation_and_domain
+            .iter()
+            .map(|(var_name, info)| {
+                // the values taken by the variable, in ascending order (i.e. the index in
+                // the list is the new value)
+                let values = info
+                    .domain
+                    .decode_collection_sorted(&self.bdd_variable_set, &reachable);
+                let new_values = first_values::<T>(values.len());
+                let max_value = *new_values.last().expect("some value is reachable");
+                let remapped = |domain: &DO| {
+                    values
+                        .iter()
+                        .zip(new_values.iter().copied())
+                        .map(|(old_value, new_value)| {
+                            (
+                                domain.encode_one(&self.bdd_variable_set, old_value),
+                                new_value,
+                            )
+                        })
+                        .collect()
+                };
+                ReencodedVariable {
+                    name: var_name.clone(),
+                    primed_name: info.primed_name.clone(),
+                    domain: DO::new(&mut builder, &internal_name(var_name), &max_value),
+                    primed_domain: DO::new(&mut builder, &info.primed_name, &max_value),
+                    relation: &info.transition_relation,
+                    default_output: &info.default_output,
+                    values: remapped(&info.domain),
+                    primed_values: remapped(&info.primed_domain),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.reencode(builder, variables)
+    }
+
+    /// Re-encodes the system with the domain of the variable `variable_name` widened to
+    /// `new_max`; the other variables and the parameters are kept as they are.
+    ///
+    /// The old values keep their transitions. The update functions were not written for the new
+    /// values (those above the current max value), so in the states where the widened variable
+    /// takes a new value, the update functions that read the variable (and the update function
+    /// of the variable itself) output their default value. The update functions that do not read
+    /// the widened variable behave the same for the new values as for the old ones.
+    pub fn widen_domain(&self, variable_name: &str, new_max: T) -> Result<Self, WidenError> {
+        let widened_idx = *self
+            .mapper
+            .get(variable_name)
+            .ok_or_else(|| WidenError::UnknownVariable(variable_name.to_string()))?;
+        let old_domain = &self.variables_transition_relation_and_domain[widened_idx]
+            .1
+            .domain;
+        let old_max = old_domain.max_value();
+        if DO::cmp(&new_max, &old_max) == std::cmp::Ordering::Less {
+            return Err(WidenError::Narrowing(variable_name.to_string()));
+        }
+
+        let mut builder = BddVariableSetBuilder::new();
+        let variables = self
+            .variables_transition_relation_and_domain
+            .iter()
+            .enumerate()
+            .map(|(idx, (var_name, info))| {
+                if idx != widened_idx {
+                    return self.reallocate_unchanged(&mut builder, var_name, info);
+                }
+                // only the old values are mapped; the new ones are added below
+                ReencodedVariable {
+                    name: var_name.clone(),
+                    primed_name: info.primed_name.clone(),
+                    domain: DO::new(&mut builder, &internal_name(var_name), &new_max),
+                    primed_domain: DO::new(&mut builder, &info.primed_name, &new_max),
+                    relation: &info.transition_relation,
+                    default_output: &info.default_output,
+                    values: self.unchanged_values(&info.domain),
+                    primed_values: self.unchanged_values(&info.primed_domain),
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut widened = self.reencode(builder, variables);
+
+        let widened_domain = &widened.variables_transition_relation_and_domain[widened_idx]
+            .1
+            .domain;
+        let widened_variables = widened_domain.raw_bdd_variables();
+        let new_states = widened_domain
+            .encode_gt(&widened.bdd_variable_set, &old_max)
+            .and(&widened.unit_vertex_set);
+        for (idx, ((_, info), (_, original))) in widened
+            .variables_transition_relation_and_domain
+            .iter_mut()
+            .zip(&self.variables_transition_relation_and_domain)
+            .enumerate()
+        {
+            let reads_widened = Self::depends_on(
+                &self.bdd_variable_set,
+                &original.transition_relation,
+                old_domain,
+            );
+            let extension = if idx == widened_idx || reads_widened {
+                new_states.and(&info.default_output)
+            } else {
+                // the relation is the same for each of the old values of the widened variable
+                info.transition_relation
+                    .exists(&widened_variables)
+                    .and(&new_states)
+            };
+            info.transition_relation = info.transition_relation.or(&extension);
+        }
+
+        Ok(widened)
+    }
+
+    /// Fuses the variables `a` and `b` into a single variable `merged_name` (which may reuse the
+    /// name of `a` or `b`); the other variables and the parameters are kept as they are.
+    ///
+    /// The merged variable has `(max_a + 1) * (max_b + 1)` values: its value
+    /// `i * (max_b + 1) + j` stands for `a` having its `i`-th and `b` its `j`-th (smallest)
+    /// value. A transition of the merged variable is either a transition of `a`, or one of `b`,
+    /// so the merged system has the same dynamics as this one (on the re-encoded states).
+    pub fn merge_variables(&self, a: &str, b: &str, merged_name: &str) -> Result<Self, MergeError>
+    where
+        T: Copy + PartialOrd + From<u8> + std::ops::Add<Output = T>,
+    {
+        let variable_info = |var_name: &str| {
+            self.mapper
+                .get(var_name)
+                .map(|idx| &self.variables_transition_relation_and_domain[*idx].1)
+                .ok_or_else(|| MergeError::UnknownVariable(var_name.to_string()))
+        };
+        let (a_info, b_info) = (variable_info(a)?, variable_info(b)?);
+        if a == b {
+            return Err(MergeError::SameVariable(a.to_string()));
+        }
+        let is_taken = self.mapper.contains_key(merged_name)
+            || self.parameters.iter().any(|(name, _)| name == merged_name);
+        if is_taken && merged_name != a && merged_name != b {
+            return Err(MergeError::NameTaken(merged_name.to_string()));
+        }
+
+        let a_values = a_info.domain.get_all_possible_values();
+        let b_values = b_info.domain.get_all_possible_values();
+        let count = a_values.len() * b_values.len();
+        if count > usize::from(u8::MAX) + 1 {
+            return Err(MergeError::TooManyValues(count));
+        }
+        let merged_values = first_values::<T>(count);
+        // the (a, b) pairs of the given pair of domains, in the order of the merged values
+        let merged_pairs = |a_domain: &DO, b_domain: &DO| {
+            a_values
+                .iter()
+                .flat_map(|a_value| b_values.iter().map(move |b_value| (a_value, b_value)))
+                .zip(&merged_values)
+                .map(|((a_value, b_value), value)| {
+                    let old = a_domain
+                        .encode_one(&self.bdd_variable_set, a_value)
+                        .and(&b_domain.encode_one(&self.bdd_variable_set, b_value));
+                    (old, *value)
+                })
+                .collect()
+        };
+
+        // `primed == un-primed` of the given variable; the variable that is not updated by
+        // a transition of the merged variable keeps its value
+        let frame = |info: &VarInfo<DO, T>| {
+            info.domain.get_all_possible_values().iter().fold(
+                self.bdd_variable_set.mk_false(),
+                |acc, value| {
+                    acc.or(&info
+                        .domain
+                        .encode_one(&self.bdd_variable_set, value)
+                        .and(&info.primed_domain.encode_one(&self.bdd_variable_set, value)))
+                },
+            )
+        };
+        let merged_relation = a_info
+            .transition_relation
+            .and(&frame(b_info))
+            .or(&b_info.transition_relation.and(&frame(a_info)));
+        let merged_default = a_info.default_output.and(&b_info.default_output);
+
+        // the variables of the merged system, sorted by name (`None` is the merged one)
+        let mut sorted_variables = self
+            .variables_transition_relation_and_domain
+            .iter()
+            .filter(|(var_name, _)| var_name != a && var_name != b)
+            .map(|(var_name, info)| (var_name.as_str(), Some(info)))
+            .chain(std::iter::once((merged_name, None)))
+            .collect::<Vec<_>>();
+        sorted_variables.sort_by_key(|(var_name, _)| *var_name);
+
+        let mut builder = BddVariableSetBuilder::new();
+        let variables = sorted_variables
+            .into_iter()
+            .map(|(var_name, info)| match info {
+                Some(info) => self.reallocate_unchanged(&mut builder, var_name, info),
+                None => {
+                    let max_value = merged_values[count - 1];
+                    let primed_name = format!("{}'", internal_name(var_name));
+                    ReencodedVariable {
+                        name: var_name.to_string(),
+                        domain: DO::new(&mut builder, &internal_name(var_name), &max_value),
+                        primed_domain: DO::new(&mut builder, &primed_name, &max_value),
+                        primed_name,
+                        relation: &merged_relation,
+                        default_output: &merged_default,
+                        values: merged_pairs(&a_info.domain, &b_info.domain),
+                        primed_values: merged_pairs(&a_info.primed_domain, &b_info.primed_domain),
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Ok(self.reencode(builder, variables))
+    }
+
+    /// The variable `var_name` of this system, re-allocated in `builder` with the same values
+    /// (to be re-encoded by [SmartSystemUpdateFn::reencode]).
+    fn reallocate_unchanged<'a>(
+        &self,
+        builder: &mut BddVariableSetBuilder,
+        var_name: &str,
+        info: &'a VarInfo<DO, T>,
+    ) -> ReencodedVariable<'a, DO, T> {
+        let max_value = info.domain.max_value();
+        ReencodedVariable {
+            name: var_name.to_string(),
+            primed_name: info.primed_name.clone(),
+            domain: DO::new(builder, &internal_name(var_name), &max_value),
+            primed_domain: DO::new(builder, &info.primed_name, &max_value),
+            relation: &info.transition_relation,
+            default_output: &info.default_output,
+            values: self.unchanged_values(&info.domain),
+            primed_values: self.unchanged_values(&info.primed_domain),
+        }
+    }
+
+    /// Each value of the `domain` (of this system), paired with itself.
+    fn unchanged_values(&self, domain: &DO) -> Vec<(Bdd, T)> {
+        domain
+            .get_all_possible_values()
+            .into_iter()
+            .map(|value| (domain.encode_one(&self.bdd_variable_set, &value), value))
+            .collect()
+    }
+
+    /// Re-encodes this system into the `variables` (sorted by name) allocated in `builder`;
+    /// the parameters are re-allocated after them, with the same values.
+    ///
+    /// The relation of each variable is translated using the values of the variables: a state
+    /// of the re-encoded system stands for the states of this system which satisfy the
+    /// conditions of its values (the same goes for the primed values of the updated variable).
+    fn reencode(
+        &self,
+        mut builder: BddVariableSetBuilder,
+        variables: Vec<ReencodedVariable<DO, T>>,
+    ) -> Self {
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|(parameter_name, domain)| {
+                let reallocated = DO::new(
+                    &mut builder,
+                    &internal_name(parameter_name),
+                    &domain.max_value(),
+                );
+                (parameter_name.clone(), reallocated)
+            })
+            .collect::<Vec<_>>();
+        let bdd_variable_set = builder.build();
+        let num_vars = bdd_variable_set.num_vars();
+
+        // the relations are translated within a combined context, where the variables of this
+        // system follow after those of the re-encoded one; this preserves their relative order
+        let combined_num_vars = num_vars + self.bdd_variable_set.num_vars();
+        let old_to_combined = self
+            .bdd_variable_set
+            .variables()
+            .into_iter()
+            .map(|var| {
+                (
+                    var,
+                    BddVariable::from_index(var.to_index() + usize::from(num_vars)),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        let lift_old = |bdd: &Bdd| translate_bdd(bdd, &old_to_combined, combined_num_vars);
+        let lift_new = |bdd: &Bdd| translate_bdd(bdd, &HashMap::new(), combined_num_vars);
+
+        // `old states` <=> `new value` of the given domain
+        let value_mapping = |values: &[(Bdd, T)], new_domain: &DO| {
+            values.iter().fold(
+                lift_new(&bdd_variable_set.mk_false()),
+                |acc, (old, new_value)| {
+                    let new = lift_new(&new_domain.encode_one(&bdd_variable_set, new_value));
+                    acc.or(&lift_old(old).and(&new))
+                },
+            )
+        };
+
+        let standard_mapping = variables
+            .iter()
+            .map(|variable| value_mapping(&variable.values, &variable.domain))
+            .chain(self.parameters.iter().zip(parameters.iter()).map(
+                |((_, old_domain), (_, new_domain))| {
+                    value_mapping(&self.unchanged_values(old_domain), new_domain)
+                },
+            ))
+            .fold(lift_new(&bdd_variable_set.mk_true()), |acc, mapping| {
+                acc.and(&mapping)
+            });
+        let old_variables = old_to_combined.values().copied().collect::<Vec<_>>();
+
+        // translates the `bdd` of this system using the given `mapping`
+        let remap = |bdd: &Bdd, mapping: &Bdd| {
+            let remapped = Bdd::binary_op_with_exists(
+                &lift_old(bdd),
+                mapping,
+                op_function::and,
+                &old_variables,
+            );
+            translate_bdd(&remapped, &HashMap::new(), num_vars)
+        };
+
+        let variables_transition_relation_and_domain = variables
+            .into_iter()
+            .map(|variable| {
+                let primed_mapping =
+                    value_mapping(&variable.primed_values, &variable.primed_domain);
+                (
+                    variable.name,
+                    VarInfo {
+                        primed_name: variable.primed_name,
+                        domain: variable.domain,
+                        primed_domain: variable.primed_domain,
+                        transition_relation: remap(
+                            variable.relation,
+                            &standard_mapping.and(&primed_mapping),
+                        ),
+                        default_output: remap(variable.default_output, &primed_mapping),
+                        _marker: std::marker::PhantomData,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        Self::from_parts(
+            variables_transition_relation_and_domain,
+            bdd_variable_set,
+            parameters,
+        )
+    }
+
+    /// Estimate the number of `BddVariable`s (both primed and un-primed) that the system built
+    /// from `update_fns` using [SmartSystemUpdateFn::from_update_fns] would require, without
+    /// actually building it. Useful for choosing the encoding before committing to it.
+    pub fn estimate_symbolic_size(
+        update_fns: &HashMap<String, UnprocessedVariableUpdateFn<T>>,
+    ) -> usize {
+        find_max_values::<DO, T, _>(update_fns)
+            .into_values()
+            .map(|max_value| 2 * DO::required_bits(max_value))
+            .sum()
+    }
+
+    /// Like [SmartSystemUpdateFn::from_update_fns], but does not enlarge the domain of a variable
+    /// that is compared with a value greater than any value the variable can be updated to
+    /// (which is what [SmartSystemUpdateFn::from_update_fns] does to accommodate "faulty"
+    /// datasets). Such comparisons are reported as [OutOfDomainConstant] instead, since a single
+    /// typo (e.g. `target eq 999`) would otherwise blow up the size of the encoding.
+    ///
+    /// Variables without any function terms (e.g. inputs of the system) are exempt from this
+    /// check, since their domain can only be inferred from the comparisons.
+    pub fn try_from_update_fns_strict(
+        vars_and_their_update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
+    ) -> Result<Self, OutOfDomainConstant<T>>
+    where
+        T: Clone,
+    {
+        let named_update_fns_sorted = {
+            let mut to_be_sorted = vars_and_their_update_fns.iter().collect::<Vec<_>>();
+            to_be_sorted.sort_unstable_by_key(|(var_name, _)| *var_name);
+            to_be_sorted
+        };
+
+        let max_outputs = named_update_fns_sorted
+            .iter()
+            .filter(|(_, update_fn)| !update_fn.terms.is_empty())
+            .map(|(var_name, update_fn)| {
+                let max_value = update_fn
+                    .terms
+                    .iter()
+                    .map(|(val, _)| val)
+                    .chain(Some(&update_fn.default))
+                    .max_by(|x, y| DO::cmp(x, y))
+                    .expect("default value always present");
+                (var_name.as_str(), max_value)
             })
+            .collect::<HashMap<_, _>>();
+
+        let out_of_domain = named_update_fns_sorted
+            .iter()
+            .flat_map(|(_, update_fn)| update_fn.terms.iter())
+            .flat_map(|(_, expression)| expression.propositions())
+            .find(|proposition| {
+                max_outputs
+                    .get(proposition.variable.as_str())
+                    .is_some_and(|max_value| {
+                        DO::cmp(&proposition.value, max_value) == std::cmp::Ordering::Greater
+                    })
+            });
+
+        if let Some(proposition) = out_of_domain {
+            return Err(OutOfDomainConstant {
+                variable: proposition.variable.clone(),
+                value: proposition.value.clone(),
+            });
+        }
+
+        Ok(Self::from_update_fns(vars_and_their_update_fns))
+    }
+
+    /// The states reachable from the `source_states_set` by updating the given variable,
+    /// including the states updated to themselves; the same as
+    /// [SmartSystemUpdateFn::successors_with_policy] with [SelfLoopPolicy::Include].
+    ///
+    /// # Panics
+    ///
+    /// Panics if variable with given name is not available; see
+    /// [SmartSystemUpdateFn::try_successors_async] for a non-panicking variant.
+    pub fn successors_async(&self, transition_variable_name: &str, source_states_set: &Bdd) -> Bdd {
+        self.successors_with_policy(
+            transition_variable_name,
+            source_states_set,
+            SelfLoopPolicy::Include,
+        )
     }
 
     /// Like `successors_async`, but a state that "transitions" to itself under
     /// given transition variable is not considered to be a proper successor,
     /// therefore is not included in the result (unless it is a proper successor
-    /// of another state from `source_states`).
+    /// of another state from `source_states`). The same as
+    /// [SmartSystemUpdateFn::successors_with_policy] with [SelfLoopPolicy::Exclude].
     pub fn successors_async_exclude_loops(
         &self,
-        _transition_variable_name: &str,
-        _source_states: &Bdd,
+        transition_variable_name: &str,
+        source_states: &Bdd,
     ) -> Bdd {
-        // todo better to directly construct the specific no_loop_transition_bdd during construction
-        // todo probably have some common, underlying method that would accept the transition bdd
-        // todo the two public methods would then just pass in the proper transition bdd
-        // self.successors_async(
-        //     transition_variable_name,
-        //     &source_states
-        //         .and(&self.those_states_capable_of_transitioning_under(transition_variable_name)),
-        // )
-        todo!()
+        self.successors_with_policy(
+            transition_variable_name,
+            source_states,
+            SelfLoopPolicy::Exclude,
+        )
     }
 
-    pub fn predecessors_async(
+    /// The states reachable from the `source_states_set` by updating the given variable,
+    /// where the self-loops are included or excluded based on the `policy` (see
+    /// [SelfLoopPolicy]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if variable with given name is not available.
+    pub fn successors_with_policy(
         &self,
         transition_variable_name: &str,
-        source_states_set: Bdd, // todo inconsistent with succs api; but `rename_variable` requires ownership
+        source_states_set: &Bdd,
+        policy: SelfLoopPolicy,
     ) -> Bdd {
-        let VarInfo {
-            transition_relation,
+        self.try_successors_with_policy(transition_variable_name, source_states_set, policy)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like [SmartSystemUpdateFn::successors_async], but an unknown variable name results
+    /// in an error instead of a panic, so the name can come from an untrusted caller.
+    pub fn try_successors_async(
+        &self,
+        transition_variable_name: &str,
+        source_states_set: &Bdd,
+    ) -> Result<Bdd, UnknownVariable> {
+        self.try_successors_with_policy(
+            transition_variable_name,
+            source_states_set,
+            SelfLoopPolicy::Include,
+        )
+    }
+
+    fn try_successors_with_policy(
+        &self,
+        transition_variable_name: &str,
+        source_states_set: &Bdd,
+        policy: SelfLoopPolicy,
+    ) -> Result<Bdd, UnknownVariable> {
+        let var_info = self.try_get_transition_relation_and_domain(transition_variable_name)?;
+        let VarInfo {
             domain: target_domain,
             primed_domain,
             ..
+        } = var_info;
+        let transition_relation = self.relation_under_policy(var_info, policy);
+
+        // fused `and` + `exists`; avoids materializing the (possibly large) conjunction
+        let forgor_old_val = Bdd::binary_op_with_exists(
+            source_states_set,
+            &transition_relation,
+            op_function::and,
+            target_domain.raw_bdd_variables().as_slice(),
+        );
+
+        Ok(rename_domain(
+            forgor_old_val,
+            &primed_domain.raw_bdd_variables(),
+            &target_domain.raw_bdd_variables(),
+        ))
+    }
+
+    /// The states that reach the `source_states_set` by updating the given variable,
+    /// including the states updated to themselves; the same as
+    /// [SmartSystemUpdateFn::predecessors_with_policy] with [SelfLoopPolicy::Include].
+    ///
+    /// # Panics
+    ///
+    /// Panics if variable with given name is not available; see
+    /// [SmartSystemUpdateFn::try_predecessors_async] for a non-panicking variant.
+    pub fn predecessors_async(
+        &self,
+        transition_variable_name: &str,
+        source_states_set: Bdd, // todo inconsistent with succs api; but `rename_domain` requires ownership
+    ) -> Bdd {
+        self.try_predecessors_of_owned(
+            transition_variable_name,
+            source_states_set,
+            SelfLoopPolicy::Include,
+        )
+        .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Like [SmartSystemUpdateFn::predecessors_async], but an unknown variable name results
+    /// in an error instead of a panic, so the name can come from an untrusted caller.
+    pub fn try_predecessors_async(
+        &self,
+        transition_variable_name: &str,
+        source_states_set: Bdd,
+    ) -> Result<Bdd, UnknownVariable> {
+        self.try_predecessors_of_owned(
+            transition_variable_name,
+            source_states_set,
+            SelfLoopPolicy::Include,
+        )
+    }
+
+    /// Like `predecessors_async`, but a state that "transitions" to itself under
+    /// given transition variable is not considered to be a proper predecessor,
+    /// therefore is not included in the result (unless it is a proper predecessor
+    /// of another state from `source_states`). The same as
+    /// [SmartSystemUpdateFn::predecessors_with_policy] with [SelfLoopPolicy::Exclude].
+    pub fn predecessors_async_exclude_loops(
+        &self,
+        variable_name: &str,
+        source_states: &Bdd,
+    ) -> Bdd {
+        self.predecessors_with_policy(variable_name, source_states, SelfLoopPolicy::Exclude)
+    }
+
+    /// The states that reach the `source_states_set` by updating the given variable,
+    /// where the self-loops are included or excluded based on the `policy` (see
+    /// [SelfLoopPolicy]). The set is cloned internally, since the renaming of the variables
+    /// requires ownership.
+    ///
+    /// # Panics
+    ///
+    /// Panics if variable with given name is not available.
+    pub fn predecessors_with_policy(
+        &self,
+        transition_variable_name: &str,
+        source_states_set: &Bdd,
+        policy: SelfLoopPolicy,
+    ) -> Bdd {
+        self.try_predecessors_of_owned(transition_variable_name, source_states_set.clone(), policy)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    fn try_predecessors_of_owned(
+        &self,
+        transition_variable_name: &str,
+        source_states_set: Bdd,
+        policy: SelfLoopPolicy,
+    ) -> Result<Bdd, UnknownVariable> {
+        let var_info = self.try_get_transition_relation_and_domain(transition_variable_name)?;
+        let VarInfo {
+            domain: target_domain,
+            primed_domain,
+            ..
+        } = var_info;
+        let transition_relation = self.relation_under_policy(var_info, policy);
+
+        let source_states_primed_set = rename_domain(
+            source_states_set,
+            &target_domain.raw_bdd_variables(),
+            &primed_domain.raw_bdd_variables(),
+        );
+
+        Ok(Bdd::binary_op_with_exists(
+            &source_states_primed_set,
+            &transition_relation,
+            op_function::and,
+            primed_domain.raw_bdd_variables().as_slice(),
+        ))
+    }
+
+    /// The transition relation of the variable, without the self-loops if these are excluded.
+    fn relation_under_policy<'a>(
+        &self,
+        var_info: &'a VarInfo<DO, T>,
+        policy: SelfLoopPolicy,
+    ) -> Cow<'a, Bdd> {
+        match policy {
+            SelfLoopPolicy::Include => Cow::Borrowed(&var_info.transition_relation),
+            SelfLoopPolicy::Exclude => Cow::Owned(
+                var_info
+                    .transition_relation
+                    .and_not(&self.value_preserved(var_info)),
+            ),
+        }
+    }
+
+    /// The primed copy of the variable has the same value as the un-primed one.
+    fn value_preserved(&self, var_info: &VarInfo<DO, T>) -> Bdd {
+        var_info
+            .domain
+            .raw_bdd_variables()
+            .into_iter()
+            .zip(var_info.primed_domain.raw_bdd_variables())
+            .fold(
+                self.bdd_variable_set.mk_true(),
+                |acc, (unprimed, primed)| {
+                    acc.and(
+                        &self
+                            .bdd_variable_set
+                            .mk_var(unprimed)
+                            .iff(&self.bdd_variable_set.mk_var(primed)),
+                    )
+                },
+            )
+    }
+
+    /// Alias of [SmartSystemUpdateFn::successors_async], provided under the name used by
+    /// the prototype reachability algorithms.
+    pub fn transition_under_variable(
+        &self,
+        transition_variable_name: &str,
+        source_states_set: &Bdd,
+    ) -> Bdd {
+        self.successors_async(transition_variable_name, source_states_set)
+    }
+
+    /// Like [SmartSystemUpdateFn::predecessors_async], but takes the source states by reference,
+    /// consistently with [SmartSystemUpdateFn::transition_under_variable]. The set is cloned
+    /// internally, since the renaming of the variables requires ownership.
+    pub fn predecessors_under_variable(
+        &self,
+        transition_variable_name: &str,
+        source_states_set: &Bdd,
+    ) -> Bdd {
+        self.predecessors_async(transition_variable_name, source_states_set.clone())
+    }
+
+    /// Returns the transitions (edges) from the `source_states_set` under the given variable,
+    /// i.e. the transition relation restricted to the `source_states_set`. Unlike
+    /// [SmartSystemUpdateFn::successors_async], the primed variables are not projected away:
+    /// each edge is encoded by the source state (un-primed variables) and the target state
+    /// (primed variables). The primed copies of the variables other than the updated one are
+    /// bound to their un-primed values, so the target state is fully determined.
+    ///
+    /// The edges can be decoded using [SmartSystemUpdateFn::decode_edges].
+    pub fn successor_edges(&self, transition_variable_name: &str, source_states_set: &Bdd) -> Bdd {
+        let VarInfo {
+            transition_relation,
+            ..
         } = self
             .get_transition_relation_and_domain(transition_variable_name)
             .expect("unknown variable");
 
-        let source_states_primed_set = target_domain
-            .raw_bdd_variables()
+        let frame_condition = self
+            .variables_transition_relation_and_domain
+            .iter()
+            .filter(|(name, _)| name != transition_variable_name)
+            .flat_map(|(_, info)| {
+                info.domain
+                    .raw_bdd_variables()
+                    .into_iter()
+                    .zip(info.primed_domain.raw_bdd_variables())
+            })
+            .fold(
+                self.bdd_variable_set.mk_true(),
+                |acc, (unprimed, primed)| {
+                    acc.and(
+                        &self
+                            .bdd_variable_set
+                            .mk_var(unprimed)
+                            .iff(&self.bdd_variable_set.mk_var(primed)),
+                    )
+                },
+            );
+
+        source_states_set
+            .and(transition_relation)
+            .and(&frame_condition)
+    }
+
+    /// Count the transitions (edges) of the asynchronous graph starting in the (valid) states of
+    /// `set`, i.e. the pairs of (source state, target state) with the source in `set` such that
+    /// the target is a proper successor of the source (see [SelfLoopPolicy::Exclude]) under
+    /// some variable. Each pair is counted once, even if several variables produce it.
+    ///
+    /// If the system has parameters, an edge is counted once, regardless of the number
+    /// of parameter valuations it appears with.
+    pub fn count_transitions(&self, set: &Bdd) -> BigInt {
+        let set = self.sanitize(set);
+        let edges = self.variables_transition_relation_and_domain.iter().fold(
+            self.bdd_variable_set.mk_false(),
+            |acc, (name, info)| {
+                acc.or(&self
+                    .successor_edges(name, &set)
+                    .and_not(&self.value_preserved(info)))
+            },
+        );
+
+        // an edge is fixed by the (primed & un-primed) standard variables, so it is counted
+        // once for every valuation of the remaining ones
+        let mut edge_variables = self.standard_variables.clone();
+        edge_variables.extend(self.primed_variables());
+        let other_variables = self
+            .bdd_variable_set
+            .variables()
             .into_iter()
-            .zip(primed_domain.raw_bdd_variables())
-            .rev() // it's magic
-            .fold(source_states_set, |mut acc, (unprimed, primed)| {
-                unsafe { acc.rename_variable(unprimed, primed) };
-                acc
-            });
+            .filter(|var| !edge_variables.contains(var))
+            .collect::<Vec<_>>();
+        edges.exists(&other_variables).exact_cardinality() >> other_variables.len()
+    }
+
+    /// Like [SmartSystemUpdateFn::influence_graph], but every edge `(regulator, target)` is also
+    /// annotated with its [Monotonicity], i.e. whether increasing the value of the regulator
+    /// (while the values of all the other variables are fixed) increases or decreases
+    /// the value the target is updated to.
+    pub fn signed_influence_graph(&self) -> Vec<(String, String, Monotonicity)> {
+        self.influence_graph()
+            .into_iter()
+            .map(|(regulator, target)| {
+                let monotonicity = self.monotonicity(&regulator, &target);
+                (regulator, target, monotonicity)
+            })
+            .collect()
+    }
+
+    fn monotonicity(&self, regulator: &str, target: &str) -> Monotonicity {
+        let regulator_domain = &self
+            .get_transition_relation_and_domain(regulator)
+            .expect("known variable")
+            .domain;
+        let target_info = self
+            .get_transition_relation_and_domain(target)
+            .expect("known variable");
+        let regulator_bits = regulator_domain.raw_bdd_variables();
+        let target_primed_bits = target_info.primed_domain.raw_bdd_variables();
+
+        let mut regulator_values = regulator_domain.get_all_possible_values();
+        regulator_values.sort_by(DO::cmp);
+        let target_values = target_info.primed_domain.get_all_possible_values();
+
+        // `at_least[i][j]` is the set of contexts (valuations of the other variables) in which
+        // the target is updated to at least `target_values[j]`, given the regulator has
+        // the value `regulator_values[i]`
+        let at_least = regulator_values
+            .iter()
+            .map(|regulator_value| {
+                let fixed = target_info
+                    .transition_relation
+                    .and(&regulator_domain.encode_one(&self.bdd_variable_set, regulator_value))
+                    .exists(&regulator_bits);
+                target_values
+                    .iter()
+                    .map(|target_value| {
+                        fixed
+                            .and(
+                                &target_info
+                                    .primed_domain
+                                    .encode_ge(&self.bdd_variable_set, target_value),
+                            )
+                            .exists(&target_primed_bits)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // contexts in which the target increases (resp. decreases) for some increase
+        // of the regulator value
+        let empty = self.bdd_variable_set.mk_false();
+        let (increasing, decreasing) =
+            at_least
+                .windows(2)
+                .fold((empty.clone(), empty), |(increasing, decreasing), pair| {
+                    pair[0].iter().zip(&pair[1]).fold(
+                        (increasing, decreasing),
+                        |(increasing, decreasing), (lower, higher)| {
+                            (
+                                increasing.or(&higher.and_not(lower)),
+                                decreasing.or(&lower.and_not(higher)),
+                            )
+                        },
+                    )
+                });
+
+        if !increasing.and(&decreasing).is_false() {
+            return Monotonicity::Unknown;
+        }
+        match (increasing.is_false(), decreasing.is_false()) {
+            (false, true) => Monotonicity::Activation,
+            (true, false) => Monotonicity::Inhibition,
+            (false, false) => Monotonicity::Dual,
+            // cannot happen for an edge of the influence graph
+            (true, true) => Monotonicity::Unknown,
+        }
+    }
+
+    fn get_transition_relation_and_domain(&self, variable_name: &str) -> Option<&VarInfo<DO, T>> {
+        self.mapper
+            .get(variable_name)
+            .map(|idx| &self.variables_transition_relation_and_domain[*idx].1)
+    }
+
+    fn try_get_transition_relation_and_domain(
+        &self,
+        variable_name: &str,
+    ) -> Result<&VarInfo<DO, T>, UnknownVariable> {
+        self.get_transition_relation_and_domain(variable_name)
+            .ok_or_else(|| UnknownVariable {
+                name: variable_name.to_string(),
+                available: self.get_system_variables(),
+            })
+    }
+
+    /// The set of states that have a proper successor (i.e. not the state itself) under
+    /// the given variable.
+    fn those_states_capable_of_transitioning_under(&self, variable_name: &str) -> Bdd {
+        // todo this should be stored in a field; built during construction
+        let var_info = self
+            .get_transition_relation_and_domain(variable_name)
+            .expect("unknown variable");
+
+        self.relation_under_policy(var_info, SelfLoopPolicy::Exclude)
+            .exists(&var_info.primed_domain.raw_bdd_variables())
+    }
+
+    /// Returns the deadlock (sink) states of the system, i.e. the valid states that have
+    /// no proper successor (a successor other than the state itself) under any variable.
+    pub fn deadlock_states(&self) -> Bdd {
+        self.variables_transition_relation_and_domain
+            .iter()
+            .fold(self.unit_vertex_set(), |acc, (name, _)| {
+                acc.and_not(&self.those_states_capable_of_transitioning_under(name))
+            })
+    }
+
+    /// Returns the names of the variables that can fire (i.e. produce a state other than
+    /// the source one) from some state of `set`, in the order of
+    /// [SmartSystemUpdateFn::get_system_variables].
+    ///
+    /// The result is empty iff `set` consists of fixed points only
+    /// (see [SmartSystemUpdateFn::deadlock_states]).
+    pub fn enabled_variables(&self, set: &Bdd) -> Vec<String> {
+        self.variables_transition_relation_and_domain
+            .iter()
+            .filter(|(name, _)| {
+                !set.and(&self.those_states_capable_of_transitioning_under(name))
+                    .is_false()
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Explicitly enumerate the fixed points (see [SmartSystemUpdateFn::deadlock_states]) as
+    /// assignments of values to the system variables, unless there are more than `max` of them.
+    ///
+    /// If the system has parameters, the states that are fixed points under any of the
+    /// parameter valuations are enumerated (once).
+    pub fn enumerate_fixed_points(&self, max: usize) -> Result<Vec<HashMap<String, T>>, TooMany> {
+        let fixed_points = self.project_to_states(&self.deadlock_states());
+
+        let count = fixed_points.exact_cardinality();
+        if count > BigInt::from(max) {
+            return Err(TooMany { count, max });
+        }
+
+        Ok(fixed_points
+            .sat_valuations()
+            .map(|valuation| {
+                self.variables_transition_relation_and_domain
+                    .iter()
+                    .map(|(name, info)| (name.clone(), decode_valuation(&info.domain, &valuation)))
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Writes the states of `set` into `writer` as CSV, so that they can be handed over to
+    /// tools that do not work with BDDs.
+    ///
+    /// The first row is a header with the names of the system variables (in the order of
+    /// [SmartSystemUpdateFn::get_system_variables]), followed by one row of decoded values
+    /// per state. At most `max_rows` states are written; the rest is silently omitted.
+    /// See [SmartSystemUpdateFn::import_states_csv] for the inverse operation.
+    pub fn export_states_csv(
+        &self,
+        set: &Bdd,
+        max_rows: usize,
+        writer: &mut impl Write,
+    ) -> io::Result<()>
+    where
+        T: Display,
+    {
+        let header = self
+            .variables_transition_relation_and_domain
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        writeln!(writer, "{}", header.join(","))?;
+
+        let states = self.project_to_states(set);
+        for valuation in states.sat_valuations().take(max_rows) {
+            let row = self
+                .variables_transition_relation_and_domain
+                .iter()
+                .map(|(_, info)| decode_valuation(&info.domain, &valuation).to_string())
+                .collect::<Vec<_>>();
+            writeln!(writer, "{}", row.join(","))?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a set of states written by [SmartSystemUpdateFn::export_states_csv].
+    ///
+    /// The header must name every system variable exactly once (in any order); every other
+    /// non-empty row is encoded as a single state using [SmartSystemUpdateFn::encode_state].
+    pub fn import_states_csv(&self, reader: &mut impl Read) -> Result<Bdd, ImportError<T>>
+    where
+        T: FromStr + Clone,
+    {
+        let mut lines = BufReader::new(reader).lines();
+        let header = match lines.next() {
+            Some(line) => line?,
+            None => return Err(ImportError::MissingHeader),
+        };
+        let header = header.split(',').map(str::trim).collect::<Vec<_>>();
+        if let Some(duplicate) = header
+            .iter()
+            .enumerate()
+            .find_map(|(idx, name)| header[..idx].contains(name).then_some(name))
+        {
+            return Err(ImportError::DuplicateVariable(duplicate.to_string()));
+        }
+
+        let mut result = self.bdd_variable_set.mk_false();
+        // the header is the first line
+        for (line_number, line) in (2..).zip(lines) {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let values = line.split(',').map(str::trim).collect::<Vec<_>>();
+            if values.len() != header.len() {
+                return Err(ImportError::WrongColumnCount {
+                    line: line_number,
+                    expected: header.len(),
+                    found: values.len(),
+                });
+            }
+
+            let mut assignment = HashMap::new();
+            for (name, value) in header.iter().zip(values) {
+                let value = value.parse().map_err(|_| ImportError::InvalidValue {
+                    line: line_number,
+                    value: value.to_string(),
+                })?;
+                assignment.insert(name.to_string(), value);
+            }
+
+            let state = self
+                .encode_state(&assignment)
+                .map_err(|source| ImportError::Encoding {
+                    line: line_number,
+                    source,
+                })?;
+            result = result.or(&state);
+        }
+
+        Ok(result)
+    }
+
+    /// Writes the system into `writer` as a sequential circuit in the ASCII AIGER format
+    /// (version 1.9), so that it can be handed over to hardware model checkers.
+    ///
+    /// The circuit has:
+    ///  - one latch per [BddVariable] of the (un-primed) domains, named like the variable
+    ///    in the [BddVariableSet] of this system, followed by one latch per [BddVariable]
+    ///    of the parameters (these never change);
+    ///  - one input per system variable, named `fire_<variable>`; in each step, the first
+    ///    variable (in the order of [SmartSystemUpdateFn::get_system_variables]) whose input
+    ///    is set and that has a successor is updated, and the state is kept if there is none;
+    ///  - one invariant constraint, which is [SmartSystemUpdateFn::unit_vertex_set]
+    ///    (the latches are uninitialised, i.e. any valid state is initial).
+    ///
+    /// The next-state function of each latch is translated from the transition relation
+    /// of its variable by the Shannon expansion of the BDD. This requires the update of each
+    /// variable to be deterministic; otherwise (e.g. after [SmartSystemUpdateFn::merge_variables])
+    /// fails with [io::ErrorKind::InvalidInput].
+    pub fn to_aiger(&self, writer: &mut impl Write) -> io::Result<()> {
+        let latches = self
+            .standard_variables
+            .iter()
+            .copied()
+            .chain(self.parameter_variables())
+            .collect::<Vec<_>>();
+        let mut aig = AigBuilder::new(
+            self.variables_transition_relation_and_domain.len(),
+            latches.len(),
+        );
+        let latch_literals = latches
+            .iter()
+            .enumerate()
+            .map(|(idx, var)| (*var, aig.latch(idx)))
+            .collect::<HashMap<_, _>>();
+
+        // the next-state functions of the latches; the parameters keep their value
+        let mut next_state = latch_literals.clone();
+        // whether none of the previous variables fired
+        let mut no_earlier_update = AigBuilder::TRUE;
+        for (idx, (name, info)) in self
+            .variables_transition_relation_and_domain
+            .iter()
+            .enumerate()
+        {
+            let primed_variables = info.primed_domain.raw_bdd_variables();
+            let relation = &info.transition_relation;
+            let has_successor = relation.exists(&primed_variables);
+            let can_update = aig.translate(&has_successor, &latch_literals);
+            let fire = aig.and(aig.input(idx), can_update);
+            let fires_first = aig.and(fire, no_earlier_update);
+            no_earlier_update = aig.and(no_earlier_update, AigBuilder::negate(fire));
+
+            for (var, primed_var) in info
+                .domain
+                .raw_bdd_variables()
+                .into_iter()
+                .zip(&primed_variables)
+            {
+                let primed_bit = self.bdd_variable_set.mk_var(*primed_var);
+                let set = relation.and(&primed_bit).exists(&primed_variables);
+                let unset = relation.and_not(&primed_bit).exists(&primed_variables);
+                if !set.and(&unset).is_false() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("the update of variable `{}` is not deterministic", name),
+                    ));
+                }
+                let updated = aig.translate(&set, &latch_literals);
+                let next = aig.ite(fires_first, updated, latch_literals[&var]);
+                next_state.insert(var, next);
+            }
+        }
+        let constraint = aig.translate(&self.unit_vertex_set, &latch_literals);
+
+        let and_gates = aig.and_gates();
+        writeln!(
+            writer,
+            "aag {} {} {} 0 {} 0 1",
+            aig.max_variable_index(),
+            self.variables_transition_relation_and_domain.len(),
+            latches.len(),
+            and_gates.len()
+        )?;
+        for idx in 0..self.variables_transition_relation_and_domain.len() {
+            writeln!(writer, "{}", aig.input(idx))?;
+        }
+        for var in &latches {
+            // resetting to the latch itself marks it as uninitialised
+            let latch = latch_literals[var];
+            writeln!(writer, "{} {} {}", latch, next_state[var], latch)?;
+        }
+        writeln!(writer, "{}", constraint)?;
+        for (lhs, rhs0, rhs1) in and_gates {
+            writeln!(writer, "{} {} {}", lhs, rhs0, rhs1)?;
+        }
+        for (idx, (name, _)) in self
+            .variables_transition_relation_and_domain
+            .iter()
+            .enumerate()
+        {
+            writeln!(writer, "i{} fire_{}", idx, name)?;
+        }
+        for (idx, var) in latches.iter().enumerate() {
+            writeln!(writer, "l{} {}", idx, self.bdd_variable_set.name_of(*var))?;
+        }
+        Ok(())
+    }
 
-        let source_states_transition_relation = source_states_primed_set.and(transition_relation);
+    /// Returns all the states reachable from the `initial` states (including themselves).
+    ///
+    /// Uses the saturation strategy: the variables are tried from the last one (in
+    /// the [SmartSystemUpdateFn::variables_in_bdd_order]), and after any variable adds new
+    /// states, the search continues from the last variable again.
+    pub fn reachable_from(&self, initial: &Bdd) -> Bdd {
+        let order = self.variables_in_bdd_order();
+        let mut reachable = self.sanitize(initial);
+        'fixpoint: loop {
+            for name in order.iter().rev() {
+                let successors = self.successors_async(name, &reachable);
+                if !successors.imp(&reachable).is_true() {
+                    reachable = reachable.or(&successors);
+                    continue 'fixpoint;
+                }
+            }
+            return reachable;
+        }
+    }
 
-        source_states_transition_relation.exists(primed_domain.raw_bdd_variables().as_slice())
+    /// Returns the weak basin of the `attractor`, i.e. all the states that *can* reach
+    /// (some state of) the `attractor`, including the `attractor` itself.
+    pub fn weak_basin(&self, attractor: &Bdd) -> Bdd {
+        let order = self.variables_in_bdd_order();
+        let mut basin = self.sanitize(attractor);
+        'fixpoint: loop {
+            for name in order.iter().rev() {
+                let predecessors = self.predecessors_under_variable(name, &basin);
+                if !predecessors.imp(&basin).is_true() {
+                    basin = basin.or(&predecessors);
+                    continue 'fixpoint;
+                }
+            }
+            return basin;
+        }
+    }
+
+    /// Returns the strong basin of the `attractor`, i.e. all the states that *must* reach
+    /// (some state of) the `attractor`, because they cannot escape to any state that
+    /// cannot reach it.
+    ///
+    /// Computed as the greatest fixpoint within the [SmartSystemUpdateFn::weak_basin]: states
+    /// having a successor outside of the current candidate set are removed until none remain.
+    pub fn strong_basin(&self, attractor: &Bdd) -> Bdd {
+        let order = self.variables_in_bdd_order();
+        let mut basin = self.weak_basin(attractor);
+        'fixpoint: loop {
+            let outside = self.complement(&basin);
+            for name in order.iter().rev() {
+                let can_escape = self.predecessors_under_variable(name, &outside).and(&basin);
+                if !can_escape.is_false() {
+                    basin = basin.and_not(&can_escape);
+                    continue 'fixpoint;
+                }
+            }
+            return basin;
+        }
+    }
+
+    /// Returns the union of all the attractors (bottom SCCs) of the system, i.e. the states
+    /// that can reach back each state reachable from them. If the system has parameters,
+    /// the attractors are computed separately for each parameter valuation.
+    pub fn attractor_states(&self) -> Bdd {
+        // each pivot fixes the parameters as well, so that its reachable sets do not mix
+        // the parameter valuations
+        let fixed_variables = self
+            .standard_variables
+            .iter()
+            .copied()
+            .chain(self.parameter_variables())
+            .collect::<Vec<_>>();
+        let mut attractors = self.bdd_variable_set.mk_false();
+        let mut universe = self.unit_vertex_set.clone();
+        while let Some(witness) = universe.sat_witness() {
+            let mut pivot_data = BddPartialValuation::empty();
+            for var in &fixed_variables {
+                pivot_data.set_value(*var, witness.value(*var));
+            }
+            let pivot = self.bdd_variable_set.mk_conjunctive_clause(&pivot_data);
+
+            // the pivot lies in an attractor iff it can reach back everything it reaches;
+            // otherwise, neither it nor any state that reaches it lies in an attractor
+            let reachable = self.reachable_from(&pivot);
+            let basin = self.weak_basin(&pivot);
+            if reachable.imp(&basin).is_true() {
+                attractors = attractors.or(&reachable);
+            }
+            universe = universe.and_not(&basin);
+        }
+        attractors
+    }
+
+    /// Returns the states from which the system is guaranteed to reach some attractor, i.e.
+    /// every path from them (not counting the loops of a state to itself) eventually enters
+    /// an attractor; this is the CTL property `AF attractor`. The states of the transient
+    /// cycles (and the states that can reach them) are excluded, since the system can cycle
+    /// there forever.
+    ///
+    /// Computed as the complement of [SmartSystemUpdateFn::eg] of the states outside of
+    /// the attractors.
+    pub fn terminating_states(&self) -> Bdd {
+        self.complement(&self.eg(&self.complement(&self.attractor_states())))
+    }
+
+    /// The CTL operator `EX phi`: the valid states that have a successor in `phi`.
+    ///
+    /// Only the proper successors are considered, except for the deadlock states, which are
+    /// their own (only) successors; this way, every state has at least one successor.
+    pub fn ex(&self, phi: &Bdd) -> Bdd {
+        let phi = self.sanitize(phi);
+        let stuck_in_phi = phi.and(&self.deadlock_states());
+        self.variables_transition_relation_and_domain
+            .iter()
+            .fold(stuck_in_phi, |acc, (name, _)| {
+                acc.or(&self.predecessors_async_exclude_loops(name, &phi))
+            })
+            .and(&self.unit_vertex_set)
+    }
+
+    /// The CTL operator `EF phi`: the valid states from which some path reaches `phi`;
+    /// the same as [SmartSystemUpdateFn::weak_basin].
+    pub fn ef(&self, phi: &Bdd) -> Bdd {
+        self.weak_basin(phi)
+    }
+
+    /// The CTL operator `EG phi`: the valid states from which some infinite path stays
+    /// in `phi` forever (see [SmartSystemUpdateFn::ex] for the treatment of self-loops).
+    ///
+    /// Computed as the greatest fixpoint of the states of `phi` having a successor
+    /// within the set.
+    pub fn eg(&self, phi: &Bdd) -> Bdd {
+        let mut result = self.sanitize(phi);
+        loop {
+            let next = result.and(&self.ex(&result));
+            if next == result {
+                return result;
+            }
+            result = next;
+        }
+    }
+
+    /// Compute a maximal subspace within the given symbolic `set`, i.e. a product of sets of
+    /// values (one for each variable) whose states all belong to the `set`.
+    ///
+    /// Starts from a single state of the `set` and greedily adds the values of each variable
+    /// (in the order of [SmartSystemUpdateFn::get_system_variables]) that keep the subspace
+    /// inside the `set`. The result is maximal (no value can be added to it), but not
+    /// necessarily the largest subspace within the `set`; if the `set` is itself a subspace,
+    /// the whole `set` is returned. If the system has parameters, they are fixed to a single
+    /// valuation (the one of the initial state).
+    pub fn pick_subspace(&self, set: &Bdd) -> Bdd {
+        let set = self.sanitize(set);
+        let witness = set
+            .sat_witness()
+            .expect("Cannot pick subspace from an empty set.");
+        let mut fixed = BddPartialValuation::empty();
+        for var in self
+            .standard_variables
+            .iter()
+            .chain(&self.parameter_variables())
+        {
+            fixed.set_value(*var, witness.value(*var))
+        }
+
+        let mut result = self
+            .bdd_variable_set
+            .mk_conjunctive_clause(&fixed)
+            .and(&self.unit_vertex_set);
+        for (_, info) in &self.variables_transition_relation_and_domain {
+            // the values of the other variables, which are kept for any added value
+            let others = result.exists(&info.domain.raw_bdd_variables());
+            for value in info.domain.get_all_possible_values() {
+                let candidate =
+                    result.or(&others.and(&info.domain.encode_one(&self.bdd_variable_set, &value)));
+                if candidate.imp(&set).is_true() {
+                    result = candidate;
+                }
+            }
+        }
+        result
+    }
+
+    pub fn encode_one(&self, variable_name: &str, value: &T) -> Bdd {
+        let VarInfo { domain, .. } = self
+            .get_transition_relation_and_domain(variable_name)
+            .expect("unknown variable");
+        domain.encode_one(&self.bdd_variable_set, value)
+    }
+
+    pub fn bdd_to_dot_string(&self, bdd: &Bdd) -> String {
+        bdd.to_dot_string(&self.bdd_variable_set, false)
+    }
+}
+
+impl<DO, T> SmartSystemUpdateFn<DO, T>
+where
+    DO: SymbolicDomainOrd<T>,
+{
+    /// The union of [SmartSystemUpdateFn::successors_async] under all the system variables,
+    /// i.e. all the states reachable from `source_states_set` in a single asynchronous step.
+    pub fn successors_async_any(&self, source_states_set: &Bdd) -> Bdd {
+        self.successors_any_with_policy(source_states_set, SelfLoopPolicy::Include)
+    }
+
+    /// The union of [SmartSystemUpdateFn::predecessors_async] under all the system variables,
+    /// i.e. all the states that reach `source_states_set` in a single asynchronous step.
+    pub fn predecessors_async_any(&self, source_states_set: &Bdd) -> Bdd {
+        self.predecessors_any_with_policy(source_states_set, SelfLoopPolicy::Include)
+    }
+
+    /// Like [SmartSystemUpdateFn::successors_async_any], but the self-loops are included or
+    /// excluded based on the `policy`; see [SmartSystemUpdateFn::successors_with_policy].
+    pub fn successors_any_with_policy(
+        &self,
+        source_states_set: &Bdd,
+        policy: SelfLoopPolicy,
+    ) -> Bdd {
+        self.union_over_variables(|name| {
+            self.successors_with_policy(name, source_states_set, policy)
+        })
+    }
+
+    /// Like [SmartSystemUpdateFn::predecessors_async_any], but the self-loops are included or
+    /// excluded based on the `policy`; see [SmartSystemUpdateFn::predecessors_with_policy].
+    pub fn predecessors_any_with_policy(
+        &self,
+        source_states_set: &Bdd,
+        policy: SelfLoopPolicy,
+    ) -> Bdd {
+        self.union_over_variables(|name| {
+            self.predecessors_with_policy(name, source_states_set, policy)
+        })
+    }
+
+    fn union_over_variables(&self, step: impl Fn(&str) -> Bdd) -> Bdd {
+        self.variables_transition_relation_and_domain
+            .iter()
+            .fold(self.bdd_variable_set.mk_false(), |acc, (name, _)| {
+                acc.or(&step(name))
+            })
+    }
+
+    /// True iff the subspace given by the `fixed` values of some of the variables (the remaining
+    /// variables are free) is a trap set, i.e. no asynchronous step leaves it.
+    ///
+    /// Panics if the subspace cannot be encoded (see [SmartSystemUpdateFn::encode_subspace]).
+    pub fn subspace_is_trap(&self, fixed: &HashMap<String, T>) -> bool
+    where
+        T: Debug + Clone,
+    {
+        let subspace = self.encode_subspace_or_panic(fixed);
+        self.successors_async_any(&subspace)
+            .imp(&subspace)
+            .is_true()
+    }
+
+    /// The set of the states forward-reachable from the subspace given by the `fixed` values
+    /// of some of the variables (including the subspace itself).
+    ///
+    /// Panics if the subspace cannot be encoded (see [SmartSystemUpdateFn::encode_subspace]).
+    pub fn subspace_closure(&self, fixed: &HashMap<String, T>) -> Bdd
+    where
+        T: Debug + Clone,
+    {
+        self.reachable_from(&self.encode_subspace_or_panic(fixed))
+    }
+
+    /// True iff some state of `from` can reach some state of `to` using only the states
+    /// of the `universe` (including the endpoints).
+    ///
+    /// The search is bidirectional: it alternates a forward step from `from` and a backward
+    /// step from `to`, returning as soon as the two explored sets meet. For targets that are
+    /// reachable, this typically explores far fewer states than a full forward reachability.
+    pub fn can_reach(&self, from: &Bdd, to: &Bdd, universe: &Bdd) -> bool {
+        let universe = self.sanitize(universe);
+        let mut forward = from.and(&universe);
+        let mut backward = to.and(&universe);
+        if !forward.and(&backward).is_false() {
+            return true;
+        }
+
+        // only the newly discovered states need to be expanded; since the explored sets
+        // have been disjoint so far, they meet iff a new frontier hits the other set
+        let mut forward_frontier = forward.clone();
+        let mut backward_frontier = backward.clone();
+        while !forward_frontier.is_false() || !backward_frontier.is_false() {
+            forward_frontier = self
+                .successors_async_any(&forward_frontier)
+                .and(&universe)
+                .and_not(&forward);
+            if !forward_frontier.and(&backward).is_false() {
+                return true;
+            }
+            forward = forward.or(&forward_frontier);
+
+            backward_frontier = self
+                .predecessors_async_any(&backward_frontier)
+                .and(&universe)
+                .and_not(&backward);
+            if !backward_frontier.and(&forward).is_false() {
+                return true;
+            }
+            backward = backward.or(&backward_frontier);
+        }
+
+        false
+    }
+
+    fn encode_subspace_or_panic(&self, fixed: &HashMap<String, T>) -> Bdd
+    where
+        T: Debug + Clone,
+    {
+        self.encode_subspace(fixed)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+/// With the `parallel` feature, the per-variable steps of the `*_any` operations are independent,
+/// so they can be computed concurrently (using `rayon`) and then reduced using `or`. This requires
+/// the system to be shared between the threads, i.e. `DO: Sync` and `T: Sync`, which holds for all
+/// the provided domains.
+#[cfg(feature = "parallel")]
+impl<DO, T> SmartSystemUpdateFn<DO, T>
+where
+    DO: SymbolicDomainOrd<T> + Sync,
+    T: Sync,
+{
+    /// Same as [SmartSystemUpdateFn::successors_async_any], but the per-variable steps
+    /// are computed concurrently.
+    pub fn par_successors_async_any(&self, source_states_set: &Bdd) -> Bdd {
+        self.par_union_over_variables(|name| self.successors_async(name, source_states_set))
+    }
+
+    /// Same as [SmartSystemUpdateFn::predecessors_async_any], but the per-variable steps
+    /// are computed concurrently.
+    pub fn par_predecessors_async_any(&self, source_states_set: &Bdd) -> Bdd {
+        self.par_union_over_variables(|name| {
+            self.predecessors_async(name, source_states_set.clone())
+        })
+    }
+
+    fn par_union_over_variables(&self, step: impl Fn(&str) -> Bdd + Sync) -> Bdd {
+        use rayon::prelude::*;
+
+        self.variables_transition_relation_and_domain
+            .par_iter()
+            .map(|(name, _)| step(name))
+            .reduce(|| self.bdd_variable_set.mk_false(), |acc, it| acc.or(&it))
+    }
+}
+
+/// Builds an and-inverter graph (AIG) with a fixed number of inputs and latches; the literals
+/// follow the AIGER conventions (`2 * variable index`, plus one if negated; `0` is `false`).
+struct AigBuilder {
+    num_inputs: usize,
+    num_latches: usize,
+    /// the right-hand sides of the and gates, in the order of their (left-hand side) literals
+    and_gates: Vec<(u32, u32)>,
+    /// structural hashing, so that each distinct gate is created once
+    known_gates: HashMap<(u32, u32), u32>,
+}
+
+impl AigBuilder {
+    const FALSE: u32 = 0;
+    const TRUE: u32 = 1;
+
+    fn new(num_inputs: usize, num_latches: usize) -> Self {
+        Self {
+            num_inputs,
+            num_latches,
+            and_gates: Vec::new(),
+            known_gates: HashMap::new(),
+        }
+    }
+
+    fn negate(literal: u32) -> u32 {
+        literal ^ 1
+    }
+
+    fn literal(variable_index: usize) -> u32 {
+        u32::try_from(2 * variable_index).expect("the circuit is too large for AIGER")
+    }
+
+    fn input(&self, idx: usize) -> u32 {
+        Self::literal(1 + idx)
+    }
+
+    fn latch(&self, idx: usize) -> u32 {
+        Self::literal(1 + self.num_inputs + idx)
+    }
+
+    fn max_variable_index(&self) -> usize {
+        self.num_inputs + self.num_latches + self.and_gates.len()
+    }
+
+    fn and(&mut self, lhs: u32, rhs: u32) -> u32 {
+        let (lhs, rhs) = (lhs.max(rhs), lhs.min(rhs));
+        if rhs == Self::FALSE || lhs == Self::negate(rhs) {
+            return Self::FALSE;
+        }
+        if rhs == Self::TRUE || lhs == rhs {
+            return lhs;
+        }
+        if let Some(gate) = self.known_gates.get(&(lhs, rhs)) {
+            return *gate;
+        }
+        self.and_gates.push((lhs, rhs));
+        let gate = Self::literal(self.max_variable_index());
+        self.known_gates.insert((lhs, rhs), gate);
+        gate
+    }
+
+    fn or(&mut self, lhs: u32, rhs: u32) -> u32 {
+        Self::negate(self.and(Self::negate(lhs), Self::negate(rhs)))
+    }
+
+    /// `if condition then on_true else on_false`
+    fn ite(&mut self, condition: u32, on_true: u32, on_false: u32) -> u32 {
+        let when_true = self.and(condition, on_true);
+        let when_false = self.and(Self::negate(condition), on_false);
+        self.or(when_true, when_false)
+    }
+
+    /// The Shannon expansion of the `bdd`, where each [BddVariable] is replaced by the given
+    /// literal (the `bdd` must not depend on any other variable).
+    fn translate(&mut self, bdd: &Bdd, literals: &HashMap<BddVariable, u32>) -> u32 {
+        // the children of a node always precede it, and the terminals come first
+        let mut translated = Vec::with_capacity(bdd.size());
+        for idx in 0..bdd.size() {
+            let node = BddPointer::from_index(idx);
+            let literal = match node.as_bool() {
+                Some(value) => u32::from(value),
+                None => {
+                    let condition = literals[&bdd.var_of(node)];
+                    let on_true = translated[bdd.high_link_of(node).to_index()];
+                    let on_false = translated[bdd.low_link_of(node).to_index()];
+                    self.ite(condition, on_true, on_false)
+                }
+            };
+            translated.push(literal);
+        }
+        translated[bdd.root_pointer().to_index()]
     }
 
-    /// Like `predecessors_async`, but a state that "transitions" to itself under
-    /// given transition variable is not considered to be a proper predecessor,
-    /// therefore is not included in the result (unless it is a proper predecessor
-    /// of another state from `source_states`).
-    pub fn predecessors_async_exclude_loops(
-        &self,
-        _variable_name: &str,
-        _source_states: &Bdd,
-    ) -> Bdd {
-        // todo better to directly construct the specific no_loop_transition_bdd during construction
-        // todo probably have some common, underlying method that would accept the transition bdd
-        // todo the two public methods would then just pass in the proper transition bdd
-        // self.predecessors_async(transition_variable_name, source_states)
-        //     .and(&self.those_states_capable_of_transitioning_under(transition_variable_name))
-        todo!()
+    /// The and gates as `(lhs, rhs0, rhs1)` literals.
+    fn and_gates(&self) -> Vec<(u32, u32, u32)> {
+        let first_gate = 1 + self.num_inputs + self.num_latches;
+        self.and_gates
+            .iter()
+            .enumerate()
+            .map(|(idx, (rhs0, rhs1))| (Self::literal(first_gate + idx), *rhs0, *rhs1))
+            .collect()
     }
+}
 
-    fn get_transition_relation_and_domain(&self, variable_name: &str) -> Option<&VarInfo<DO, T>> {
-        self.mapper
-            .get(variable_name)
-            .map(|idx| &self.variables_transition_relation_and_domain[*idx].1)
+/// Decodes the value of the `domain` from a (full) `valuation` of the BDD variables.
+fn decode_valuation<D: SymbolicDomain<T>, T>(domain: &D, valuation: &BddValuation) -> T {
+    let mut bits = BddPartialValuation::empty();
+    domain
+        .raw_bdd_variables()
+        .into_iter()
+        .for_each(|var| bits.set_value(var, valuation.value(var)));
+    domain.decode_bits(&bits)
+}
+
+/// The values `0, 1, ..., count - 1` of type `T`, obtained by counting (without ever computing
+/// the value `count`, which may not fit into `T`).
+fn first_values<T: Copy + From<u8> + std::ops::Add<Output = T>>(count: usize) -> Vec<T> {
+    let mut values: Vec<T> = Vec::with_capacity(count);
+    for _ in 0..count {
+        let value = match values.last() {
+            Some(last) => *last + T::from(1),
+            None => T::from(0),
+        };
+        values.push(value);
     }
+    values
+}
 
-    fn those_states_capable_of_transitioning_under(&self, _variable_name: &str) -> Bdd {
-        // todo this should be stored in a field; built during construction
-        todo!()
+/// Moves the `bdd` into a context of `num_vars` variables, replacing its variables according to
+/// the `translation` (the variables missing from it are kept).
+///
+/// If the translation preserves the order of the variables the `bdd` depends on, its nodes are
+/// only renamed; otherwise, the `bdd` is rebuilt node by node, which is much more expensive.
+pub(crate) fn translate_bdd(
+    bdd: &Bdd,
+    translation: &HashMap<BddVariable, BddVariable>,
+    num_vars: u16,
+) -> Bdd {
+    let translate_var = |var: BddVariable| translation.get(&var).copied().unwrap_or(var);
+    let mut support = bdd.support_set().into_iter().collect::<Vec<_>>();
+    support.sort();
+    if support
+        .windows(2)
+        .all(|pair| translate_var(pair[0]) < translate_var(pair[1]))
+    {
+        let mut translated = bdd.clone();
+        // safety: the translation preserves the order of the variables (which is also checked
+        // by `rename_variables`), so the renamed bdd is valid & represents the same function
+        unsafe {
+            translated.set_num_vars(num_vars.max(bdd.num_vars()));
+            translated.rename_variables(translation);
+            translated.set_num_vars(num_vars);
+        }
+        return translated;
     }
 
-    pub fn encode_one(&self, variable_name: &str, value: &T) -> Bdd {
-        let VarInfo { domain, .. } = self
-            .get_transition_relation_and_domain(variable_name)
-            .expect("unknown variable");
-        domain.encode_one(&self.bdd_variable_set, value)
+    let context = BddVariableSet::new_anonymous(num_vars);
+    // the nodes are stored bottom-up (terminals first), so the children of every node
+    // are translated before the node itself
+    let mut translated: Vec<Bdd> = Vec::with_capacity(bdd.size());
+    for node in (0..bdd.size()).map(BddPointer::from_index) {
+        let node_bdd = match (node.is_terminal(), node.is_one()) {
+            (true, true) => context.mk_true(),
+            (true, false) => context.mk_false(),
+            (false, _) => Bdd::if_then_else(
+                &context.mk_var(translate_var(bdd.var_of(node))),
+                &translated[bdd.high_link_of(node).to_index()],
+                &translated[bdd.low_link_of(node).to_index()],
+            ),
+        };
+        translated.push(node_bdd);
     }
+    translated.pop().expect("every bdd has a node")
+}
 
-    pub fn bdd_to_dot_string(&self, bdd: &Bdd) -> String {
-        bdd.to_dot_string(&self.bdd_variable_set, false)
+/// Renames the `from` variables of the `bdd` to the `to` variables (pairwise); used to move
+/// a set between the bits of a domain and the bits of its primed copy.
+///
+/// The result represents the same function (only over the renamed variables) iff:
+///  - the `bdd` does not depend on any of the `to` variables, which would otherwise be
+///    conflated with the renamed ones, and
+///  - the renaming preserves the relative order of all the variables the `bdd` depends on.
+///
+/// The latter holds for the domains allocated by this module, since the bits of the primed
+/// copy are allocated right after the bits of the domain. Both are checked by
+/// [Bdd::rename_variables], which panics otherwise (the former is also checked
+/// in debug builds, to report the violation more clearly).
+fn rename_domain(mut bdd: Bdd, from: &[BddVariable], to: &[BddVariable]) -> Bdd {
+    debug_assert_eq!(from.len(), to.len());
+    debug_assert!(
+        {
+            let support = bdd.support_set();
+            to.iter().all(|var| !support.contains(var))
+        },
+        "the set already depends on the variables it is being renamed to"
+    );
+    let renaming = from.iter().copied().zip(to.iter().copied()).collect();
+    // safety: the conditions above make the renaming semantic; `rename_variables` checks
+    // them & panics if they are violated, so it never produces an invalid BDD
+    unsafe { bdd.rename_variables(&renaming) };
+    bdd
+}
+
+/// The name under which the system variable (or parameter) `variable_name` is encoded in the
+/// [BddVariableSet] of a [SystemUpdateFn] or a [SmartSystemUpdateFn].
+///
+/// The prime symbol `'` (tick) marks the primed copies of the variables, so any tick within
+/// `variable_name` is escaped by doubling it. Thanks to that, the names of the un-primed
+/// variables never end with an odd number of ticks, and cannot clash with the primed names.
+pub fn internal_name(variable_name: &str) -> String {
+    variable_name.replace('\'', "''")
+}
+
+/// The inverse of [internal_name]. Returns `None` if `internal_name` is not a valid encoding
+/// of any un-primed variable (e.g. it is the name of a primed variable).
+pub fn display_name(internal_name: &str) -> Option<String> {
+    let mut result = String::with_capacity(internal_name.len());
+    let mut chars = internal_name.chars();
+    while let Some(c) = chars.next() {
+        if c == '\'' && chars.next() != Some('\'') {
+            return None;
+        }
+        result.push(c);
     }
+    Some(result)
 }
 
 fn find_bdd_variables_prime<D, T>(
@@ -716,35 +3825,35 @@ where
         .expect("should be present")
 }
 
-fn find_max_values<DO, T>(
-    vars_and_their_update_fns: &[(String, UnprocessedVariableUpdateFn<T>)],
-) -> HashMap<&str, &T>
+fn find_max_values<'a, DO, T, I>(vars_and_their_update_fns: I) -> HashMap<&'a str, &'a T>
 where
     DO: SymbolicDomainOrd<T>,
+    T: 'a,
+    I: IntoIterator<Item = (&'a String, &'a UnprocessedVariableUpdateFn<T>)> + Clone,
 {
-    let max_outputs =
-        vars_and_their_update_fns
-            .iter()
-            .fold(HashMap::new(), |mut acc, (var_name, update_fn)| {
-                let max_value = update_fn
-                    .terms
-                    .iter()
-                    .map(|(val, _)| val)
-                    .chain(Some(&update_fn.default))
-                    .max_by(|x, y| DO::cmp(x, y))
-                    .expect("default value always present");
-                // no balls
-                // // SAFETY: there is always at least the default value
-                // let max_value = unsafe { max_value_option.unwrap_unchecked() };
-                acc.insert(var_name.as_str(), max_value);
-                acc
-            });
+    let max_outputs = vars_and_their_update_fns.clone().into_iter().fold(
+        HashMap::new(),
+        |mut acc, (var_name, update_fn)| {
+            let max_value = update_fn
+                .terms
+                .iter()
+                .map(|(val, _)| val)
+                .chain(Some(&update_fn.default))
+                .max_by(|x, y| DO::cmp(x, y))
+                .expect("default value always present");
+            // no balls
+            // // SAFETY: there is always at least the default value
+            // let max_value = unsafe { max_value_option.unwrap_unchecked() };
+            acc.insert(var_name.as_str(), max_value);
+            acc
+        },
+    );
 
     // the following step is necessary on "faulty" datasets, that compare variables
     //  with values that are out of the domain of the variable
     //  e.g. `target eq 999` when (integer) `target` has max value 2
     vars_and_their_update_fns
-        .iter()
+        .into_iter()
         .flat_map(|(_var_name, update_fn)| update_fn.terms.iter().map(|(_, expr)| expr))
         .fold(max_outputs, |mut acc, expr| {
             update_max::<DO, T>(&mut acc, expr);
@@ -760,6 +3869,10 @@ where
         Expression::Terminal(proposition) => {
             update_from_proposition::<DO, T>(acc, proposition);
         }
+        Expression::VariableComparison(_) => {
+            // no constant involved; the values of both operands are already within their domains
+            //  and pairs of values from two different domains are compared directly
+        }
         Expression::Not(expression) => {
             update_max::<DO, T>(acc, expression);
         }
@@ -794,29 +3907,355 @@ fn update_from_proposition<'a, DO, T>(
         .or_insert(value);
 }
 
+/// Combines the `bdds` using the (associative) `op` in a balanced binary tree, rather than
+/// left-to-right; this keeps the intermediate results of wide conjunctions/disjunctions
+/// smaller. Returns `None` if there are no `bdds`.
+fn balanced_fold(mut bdds: Vec<Bdd>, op: &impl Fn(&Bdd, &Bdd) -> Bdd) -> Option<Bdd> {
+    match bdds.len() {
+        0 => None,
+        1 => bdds.pop(),
+        len => {
+            let right = bdds.split_off(len / 2);
+            let left = balanced_fold(bdds, op).expect("non-empty half");
+            let right = balanced_fold(right, op).expect("non-empty half");
+            Some(op(&left, &right))
+        }
+    }
+}
+
 pub mod variable_update_fn {
     use std::collections::HashMap;
 
-    use biodivine_lib_bdd::{Bdd, BddVariable, BddVariableSet};
+    use biodivine_lib_bdd::{Bdd, BddPartialValuation, BddVariable, BddVariableSet};
+    use thiserror::Error;
 
+    use super::balanced_fold;
     use crate::{
         expression_components::{
             expression::Expression,
-            proposition::{ComparisonOperator as CmpOp, Proposition},
+            proposition::{ComparisonOperator as CmpOp, Proposition, VariableComparison},
         },
         symbolic_domains::symbolic_domain::SymbolicDomainOrd,
         update::unprocessed_variable_update_function::UnprocessedVariableUpdateFn as UnprocessedFn,
     };
 
-    #[derive(Debug)]
-    pub struct VariableUpdateFn {
-        pub bit_answering_bdds: Vec<(BddVariable, Bdd)>,
+    /// How the terms of an update function are combined when several of them match
+    /// the same state; see [VariableUpdateFn::try_from_update_fn].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub enum TermSemantics {
+        /// The first matching term determines the output.
+        #[default]
+        FirstWins,
+        /// No two terms may match the same (valid) state.
+        RequireDisjoint,
+    }
+
+    /// Two terms of the update function of `target` (indexed from zero, in the order of
+    /// their declaration) match the same state; see [TermSemantics::RequireDisjoint].
+    #[derive(Error, Debug, Clone, PartialEq, Eq)]
+    #[error("terms {first} and {second} of the update function of `{target}` overlap")]
+    pub struct OverlappingTerms {
+        pub target: String,
+        pub first: usize,
+        pub second: usize,
     }
 
-    impl VariableUpdateFn {
+    /// The `condition` (a set of states) as an [Expression] over the variables of
+    /// `named_symbolic_domains`; see [VariableUpdateFn::to_expression].
+    pub(crate) fn condition_to_expression<DO, T>(
+        condition: &Bdd,
+        bdd_variable_set: &BddVariableSet,
+        named_symbolic_domains: &HashMap<&str, &DO>,
+    ) -> Expression<T>
+    where
+        DO: SymbolicDomainOrd<T>,
+    {
+        // sorted, so that the order of the propositions does not depend on the map
+        let mut domains = named_symbolic_domains.iter().collect::<Vec<_>>();
+        domains.sort_unstable_by_key(|(name, _)| **name);
+        let mut condition = domains.iter().fold(condition.clone(), |acc, (_, domain)| {
+            acc.and(&domain.unit_collection(bdd_variable_set))
+        });
+        // the variables the condition does not depend on are left out; otherwise, their
+        // unit collections would split the clauses
+        domains.retain(|(_, domain)| {
+            let forgotten = condition.exists(&domain.raw_bdd_variables());
+            let is_free = forgotten.and(&domain.unit_collection(bdd_variable_set)) == condition;
+            if is_free {
+                condition = forgotten;
+            }
+            !is_free
+        });
+
+        // each clause of a valid condition is a product of the sets of values of the domains
+        let clauses = condition
+            .sat_clauses()
+            .map(|clause| {
+                let clause = bdd_variable_set.mk_conjunctive_clause(&clause);
+                let propositions = domains
+                    .iter()
+                    .filter_map(|(name, domain)| {
+                        let all_values = domain.get_all_possible_values();
+                        let count = all_values.len();
+                        let values = all_values
+                            .into_iter()
+                            .filter(|it| {
+                                !domain
+                                    .encode_one(bdd_variable_set, it)
+                                    .and(&clause)
+                                    .is_false()
+                            })
+                            .map(|it| {
+                                Expression::Terminal(Proposition::new(
+                                    CmpOp::Eq,
+                                    name.to_string(),
+                                    it,
+                                ))
+                            })
+                            .collect::<Vec<_>>();
+                        // a variable that may take any value is not constrained
+                        (values.len() < count).then_some(Expression::Or(values))
+                    })
+                    .collect();
+                Expression::And(propositions)
+            })
+            .collect();
+
+        Expression::Or(clauses).flatten()
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct VariableUpdateFn<T> {
+        /// for each bit of the target variable, the condition under which the updated value
+        /// has that bit set; see [VariableUpdateFn::bit_answering_bdds]
+        pub(crate) bit_answering_bdds: Vec<(BddVariable, Bdd)>,
+        /// for each distinct output value, the (mutually exclusive) condition under which
+        /// the target variable is updated to that value
+        pub(crate) value_conditions: Vec<(T, Bdd)>,
+    }
+
+    /// Two update functions are equal iff they answer the same bits (of the target variable)
+    /// using the same BDDs, regardless of the order of the bits. The `value_conditions` are
+    /// derived from the same function as the bits, so they are not compared.
+    impl<T> PartialEq for VariableUpdateFn<T> {
+        fn eq(&self, other: &Self) -> bool {
+            fn sorted_bits<T>(update_fn: &VariableUpdateFn<T>) -> Vec<&(BddVariable, Bdd)> {
+                let mut bits = update_fn.bit_answering_bdds.iter().collect::<Vec<_>>();
+                bits.sort_by_key(|(bdd_variable, _)| *bdd_variable);
+                bits
+            }
+            sorted_bits(self) == sorted_bits(other)
+        }
+    }
+
+    impl<T> Eq for VariableUpdateFn<T> {}
+
+    impl<T> VariableUpdateFn<T> {
+        /// The bits of the updated value of the target variable, as functions of the current
+        /// state. This is the core of the encoding of the update function.
+        ///
+        /// There is one entry `(bit, bdd)` for each [BddVariable] of the (un-primed) domain of
+        /// the target variable, in the order of its [SymbolicDomain::raw_bdd_variables]
+        /// (a domain encoded using no bits has no entries). The `bdd` is over the un-primed
+        /// variables and holds exactly in the states in which the value the target is updated
+        /// to has the `bit` set, i.e. in which the *primed* copy of the `bit` (the variable
+        /// at the same position within the primed domain) is set after the update.
+        ///
+        /// Within the valid states (the unit collections of all the domains), the bits always
+        /// encode a value of the target domain, namely the output of the update function
+        /// (see [VariableUpdateFn::condition_for_value]). Outside of them, the BDDs are
+        /// unspecified. Hence, the transition relation of the target variable is formed by
+        /// the conjunction of `primed_bit.iff(bdd)` over all the entries, intersected with
+        /// the unit collections of the (un-primed) domains and with the primed unit collection
+        /// of the target; the latter only removes the invalid states.
+        ///
+        /// [SymbolicDomain::raw_bdd_variables]: crate::symbolic_domains::symbolic_domain::SymbolicDomain::raw_bdd_variables
+        pub fn bit_answering_bdds(&self) -> &[(BddVariable, Bdd)] {
+            &self.bit_answering_bdds
+        }
+
+        /// The condition under which the target variable is updated to `value`, or `None`
+        /// if the update function never outputs `value`.
+        pub fn condition_for_value(&self, value: &T) -> Option<&Bdd>
+        where
+            T: PartialEq,
+        {
+            self.value_conditions
+                .iter()
+                .find_map(|(output, condition)| (output == value).then_some(condition))
+        }
+
+        /// The condition under which the target variable is updated to `value` (see
+        /// [VariableUpdateFn::condition_for_value]), recovered as an [Expression] over
+        /// the variables of `named_symbolic_domains` (the domains the update function was
+        /// built with).
+        ///
+        /// The expression is a disjunction of conjunctions, each constraining some of
+        /// the variables to one (or a disjunction of several) `variable == value` propositions.
+        /// It is equivalent to the condition within the valid states. For a value that is never
+        /// output, the expression is the constant `false` (an empty [Expression::Or]).
+        pub fn to_expression<DO>(
+            &self,
+            value: &T,
+            bdd_variable_set: &BddVariableSet,
+            named_symbolic_domains: &HashMap<&str, &DO>,
+        ) -> Expression<T>
+        where
+            DO: SymbolicDomainOrd<T>,
+            T: PartialEq,
+        {
+            match self.condition_for_value(value) {
+                Some(condition) => {
+                    condition_to_expression(condition, bdd_variable_set, named_symbolic_domains)
+                }
+                None => Expression::Or(Vec::new()),
+            }
+        }
+
         /// target_variable_name is a key in named_symbolic_domains
-        pub fn from_update_fn<DO, T>(
+        pub fn from_update_fn<DO>(
+            update_fn: &UnprocessedFn<T>,
+            target_variable_name: &str,
+            bdd_variable_set: &BddVariableSet,
+            named_symbolic_domains: &HashMap<&str, &DO>,
+        ) -> Self
+        where
+            DO: SymbolicDomainOrd<T>,
+        {
+            let conditions =
+                Self::term_conditions(update_fn, bdd_variable_set, named_symbolic_domains);
+            Self::from_term_conditions(
+                update_fn,
+                conditions,
+                target_variable_name,
+                bdd_variable_set,
+                named_symbolic_domains,
+            )
+        }
+
+        /// Like [VariableUpdateFn::from_update_fn] (which always uses
+        /// [TermSemantics::FirstWins]), but the overlapping terms can be rejected instead.
+        ///
+        /// Under [TermSemantics::RequireDisjoint], the first pair of terms whose conditions
+        /// are satisfied by a common valid state is reported as [OverlappingTerms]. The
+        /// default value is not a term, so it never overlaps.
+        pub fn try_from_update_fn<DO>(
+            update_fn: &UnprocessedFn<T>,
+            target_variable_name: &str,
+            bdd_variable_set: &BddVariableSet,
+            named_symbolic_domains: &HashMap<&str, &DO>,
+            semantics: TermSemantics,
+        ) -> Result<Self, OverlappingTerms>
+        where
+            DO: SymbolicDomainOrd<T>,
+        {
+            let conditions =
+                Self::term_conditions(update_fn, bdd_variable_set, named_symbolic_domains);
+
+            if semantics == TermSemantics::RequireDisjoint {
+                let unit = named_symbolic_domains
+                    .values()
+                    .fold(bdd_variable_set.mk_true(), |acc, domain| {
+                        acc.and(&domain.unit_collection(bdd_variable_set))
+                    });
+                if let Some((first, second)) = first_overlap(&conditions, &unit, bdd_variable_set) {
+                    return Err(OverlappingTerms {
+                        target: target_variable_name.to_string(),
+                        first,
+                        second,
+                    });
+                }
+            }
+
+            Ok(Self::from_term_conditions(
+                update_fn,
+                conditions,
+                target_variable_name,
+                bdd_variable_set,
+                named_symbolic_domains,
+            ))
+        }
+
+        /// The conditions of the terms of `update_fn` (in their order), each on its own, i.e.
+        /// not yet restricted by the earlier terms.
+        fn term_conditions<DO>(
+            update_fn: &UnprocessedFn<T>,
+            bdd_variable_set: &BddVariableSet,
+            named_symbolic_domains: &HashMap<&str, &DO>,
+        ) -> Vec<Bdd>
+        where
+            DO: SymbolicDomainOrd<T>,
+        {
+            update_fn
+                .terms
+                .iter()
+                .map(|(_, match_condition)| {
+                    bdd_from_expression(match_condition, named_symbolic_domains, bdd_variable_set)
+                })
+                .collect()
+        }
+
+        /// [VariableUpdateFn::from_update_fn] with the `conditions` of the terms already
+        /// computed (see [VariableUpdateFn::term_conditions]).
+        fn from_term_conditions<DO>(
+            update_fn: &UnprocessedFn<T>,
+            conditions: Vec<Bdd>,
+            target_variable_name: &str,
+            bdd_variable_set: &BddVariableSet,
+            named_symbolic_domains: &HashMap<&str, &DO>,
+        ) -> Self
+        where
+            DO: SymbolicDomainOrd<T>,
+        {
+            let target_domain = named_symbolic_domains
+                .get(target_variable_name)
+                .expect("must know the domain of the target variable");
+
+            // Boolean networks are by far the most common input
+            if target_domain.get_all_possible_values().len() == 2 {
+                return Self::from_boolean_update_fn(
+                    update_fn,
+                    conditions,
+                    *target_domain,
+                    bdd_variable_set,
+                );
+            }
+
+            Self::from_term_conditions_general(
+                update_fn,
+                conditions,
+                target_variable_name,
+                bdd_variable_set,
+                named_symbolic_domains,
+            )
+        }
+
+        /// The same as [VariableUpdateFn::from_update_fn], but never takes the shortcut for
+        /// Boolean target variables; the result is the same, only computed in a more
+        /// expensive way. Useful for validating the shortcut.
+        pub fn from_update_fn_general<DO>(
+            update_fn: &UnprocessedFn<T>,
+            target_variable_name: &str,
+            bdd_variable_set: &BddVariableSet,
+            named_symbolic_domains: &HashMap<&str, &DO>,
+        ) -> Self
+        where
+            DO: SymbolicDomainOrd<T>,
+        {
+            let conditions =
+                Self::term_conditions(update_fn, bdd_variable_set, named_symbolic_domains);
+            Self::from_term_conditions_general(
+                update_fn,
+                conditions,
+                target_variable_name,
+                bdd_variable_set,
+                named_symbolic_domains,
+            )
+        }
+
+        fn from_term_conditions_general<DO>(
             update_fn: &UnprocessedFn<T>,
+            conditions: Vec<Bdd>,
             target_variable_name: &str,
             bdd_variable_set: &BddVariableSet,
             named_symbolic_domains: &HashMap<&str, &DO>,
@@ -828,14 +4267,8 @@ pub mod variable_update_fn {
 
             let (outputs, bdd_conds): (Vec<_>, Vec<_>) = terms
                 .iter()
-                .map(|(val, match_condition)| {
-                    let match_condition_bdd = bdd_from_expression(
-                        match_condition,
-                        named_symbolic_domains,
-                        bdd_variable_set,
-                    );
-                    (val, match_condition_bdd)
-                })
+                .map(|(val, _)| val)
+                .zip(conditions)
                 .chain(Some((default, bdd_variable_set.mk_true())))
                 .unzip();
 
@@ -860,24 +4293,163 @@ pub mod variable_update_fn {
                 .map(|output| target_domain.raw_bdd_variables_encode(output))
                 .collect::<Vec<_>>();
 
-            let bit_answering_bdds = (0..bit_matrix[0].len()).map(|bit_idx| {
-                (0..bit_matrix.len()).fold(bdd_variable_set.mk_false(), |acc, row_idx| {
-                    if bit_matrix[row_idx][bit_idx] {
-                        acc.or(&values_mutally_exclusive_terms[row_idx])
+            // rows of `bit_matrix` are equal iff the output values are equal; the owned values
+            // are decoded back from the bits, so that `T` does not need to be `Clone`
+            let value_conditions = bit_matrix.iter().enumerate().fold(
+                Vec::<(&Vec<bool>, Bdd)>::new(),
+                |mut acc, (row_idx, row)| {
+                    let condition = &values_mutally_exclusive_terms[row_idx];
+                    match acc.iter_mut().find(|(bits, _)| *bits == row) {
+                        Some((_, merged)) => *merged = merged.or(condition),
+                        None => acc.push((row, condition.clone())),
+                    }
+                    acc
+                },
+            );
+            let value_conditions = value_conditions
+                .into_iter()
+                .map(|(bits, condition)| {
+                    let mut valuation = BddPartialValuation::empty();
+                    target_domain
+                        .raw_bdd_variables()
+                        .into_iter()
+                        .zip(bits)
+                        .for_each(|(var, bit)| valuation.set_value(var, *bit));
+                    (target_domain.decode_bits(&valuation), condition)
+                })
+                .collect();
+
+            // iterating over the variables of the domain (rather than the width of the rows)
+            // also covers domains encoded using no bits at all (i.e. constant zero)
+            let bit_answering_bdds = target_domain
+                .raw_bdd_variables()
+                .into_iter()
+                .enumerate()
+                .map(|(bit_idx, bdd_variable)| {
+                    let bit_answering_bdd = bit_matrix.iter().enumerate().fold(
+                        bdd_variable_set.mk_false(),
+                        |acc, (row_idx, row)| {
+                            if row[bit_idx] {
+                                acc.or(&values_mutally_exclusive_terms[row_idx])
+                            } else {
+                                acc
+                            }
+                        },
+                    );
+                    (bdd_variable, bit_answering_bdd)
+                })
+                .collect();
+
+            Self {
+                bit_answering_bdds,
+                value_conditions,
+            }
+        }
+
+        /// [VariableUpdateFn::from_update_fn] for a target domain with exactly two values.
+        ///
+        /// Instead of making the term conditions mutually exclusive, the terms are folded
+        /// in reverse: a matching term overrides whatever the later terms (and the default)
+        /// output, which is exactly the "first match wins" semantics. This yields the
+        /// condition of the greater value; the condition of the other one is its complement.
+        fn from_boolean_update_fn<DO>(
+            update_fn: &UnprocessedFn<T>,
+            conditions: Vec<Bdd>,
+            target_domain: &DO,
+            bdd_variable_set: &BddVariableSet,
+        ) -> Self
+        where
+            DO: SymbolicDomainOrd<T>,
+        {
+            let UnprocessedFn { terms, default, .. } = update_fn;
+
+            let mut values = target_domain.get_all_possible_values();
+            values.sort_by(DO::cmp);
+            let is_high = |value: &T| DO::cmp(value, &values[1]) == std::cmp::Ordering::Equal;
+
+            let initial = if is_high(default) {
+                bdd_variable_set.mk_true()
+            } else {
+                bdd_variable_set.mk_false()
+            };
+            let high_condition = terms.iter().zip(conditions).rev().fold(
+                initial,
+                |acc, ((output, _), condition)| {
+                    if is_high(output) {
+                        condition.or(&acc)
                     } else {
-                        acc
+                        acc.and_not(&condition)
+                    }
+                },
+            );
+            let low_condition = high_condition.not();
+
+            // the output values in the order of their first appearance, as in the general case
+            let outputs_high = terms
+                .iter()
+                .map(|(output, _)| output)
+                .chain(Some(default))
+                .map(is_high)
+                .fold(Vec::new(), |mut acc, output_high| {
+                    if !acc.contains(&output_high) {
+                        acc.push(output_high);
                     }
+                    acc
+                });
+
+            let low_bits = target_domain.raw_bdd_variables_encode(&values[0]);
+            let high_bits = target_domain.raw_bdd_variables_encode(&values[1]);
+            let bit_answering_bdds = target_domain
+                .raw_bdd_variables()
+                .into_iter()
+                .zip(low_bits.into_iter().zip(high_bits))
+                .map(|(bdd_variable, bits)| {
+                    let bit_answering_bdd = match bits {
+                        (false, false) => bdd_variable_set.mk_false(),
+                        (true, false) => low_condition.clone(),
+                        (false, true) => high_condition.clone(),
+                        (true, true) => bdd_variable_set.mk_true(),
+                    };
+                    (bdd_variable, bit_answering_bdd)
                 })
-            });
+                .collect();
+
+            let high = values.pop().expect("the domain has two values");
+            let low = values.pop().expect("the domain has two values");
+            let (mut high, mut low) = (Some((high, high_condition)), Some((low, low_condition)));
+            let value_conditions = outputs_high
+                .into_iter()
+                .filter_map(|output_high| if output_high { high.take() } else { low.take() })
+                .collect();
 
             Self {
-                bit_answering_bdds: target_domain
-                    .raw_bdd_variables()
-                    .into_iter()
-                    .zip(bit_answering_bdds)
-                    .collect(),
+                bit_answering_bdds,
+                value_conditions,
+            }
+        }
+    }
+
+    /// The first pair `(first, second)` of the `conditions` (`first < second`) satisfied by
+    /// a common state of `unit`. The union of the earlier conditions is tracked, so the pair
+    /// is only searched for once some condition actually overlaps it.
+    fn first_overlap(
+        conditions: &[Bdd],
+        unit: &Bdd,
+        bdd_variable_set: &BddVariableSet,
+    ) -> Option<(usize, usize)> {
+        let mut seen_states = bdd_variable_set.mk_false();
+        for (second, condition) in conditions.iter().enumerate() {
+            let condition = condition.and(unit);
+            if !seen_states.and(&condition).is_false() {
+                let first = conditions[..second]
+                    .iter()
+                    .position(|earlier| !earlier.and(&condition).is_false())
+                    .expect("some earlier condition overlaps");
+                return Some((first, second));
             }
+            seen_states = seen_states.or(&condition);
         }
+        None
     }
 
     fn bdd_from_expression<DO, T>(
@@ -892,30 +4464,29 @@ pub mod variable_update_fn {
             Expression::Terminal(proposition) => {
                 bdd_from_proposition(proposition, named_symbolic_domains, bdd_variable_set)
             }
+            Expression::VariableComparison(comparison) => {
+                bdd_from_variable_comparison(comparison, named_symbolic_domains, bdd_variable_set)
+            }
             Expression::Not(expression) => {
                 bdd_from_expression(expression, named_symbolic_domains, bdd_variable_set).not()
             }
             Expression::And(clauses) => {
-                clauses
+                let bdds = clauses
                     .iter()
-                    .fold(bdd_variable_set.mk_true(), |acc, clausule| {
-                        acc.and(&bdd_from_expression(
-                            clausule,
-                            named_symbolic_domains,
-                            bdd_variable_set,
-                        ))
+                    .map(|clause| {
+                        bdd_from_expression(clause, named_symbolic_domains, bdd_variable_set)
                     })
+                    .collect();
+                balanced_fold(bdds, &Bdd::and).unwrap_or_else(|| bdd_variable_set.mk_true())
             }
             Expression::Or(clauses) => {
-                clauses
+                let bdds = clauses
                     .iter()
-                    .fold(bdd_variable_set.mk_false(), |acc, clausule| {
-                        acc.or(&bdd_from_expression(
-                            clausule,
-                            named_symbolic_domains,
-                            bdd_variable_set,
-                        ))
+                    .map(|clause| {
+                        bdd_from_expression(clause, named_symbolic_domains, bdd_variable_set)
                     })
+                    .collect();
+                balanced_fold(bdds, &Bdd::or).unwrap_or_else(|| bdd_variable_set.mk_false())
             }
             Expression::Xor(lhs, rhs) => {
                 let lhs = bdd_from_expression(lhs, named_symbolic_domains, bdd_variable_set);
@@ -948,13 +4519,162 @@ pub mod variable_update_fn {
 
         match proposition.comparison_operator {
             CmpOp::Eq => target_vars_domain.encode_one(bdd_variable_set, &proposition.value),
-            CmpOp::Neq => target_vars_domain
-                .encode_one(bdd_variable_set, &proposition.value)
-                .not(),
+            // a plain `not()` would also admit the invalid encodings of the domain
+            CmpOp::Neq => target_vars_domain.encode_one_not(bdd_variable_set, &proposition.value),
             CmpOp::Lt => target_vars_domain.encode_lt(bdd_variable_set, &proposition.value),
             CmpOp::Leq => target_vars_domain.encode_le(bdd_variable_set, &proposition.value),
             CmpOp::Gt => target_vars_domain.encode_gt(bdd_variable_set, &proposition.value),
             CmpOp::Geq => target_vars_domain.encode_ge(bdd_variable_set, &proposition.value),
         }
     }
+
+    /// Enumerates all the pairs of values of the two compared variables; the domains
+    /// are typically small, so this is cheaper than building a dedicated comparator.
+    fn bdd_from_variable_comparison<DO, T>(
+        comparison: &VariableComparison,
+        named_symbolic_domains: &HashMap<&str, &DO>,
+        bdd_variable_set: &BddVariableSet,
+    ) -> Bdd
+    where
+        DO: SymbolicDomainOrd<T>,
+    {
+        let domain_of = |variable_name: &str| {
+            *named_symbolic_domains
+                .get(variable_name)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Symbolic domain for variable {} should be avilable, but is not",
+                        variable_name
+                    )
+                })
+        };
+        let lhs_domain = domain_of(&comparison.lhs);
+        let rhs_domain = domain_of(&comparison.rhs);
+
+        lhs_domain.get_all_possible_values().into_iter().fold(
+            bdd_variable_set.mk_false(),
+            |acc, lhs_value| {
+                let satisfying_rhs_values = rhs_domain
+                    .get_all_possible_values()
+                    .into_iter()
+                    .filter(|rhs_value| {
+                        comparison
+                            .comparison_operator
+                            .holds_for(DO::cmp(&lhs_value, rhs_value))
+                    })
+                    .fold(bdd_variable_set.mk_false(), |acc, rhs_value| {
+                        acc.or(&rhs_domain.encode_one(bdd_variable_set, &rhs_value))
+                    });
+
+                acc.or(&lhs_domain
+                    .encode_one(bdd_variable_set, &lhs_value)
+                    .and(&satisfying_rhs_values))
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use biodivine_lib_bdd::{Bdd, BddVariableSetBuilder};
+
+    use super::{balanced_fold, variable_update_fn::VariableUpdateFn};
+    use crate::{
+        expression_components::{
+            expression::Expression,
+            proposition::{ComparisonOperator, Proposition},
+        },
+        symbolic_domains::symbolic_domain::{
+            BinaryIntegerDomain, SymbolicDomain, SymbolicDomainOrd,
+        },
+        update::unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+    };
+
+    /// In a wide conjunction, the balanced fold never builds the large prefix that the left fold
+    /// does (each `x == y` stays unresolved until the matching `x == 0` is reached).
+    #[test]
+    fn balanced_fold_has_smaller_peak() {
+        let names = ["a", "b", "c", "d", "e", "f", "g", "h"];
+        let mut builder = BddVariableSetBuilder::new();
+        // all the `x`-s precede all the `y`-s, so each unresolved `x == y` doubles the BDD
+        let xs = names.map(|name| BinaryIntegerDomain::<u8>::new(&mut builder, name, &1));
+        let ys =
+            names.map(|name| BinaryIntegerDomain::<u8>::new(&mut builder, &name.repeat(2), &1));
+        let variables = builder.build();
+        let index = |name: char| (name as u8 - b'a') as usize;
+        let same = |name: char| {
+            let (x, y) = (&xs[index(name)], &ys[index(name)]);
+            (0..=1).fold(variables.mk_false(), |acc, value| {
+                acc.or(&x
+                    .encode_one(&variables, &value)
+                    .and(&y.encode_one(&variables, &value)))
+            })
+        };
+        let zero = |name: char| xs[index(name)].encode_one(&variables, &0);
+        let clauses = vec![
+            same('a'),
+            same('b'),
+            same('f'),
+            zero('f'),
+            same('g'),
+            zero('g'),
+            same('h'),
+            zero('h'),
+            same('c'),
+            same('d'),
+            same('e'),
+            zero('e'),
+            zero('a'),
+            zero('b'),
+            zero('c'),
+            zero('d'),
+        ];
+
+        let peak = std::cell::Cell::new(0);
+        let and = |lhs: &Bdd, rhs: &Bdd| {
+            let result = lhs.and(rhs);
+            peak.set(peak.get().max(result.size()));
+            result
+        };
+        let left = clauses
+            .iter()
+            .fold(variables.mk_true(), |acc, clause| and(&acc, clause));
+        let left_peak = peak.replace(0);
+
+        let balanced = balanced_fold(clauses, &and).expect("non-empty");
+        let balanced_peak = peak.get();
+
+        assert_eq!(balanced, left);
+        assert!(
+            balanced_peak < left_peak,
+            "balanced peak {} should be below left peak {}",
+            balanced_peak,
+            left_peak
+        );
+    }
+
+    #[test]
+    fn equality_ignores_the_order_of_the_bits() {
+        let mut builder = BddVariableSetBuilder::new();
+        let a = BinaryIntegerDomain::<u8>::new(&mut builder, "a", &3);
+        let variables = builder.build();
+        let domains = HashMap::from([("a", &a)]);
+        let update_fn = UnprocessedVariableUpdateFn::new(
+            vec!["a".to_string()],
+            "a".to_string(),
+            vec![(
+                3,
+                Expression::Terminal(Proposition::new(ComparisonOperator::Eq, "a".to_string(), 1)),
+            )],
+            1,
+        );
+        let compiled = VariableUpdateFn::from_update_fn(&update_fn, "a", &variables, &domains);
+        assert!(compiled.bit_answering_bdds().len() > 1);
+
+        let mut reordered = compiled.clone();
+        reordered.bit_answering_bdds.reverse();
+        assert_eq!(reordered, compiled);
+    }
 }
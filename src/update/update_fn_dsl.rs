@@ -0,0 +1,386 @@
+use thiserror::Error;
+
+use crate::expression_components::{
+    expression::Expression,
+    proposition::{ComparisonOperator, Proposition, VariableComparison},
+};
+use crate::update::unprocessed_variable_update_function::UnprocessedVariableUpdateFn;
+
+/// Returned when the source of an update function cannot be parsed; see [parse_update_fn].
+/// The positions are byte offsets into the source.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("unexpected character `{character}` at {position}")]
+    UnexpectedCharacter { position: usize, character: char },
+    #[error("expected {expected} at {position}, found `{found}`")]
+    UnexpectedToken {
+        position: usize,
+        expected: &'static str,
+        found: String,
+    },
+    #[error("expected {expected}, found the end of the input")]
+    UnexpectedEnd { expected: &'static str },
+    #[error("`{value}` at {position} is not a valid value")]
+    InvalidValue { position: usize, value: String },
+    #[error("the default value is missing")]
+    MissingDefault,
+    #[error("the default value is given more than once (again at {position})")]
+    DuplicateDefault { position: usize },
+}
+
+/// Parse the update function of the variable `target` from a compact textual syntax, e.g.
+/// `2: A >= 1 & B < 3; 1: C == 0; default 0`.
+///
+/// The terms are separated by semicolons and are tried in the order of their appearance
+/// (the first matching one wins). Each term is either `value: condition`, or
+/// `default value`, which must appear exactly once (anywhere in the list).
+///
+/// The conditions consist of comparisons of a variable with a value (`A >= 1`, or `1 <= A`)
+/// or of two variables (`A < B`), using any spelling of a [ComparisonOperator]; of the
+/// constants `true` and `false`; and of the connectives `!`, `&`, `|`, `^` and `=>`, listed
+/// from the tightest binding one. All the binary connectives except for `=>` (which is right
+/// associative) are left associative; parentheses can be used for grouping.
+///
+/// The input variables of the resulting function are the variables referenced by
+/// the conditions, in the order of their first appearance.
+pub fn parse_update_fn(
+    target: &str,
+    src: &str,
+) -> Result<UnprocessedVariableUpdateFn<u8>, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(src)?,
+        position: 0,
+        input_vars_names: Vec::new(),
+    };
+
+    let mut terms = Vec::new();
+    let mut default = None;
+    loop {
+        match parser.peek() {
+            None => break,
+            Some((_, Token::Semicolon)) => {
+                // empty terms (e.g. a trailing semicolon) are skipped
+                parser.position += 1;
+                continue;
+            }
+            Some((position, Token::Identifier(keyword))) if keyword == "default" => {
+                let position = *position;
+                parser.position += 1;
+                let value = parser.value()?;
+                if default.replace(value).is_some() {
+                    return Err(ParseError::DuplicateDefault { position });
+                }
+            }
+            Some(_) => {
+                let value = parser.value()?;
+                parser.expect(Token::Colon, "`:`")?;
+                let condition = parser.implication()?;
+                terms.push((value, condition));
+            }
+        }
+
+        match parser.next() {
+            None | Some((_, Token::Semicolon)) => {}
+            Some((position, token)) => {
+                return Err(ParseError::UnexpectedToken {
+                    position,
+                    expected: "`;`",
+                    found: token.to_string(),
+                })
+            }
+        }
+    }
+
+    let default = default.ok_or(ParseError::MissingDefault)?;
+    Ok(UnprocessedVariableUpdateFn::new(
+        parser.input_vars_names,
+        target.to_string(),
+        terms,
+        default,
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Identifier(String),
+    Number(String),
+    Comparison(ComparisonOperator),
+    Not,
+    And,
+    Or,
+    Xor,
+    Implies,
+    LeftParenthesis,
+    RightParenthesis,
+    Colon,
+    Semicolon,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Identifier(it) | Token::Number(it) => write!(f, "{}", it),
+            Token::Comparison(operator) => write!(f, "{:#}", operator),
+            Token::Not => write!(f, "!"),
+            Token::And => write!(f, "&"),
+            Token::Or => write!(f, "|"),
+            Token::Xor => write!(f, "^"),
+            Token::Implies => write!(f, "=>"),
+            Token::LeftParenthesis => write!(f, "("),
+            Token::RightParenthesis => write!(f, ")"),
+            Token::Colon => write!(f, ":"),
+            Token::Semicolon => write!(f, ";"),
+        }
+    }
+}
+
+/// Splits the `src` into tokens, each paired with its (byte) position.
+fn tokenize(src: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = src.char_indices().peekable();
+    while let Some((position, character)) = chars.next() {
+        let token = match character {
+            it if it.is_whitespace() => continue,
+            '(' => Token::LeftParenthesis,
+            ')' => Token::RightParenthesis,
+            ':' => Token::Colon,
+            ';' => Token::Semicolon,
+            '&' => Token::And,
+            '|' => Token::Or,
+            '^' => Token::Xor,
+            '!' | '=' | '<' | '>' => {
+                // all the two-character operators end with `=` or `>`
+                let mut operator = character.to_string();
+                if let Some((_, it)) = chars.next_if(|(_, it)| matches!(it, '=' | '>')) {
+                    operator.push(it);
+                }
+                match operator.as_str() {
+                    "!" => Token::Not,
+                    "=>" => Token::Implies,
+                    comparison => Token::Comparison(comparison.parse().map_err(|_| {
+                        ParseError::UnexpectedToken {
+                            position,
+                            expected: "an operator",
+                            found: operator.clone(),
+                        }
+                    })?),
+                }
+            }
+            it if it.is_ascii_digit() => {
+                let mut number = it.to_string();
+                while let Some((_, digit)) = chars.next_if(|(_, it)| it.is_ascii_digit()) {
+                    number.push(digit);
+                }
+                Token::Number(number)
+            }
+            it if it.is_alphabetic() || it == '_' => {
+                let mut identifier = it.to_string();
+                while let Some((_, it)) =
+                    chars.next_if(|(_, it)| it.is_alphanumeric() || *it == '_')
+                {
+                    identifier.push(it);
+                }
+                Token::Identifier(identifier)
+            }
+            character => {
+                return Err(ParseError::UnexpectedCharacter {
+                    position,
+                    character,
+                })
+            }
+        };
+        tokens.push((position, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    /// index of the next token to process
+    position: usize,
+    input_vars_names: Vec<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&(usize, Token)> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<(usize, Token)> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Consumes the next token if it is the given one.
+    fn accept(&mut self, token: &Token) -> bool {
+        let accepted = matches!(self.peek(), Some((_, it)) if it == token);
+        if accepted {
+            self.position += 1;
+        }
+        accepted
+    }
+
+    fn expect(&mut self, token: Token, expected: &'static str) -> Result<(), ParseError> {
+        match self.next() {
+            Some((_, it)) if it == token => Ok(()),
+            Some((position, found)) => Err(ParseError::UnexpectedToken {
+                position,
+                expected,
+                found: found.to_string(),
+            }),
+            None => Err(ParseError::UnexpectedEnd { expected }),
+        }
+    }
+
+    fn value(&mut self) -> Result<u8, ParseError> {
+        match self.next() {
+            Some((position, Token::Number(value))) => parse_value(position, value),
+            Some((position, found)) => Err(ParseError::UnexpectedToken {
+                position,
+                expected: "a value",
+                found: found.to_string(),
+            }),
+            None => Err(ParseError::UnexpectedEnd {
+                expected: "a value",
+            }),
+        }
+    }
+
+    /// `xor (=> implication)?`
+    fn implication(&mut self) -> Result<Expression<u8>, ParseError> {
+        let lhs = self.exclusive_disjunction()?;
+        if self.accept(&Token::Implies) {
+            let rhs = self.implication()?;
+            return Ok(Expression::Implies(Box::new(lhs), Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    /// `or (^ or)*`
+    fn exclusive_disjunction(&mut self) -> Result<Expression<u8>, ParseError> {
+        let mut result = self.disjunction()?;
+        while self.accept(&Token::Xor) {
+            let rhs = self.disjunction()?;
+            result = Expression::Xor(Box::new(result), Box::new(rhs));
+        }
+        Ok(result)
+    }
+
+    /// `and (| and)*`
+    fn disjunction(&mut self) -> Result<Expression<u8>, ParseError> {
+        let mut clauses = vec![self.conjunction()?];
+        while self.accept(&Token::Or) {
+            clauses.push(self.conjunction()?);
+        }
+        Ok(match clauses.len() {
+            1 => clauses.pop().expect("there is a clause"),
+            _ => Expression::Or(clauses),
+        })
+    }
+
+    /// `negation (& negation)*`
+    fn conjunction(&mut self) -> Result<Expression<u8>, ParseError> {
+        let mut clauses = vec![self.negation()?];
+        while self.accept(&Token::And) {
+            clauses.push(self.negation()?);
+        }
+        Ok(match clauses.len() {
+            1 => clauses.pop().expect("there is a clause"),
+            _ => Expression::And(clauses),
+        })
+    }
+
+    /// `!negation | (implication) | true | false | comparison`
+    fn negation(&mut self) -> Result<Expression<u8>, ParseError> {
+        const EXPECTED: &str = "a condition";
+        match self.peek() {
+            Some((_, Token::Not)) => {
+                self.position += 1;
+                Ok(Expression::Not(Box::new(self.negation()?)))
+            }
+            Some((_, Token::LeftParenthesis)) => {
+                self.position += 1;
+                let inner = self.implication()?;
+                self.expect(Token::RightParenthesis, "`)`")?;
+                Ok(inner)
+            }
+            Some((_, Token::Identifier(it))) if it == "true" => {
+                self.position += 1;
+                Ok(Expression::And(Vec::new()))
+            }
+            Some((_, Token::Identifier(it))) if it == "false" => {
+                self.position += 1;
+                Ok(Expression::Or(Vec::new()))
+            }
+            Some((_, Token::Identifier(_) | Token::Number(_))) => self.comparison(),
+            Some((position, found)) => Err(ParseError::UnexpectedToken {
+                position: *position,
+                expected: EXPECTED,
+                found: found.to_string(),
+            }),
+            None => Err(ParseError::UnexpectedEnd { expected: EXPECTED }),
+        }
+    }
+
+    /// `variable op value | value op variable | variable op variable`
+    fn comparison(&mut self) -> Result<Expression<u8>, ParseError> {
+        let lhs = self.next().expect("checked by the caller");
+        let operator = match self.next() {
+            Some((_, Token::Comparison(operator))) => operator,
+            Some((position, found)) => {
+                return Err(ParseError::UnexpectedToken {
+                    position,
+                    expected: "a comparison operator",
+                    found: found.to_string(),
+                })
+            }
+            None => {
+                return Err(ParseError::UnexpectedEnd {
+                    expected: "a comparison operator",
+                })
+            }
+        };
+        let rhs = self.next().ok_or(ParseError::UnexpectedEnd {
+            expected: "a variable or a value",
+        })?;
+
+        let expression = match (lhs, rhs) {
+            ((_, Token::Identifier(variable)), (position, Token::Number(value))) => {
+                let value = parse_value(position, value)?;
+                self.reference(&variable);
+                Expression::Terminal(Proposition::new(operator, variable, value))
+            }
+            ((position, Token::Number(value)), (_, Token::Identifier(variable))) => {
+                let value = parse_value(position, value)?;
+                self.reference(&variable);
+                Expression::Terminal(Proposition::new(operator.flip(), variable, value))
+            }
+            ((_, Token::Identifier(lhs)), (_, Token::Identifier(rhs))) => {
+                self.reference(&lhs);
+                self.reference(&rhs);
+                Expression::VariableComparison(VariableComparison::new(operator, lhs, rhs))
+            }
+            (_, (position, found)) => {
+                return Err(ParseError::UnexpectedToken {
+                    position,
+                    expected: "a variable",
+                    found: found.to_string(),
+                })
+            }
+        };
+        Ok(expression)
+    }
+
+    fn reference(&mut self, variable: &str) {
+        if !self.input_vars_names.iter().any(|it| it == variable) {
+            self.input_vars_names.push(variable.to_string());
+        }
+    }
+}
+
+fn parse_value(position: usize, value: String) -> Result<u8, ParseError> {
+    value
+        .parse()
+        .map_err(|_| ParseError::InvalidValue { position, value })
+}
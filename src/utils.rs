@@ -1,6 +1,6 @@
 use biodivine_lib_bdd::{Bdd, BddPartialValuation};
 use num_bigint::BigInt;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::ops::Shr;
 
@@ -13,27 +13,6 @@ use std::ops::Shr;
 use crate::symbolic_domains::symbolic_domain::SymbolicDomain;
 use crate::update::update_fn::SmartSystemUpdateFn;
 
-/// Compute a [Bdd] which represents a single (un-primed) state within the given symbolic `set`.
-pub fn pick_state_bdd<D: SymbolicDomain<u8> + Debug>(
-    system: &SmartSystemUpdateFn<D, u8>,
-    set: &Bdd,
-) -> Bdd {
-    // Unfortunately, this is now a bit more complicated than it needs to be, because
-    // we have to ignore the primed variables, but it shouldn't bottleneck anything outside of
-    // truly extreme cases.
-    let standard_variables = system.standard_variables();
-    let valuation = set
-        .sat_witness()
-        .expect("Cannot pick state from an empty set.");
-    let mut state_data = BddPartialValuation::empty();
-    for var in standard_variables {
-        state_data.set_value(var, valuation.value(var))
-    }
-    system
-        .get_bdd_variable_set()
-        .mk_conjunctive_clause(&state_data)
-}
-
 /// Pick a state from a symbolic set and "decode" it into normal integers.
 pub fn pick_state_map<D: SymbolicDomain<u8> + Debug>(
     system: &SmartSystemUpdateFn<D, u8>,
@@ -67,8 +46,44 @@ pub fn encode_state_map<D: SymbolicDomain<u8> + Debug>(
     system.get_bdd_variable_set().mk_conjunctive_clause(&result)
 }
 
+/// Decode all the (valid) states of `set` into the values of the variables, in the order
+/// of [SmartSystemUpdateFn::get_system_variables]. Meant for comparing sets across encodings,
+/// since the number of states can be exponential in the number of variables.
+pub fn decode_states<D: SymbolicDomain<u8> + Debug>(
+    system: &SmartSystemUpdateFn<D, u8>,
+    set: &Bdd,
+) -> HashSet<Vec<u8>> {
+    let states = system.project_to_states(set);
+    let domains = system
+        .get_system_variables()
+        .into_iter()
+        .map(|var| system.get_domain(&var).expect("known variables"))
+        .collect::<Vec<_>>();
+    states
+        .sat_valuations()
+        .map(|valuation| {
+            let valuation = BddPartialValuation::from(valuation);
+            domains
+                .iter()
+                .map(|domain| domain.decode_bits(&valuation))
+                .collect()
+        })
+        .collect()
+}
+
+/// The size of `set` relative to `universe` on a logarithmic scale, in percent.
+///
+/// The empty set is always at `0.0`. A non-empty set within a universe of (at most) a single
+/// valuation is at `100.0`, since the logarithm of such universe is zero.
 pub fn log_percent(set: &Bdd, universe: &Bdd) -> f64 {
-    set.cardinality().log2() / universe.cardinality().log2() * 100.0
+    if set.is_false() {
+        return 0.0;
+    }
+    let universe_cardinality = universe.cardinality();
+    if universe_cardinality <= 1.0 {
+        return 100.0;
+    }
+    set.cardinality().log2() / universe_cardinality.log2() * 100.0
 }
 
 /// Compute an (approximate) count of state in the given `set` using the encoding of `system`.
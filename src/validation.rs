@@ -0,0 +1,249 @@
+use num_bigint::BigInt;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use thiserror::Error;
+
+use crate::symbolic_domains::symbolic_domain::{
+    BinaryIntegerDomain, GrayCodeIntegerDomain, PetriNetIntegerDomain, SymbolicDomainOrd,
+    UnaryIntegerDomain,
+};
+use crate::update::reachability::ReachabilitySession;
+use crate::update::update_fn::SmartSystemUpdateFn;
+use crate::utils::{count_states_exact, decode_states, encode_state_map, pick_state_map};
+use crate::xml_parsing::utils::find_start_of;
+
+/// The state counts of the encodings diverged; see [assert_encodings_agree].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("encodings disagree at step {step}: {} (unary) <> {} (binary) <> {} (gray) <> {} (petri net)", counts[0], counts[1], counts[2], counts[3])]
+pub struct Mismatch {
+    pub step: usize,
+    /// state counts in the order unary, binary, gray, petri net
+    pub counts: Vec<BigInt>,
+}
+
+/// The encodings diverged, either in the number of reached states or in the states
+/// themselves; see [assert_encodings_agree_deep].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DeepMismatch {
+    #[error(transparent)]
+    Counts(#[from] Mismatch),
+    /// The numbers of the reached states agree, but the `state` (the values of the variables,
+    /// sorted by name) is reached by only one of the unary and the other `encoding`.
+    #[error("encodings disagree at step {step}: state {state:?} is reached by only one of unary and {encoding}")]
+    States {
+        step: usize,
+        encoding: &'static str,
+        state: Vec<u8>,
+    },
+}
+
+/// Run (at most) `steps` steps of forward reachability on the model at `sbml_path` using
+/// all four integer encodings at once, and check that the encodings agree on the number
+/// of reached states after every step.
+///
+/// Every time the reachability converges, it is restarted from some state that was not
+/// reached yet (the same for all encodings). Hence, the computation stops before `steps`
+/// only if the whole state space is explored.
+///
+/// # Panics
+///
+/// Panics if the model cannot be loaded.
+pub fn assert_encodings_agree(sbml_path: &str, steps: usize) -> Result<(), Mismatch> {
+    run_in_lockstep(sbml_path, steps, Lockstep::compare_encodings)
+}
+
+/// Like [assert_encodings_agree], but after every step, the encodings must also agree on
+/// the reached states themselves, not just on their number. This catches the bugs that
+/// produce wrong states of the right count, but is much more expensive, since all the reached
+/// states are enumerated (see [Lockstep::compare_encodings_deep]).
+///
+/// # Panics
+///
+/// Panics if the model cannot be loaded.
+pub fn assert_encodings_agree_deep(sbml_path: &str, steps: usize) -> Result<(), DeepMismatch> {
+    run_in_lockstep(sbml_path, steps, Lockstep::compare_encodings_deep)
+}
+
+/// Runs the reachability of [assert_encodings_agree], checking the encodings using `compare`
+/// after every step.
+fn run_in_lockstep<E>(
+    sbml_path: &str,
+    steps: usize,
+    compare: impl Fn(&Lockstep, usize) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut computation = Lockstep::new(sbml_path);
+
+    for step in 0..steps {
+        if computation.can_initialize() {
+            if computation.is_done() {
+                break;
+            }
+            computation.initialize();
+            compare(&computation, step)?;
+        }
+
+        computation.perform_fwd_step();
+        compare(&computation, step + 1)?;
+    }
+
+    Ok(())
+}
+
+/// Reachability running on the same model in all four integer encodings at once, one step
+/// at a time, so that the results of the encodings can be compared after every step.
+pub struct Lockstep {
+    session_unary: ReachabilitySession<UnaryIntegerDomain, u8>,
+    session_binary: ReachabilitySession<BinaryIntegerDomain<u8>, u8>,
+    session_gray: ReachabilitySession<GrayCodeIntegerDomain<u8>, u8>,
+    session_petri_net: ReachabilitySession<PetriNetIntegerDomain, u8>,
+}
+
+/// A generic function that builds [SmartSystemUpdateFn] from an SBML file.
+fn build_update_fn<D: SymbolicDomainOrd<u8> + Debug>(
+    sbml_path: &str,
+) -> SmartSystemUpdateFn<D, u8> {
+    let file = std::fs::File::open(sbml_path).expect("Cannot open SBML file.");
+    let reader = std::io::BufReader::new(file);
+    let mut xml = xml::reader::EventReader::new(reader);
+
+    find_start_of(&mut xml, &["listOfTransitions"])
+        .expect("Cannot find transitions in the SBML file.");
+
+    SmartSystemUpdateFn::try_from_xml(&mut xml).expect("Loading system fn update failed.")
+}
+
+impl Lockstep {
+    pub fn new(sbml_path: &str) -> Lockstep {
+        Lockstep {
+            session_unary: ReachabilitySession::new(build_update_fn(sbml_path)),
+            session_binary: ReachabilitySession::new(build_update_fn(sbml_path)),
+            session_gray: ReachabilitySession::new(build_update_fn(sbml_path)),
+            session_petri_net: ReachabilitySession::new(build_update_fn(sbml_path)),
+        }
+    }
+
+    /// True if the computation explored all states of the system.
+    pub fn is_done(&self) -> bool {
+        self.session_unary.is_done()
+    }
+
+    pub fn can_initialize(&self) -> bool {
+        self.session_unary.can_initialize()
+    }
+
+    pub fn remaining(&self) -> BigInt {
+        self.session_unary.remaining()
+    }
+
+    /// The number of steps performed so far.
+    pub fn steps(&self) -> usize {
+        self.session_unary.steps()
+    }
+
+    /// Setup a new initial state from the remaining universe of states. The current wave
+    /// must be converged and the computation must not be done (see [Lockstep::is_done]).
+    pub fn initialize(&mut self) {
+        assert!(!self.is_done());
+        assert!(self.can_initialize());
+        let state = pick_state_map::<UnaryIntegerDomain>(
+            self.session_unary.system(),
+            self.session_unary.unexplored(),
+        );
+        initialize_with_state(&mut self.session_unary, &state);
+        initialize_with_state(&mut self.session_binary, &state);
+        initialize_with_state(&mut self.session_gray, &state);
+        initialize_with_state(&mut self.session_petri_net, &state);
+    }
+
+    pub fn perform_bwd_step(&mut self) {
+        self.session_unary.step_bwd();
+        self.session_binary.step_bwd();
+        self.session_gray.step_bwd();
+        self.session_petri_net.step_bwd();
+    }
+
+    pub fn perform_fwd_step(&mut self) {
+        self.session_unary.step_fwd();
+        self.session_binary.step_fwd();
+        self.session_gray.step_fwd();
+        self.session_petri_net.step_fwd();
+    }
+
+    /// Exact state counts of the current results, in the order unary, binary, gray, petri net.
+    pub fn state_counts(&self) -> [BigInt; 4] {
+        [
+            count_states_exact(self.session_unary.system(), self.session_unary.current()),
+            count_states_exact(self.session_binary.system(), self.session_binary.current()),
+            count_states_exact(self.session_gray.system(), self.session_gray.current()),
+            count_states_exact(
+                self.session_petri_net.system(),
+                self.session_petri_net.current(),
+            ),
+        ]
+    }
+
+    /// Compare the current results of all the encodings; `step` is reported in the
+    /// [Mismatch] if they do not agree.
+    pub fn compare_encodings(&self, step: usize) -> Result<(), Mismatch> {
+        let counts = self.state_counts();
+        if counts.iter().all(|count| count == &counts[0]) {
+            Ok(())
+        } else {
+            Err(Mismatch {
+                step,
+                counts: counts.to_vec(),
+            })
+        }
+    }
+
+    /// Sizes of the BDDs of the current results, in the order unary, binary, gray, petri net.
+    pub fn bdd_sizes(&self) -> [usize; 4] {
+        [
+            self.session_unary.current().size(),
+            self.session_binary.current().size(),
+            self.session_gray.current().size(),
+            self.session_petri_net.current().size(),
+        ]
+    }
+
+    /// Decoded states of the current results (the values of the variables, sorted by name),
+    /// in the order unary, binary, gray, petri net.
+    pub fn decoded_states(&self) -> [HashSet<Vec<u8>>; 4] {
+        [
+            decode_states(self.session_unary.system(), self.session_unary.current()),
+            decode_states(self.session_binary.system(), self.session_binary.current()),
+            decode_states(self.session_gray.system(), self.session_gray.current()),
+            decode_states(
+                self.session_petri_net.system(),
+                self.session_petri_net.current(),
+            ),
+        ]
+    }
+
+    /// Like [Self::compare_encodings], but (if the state counts agree) also compares
+    /// the decoded states of the current results. Much more expensive, since all the states
+    /// are enumerated.
+    pub fn compare_encodings_deep(&self, step: usize) -> Result<(), DeepMismatch> {
+        self.compare_encodings(step)?;
+        let [unary, others @ ..] = self.decoded_states();
+        for (encoding, states) in ["binary", "gray", "petri net"].into_iter().zip(others) {
+            if let Some(state) = unary.symmetric_difference(&states).min() {
+                return Err(DeepMismatch::States {
+                    step,
+                    encoding,
+                    state: state.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Start a new wave of `session` from the single `state`.
+fn initialize_with_state<D: SymbolicDomainOrd<u8> + Debug>(
+    session: &mut ReachabilitySession<D, u8>,
+    state: &HashMap<String, u8>,
+) {
+    let state = encode_state_map(session.system(), state);
+    session.initialize_with(state);
+}
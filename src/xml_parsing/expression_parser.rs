@@ -1,11 +1,11 @@
-use std::{io::BufRead, str::FromStr};
+use std::{fmt::Display, io::BufRead, str::FromStr};
 
 use xml::reader::XmlEvent;
 
 use crate::{
     expression_components::{
         expression::Expression,
-        proposition::{ComparisonOperator, Proposition},
+        proposition::{ComparisonOperator, Proposition, VariableComparison},
     },
     xml_parsing::utils::{expect_closure_of, expect_opening},
 };
@@ -38,16 +38,16 @@ impl FromStr for LogicalOperator {
     }
 }
 
-impl ToString for LogicalOperator {
-    fn to_string(&self) -> String {
-        match self {
+impl Display for LogicalOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let as_str = match self {
             Self::Not => "not",
             Self::And => "and",
             Self::Or => "or",
             Self::Xor => "xor",
             Self::Implies => "implies",
-        }
-        .to_string()
+        };
+        write!(f, "{}", as_str)
     }
 }
 
@@ -72,10 +72,14 @@ impl<T: FromStr> Expression<T> {
                     if let Ok(comparison_operator) = received_operator.parse::<ComparisonOperator>()
                     {
                         expect_closure_of(xml, &comparison_operator.to_string())?;
-                        let proposition = proposition_from_xml(xml, comparison_operator)?;
+                        let terminal = terminal_from_xml(xml, comparison_operator)?;
                         expect_closure_of(xml, "apply")?;
-                        return Ok(Expression::Terminal(proposition));
+                        return Ok(terminal);
                     }
+
+                    return Err(XmlReadingError::UnsupportedOperator(
+                        received_operator.to_string(),
+                    ));
                 }
                 other => {
                     return Err(XmlReadingError::UnexpectedEvent {
@@ -106,7 +110,7 @@ where
             expect_opening_of(xml, "apply")?; // "open" the inner apply tag
             let inner_expression = Expression::try_from_xml(xml)?;
             expect_closure_of(xml, "apply")?; // "close" the *this* apply tag
-            Ok(inner_expression)
+            Ok(Expression::Not(Box::new(inner_expression)))
         }
         LogicalOperator::And => {
             let cnf_items = get_cnf_or_dnf_items(xml)?;
@@ -175,10 +179,12 @@ fn get_cnf_or_dnf_items<T: FromStr, XR: XmlReader<BR>, BR: BufRead>(
 }
 
 /// Expects xml to be at the end of the comparison operator tag (ie next is either value or variable name)
-fn proposition_from_xml<XR, BR, T>(
+///
+/// Produces either a proposition (variable vs. constant) or a comparison of two variables.
+fn terminal_from_xml<XR, BR, T>(
     xml: &mut XR,
     comparison_operator: ComparisonOperator,
-) -> Result<Proposition<T>, XmlReadingError>
+) -> Result<Expression<T>, XmlReadingError>
 where
     XR: XmlReader<BR>,
     BR: BufRead,
@@ -190,14 +196,35 @@ where
         "ci" => {
             let variable_name = get_variable_name(xml)?;
 
-            expect_opening_of(xml, "cn")?;
-            let constant_value = get_constant_value(xml)?;
+            let second_element = expect_opening(xml)?;
+            match second_element.name.local_name.as_str() {
+                "cn" => {
+                    let constant_value = get_constant_value(xml)?;
 
-            Ok(Proposition::new(
-                comparison_operator,
-                variable_name,
-                constant_value,
-            ))
+                    Ok(Expression::Terminal(Proposition::new(
+                        comparison_operator,
+                        variable_name,
+                        constant_value,
+                    )))
+                }
+                "ci" => {
+                    let other_variable_name = get_variable_name(xml)?;
+
+                    Ok(Expression::VariableComparison(VariableComparison::new(
+                        comparison_operator,
+                        variable_name,
+                        other_variable_name,
+                    )))
+                }
+                _ => Err(XmlReadingError::UnexpectedEvent {
+                    expected: super::utils::ExpectedXmlEvent::Start("ci or cn".to_string()),
+                    got: XmlEvent::StartElement {
+                        name: second_element.name,
+                        attributes: second_element.attributes,
+                        namespace: second_element.namespace,
+                    },
+                }),
+            }
         }
         "cn" => {
             let constant_value = get_constant_value(xml)?;
@@ -205,11 +232,12 @@ where
             expect_opening_of(xml, "ci")?;
             let variable_name = get_variable_name(xml)?;
 
-            Ok(Proposition::new(
-                comparison_operator,
+            // `value op variable` is stored as `variable op.flip() value`
+            Ok(Expression::Terminal(Proposition::new(
+                comparison_operator.flip(),
                 variable_name,
                 constant_value,
-            ))
+            )))
         }
         _ => Err(XmlReadingError::UnexpectedEvent {
             expected: super::utils::ExpectedXmlEvent::Start("ci or cn".to_string()),
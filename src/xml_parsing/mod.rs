@@ -1,3 +1,7 @@
+// `XmlReadingError` carries the offending `XmlEvent`, which makes it rather large; it is only
+// ever constructed on the (cold) error path, so boxing it is not worth the extra noise
+#![allow(clippy::result_large_err)]
+
 pub mod expression_parser;
 pub mod utils;
 pub mod variable_update_fn_parser;
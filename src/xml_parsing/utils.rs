@@ -29,6 +29,19 @@ pub enum XmlReadingError {
         expected_amount: usize,
         found_items_string: String,
     },
+    /// there are multiple transitions defining the update function of the same variable
+    DuplicateVariable(String),
+    /// some update function references a variable that has no update function of its own
+    MissingUpdateFunction(String),
+    /// some update function compares a variable with a value out of its domain;
+    /// only reported when loading in the strict mode
+    OutOfDomainConstant {
+        variable: String,
+        value: String,
+    },
+    /// some function term applies a MathML operator that is neither a logical connective
+    /// nor a comparison (e.g. the arithmetic `plus`)
+    UnsupportedOperator(String),
 }
 
 impl Display for XmlReadingError {
@@ -54,6 +67,31 @@ impl Display for XmlReadingError {
                     expected_amount, found_items_string
                 )
             }
+            XmlReadingError::DuplicateVariable(s) => {
+                write!(f, "Multiple update functions defined for variable {}", s)
+            }
+            XmlReadingError::MissingUpdateFunction(s) => {
+                write!(
+                    f,
+                    "Variable {} is referenced, but has no update function",
+                    s
+                )
+            }
+            XmlReadingError::OutOfDomainConstant { variable, value } => {
+                write!(
+                    f,
+                    "Variable {} is compared with {}, which is out of its domain",
+                    variable, value
+                )
+            }
+            XmlReadingError::UnsupportedOperator(s) => {
+                write!(
+                    f,
+                    "Unsupported MathML operator: {}; expected one of not, and, or, xor, \
+                    implies, eq, neq, lt, gt, leq, geq",
+                    s
+                )
+            }
         }
     }
 }
@@ -244,29 +282,50 @@ where
     }
 }
 
-/// iterates through the xml until it finds the first opening tag with the given name
-/// (specifically, opening_element.name.local_name == expected_name)
-pub fn find_start_of<XR, BR>(xml: &mut XR, expected_name: &str) -> Result<(), XmlReadingError>
+/// iterates through the xml until it finds the opening tag of the last element of the `path`
+/// (specifically, opening_element.name.local_name == path.last()), nested inside the elements
+/// given by the rest of the `path`.
+///
+/// The first element of the `path` is searched for anywhere in the (remaining) document, every
+/// next one anywhere inside the previous one (not necessarily as its direct child); e.g.
+/// `["model", "listOfTransitions"]` finds the first `listOfTransitions` inside the first `model`.
+/// Fails if the element containing the next element of the `path` ends without containing it.
+/// An empty `path` is trivially found without reading anything.
+pub fn find_start_of<XR, BR>(xml: &mut XR, path: &[&str]) -> Result<(), XmlReadingError>
 where
     XR: XmlReader<BR>,
     BR: BufRead,
 {
-    loop {
-        match xml.next()? {
-            xml::reader::XmlEvent::StartElement { name: n, .. }
-                if n.local_name == expected_name =>
-            {
-                return Ok(())
-            }
-            xml::reader::XmlEvent::EndDocument => {
-                return Err(XmlReadingError::UnexpectedEvent {
-                    expected: ExpectedXmlEvent::Start(expected_name.into()),
-                    got: XmlEvent::EndDocument,
-                })
+    for (position, expected_name) in path.iter().enumerate() {
+        // nesting depth relative to the element found in the previous step
+        let mut depth = 0usize;
+        loop {
+            match xml.next()? {
+                xml::reader::XmlEvent::StartElement { name: n, .. }
+                    if n.local_name == *expected_name =>
+                {
+                    break;
+                }
+                xml::reader::XmlEvent::StartElement { .. } => depth += 1,
+                xml::reader::XmlEvent::EndElement { name } if position > 0 && depth == 0 => {
+                    return Err(XmlReadingError::UnexpectedEvent {
+                        expected: ExpectedXmlEvent::Start(expected_name.to_string()),
+                        got: XmlEvent::EndElement { name },
+                    })
+                }
+                xml::reader::XmlEvent::EndElement { .. } => depth = depth.saturating_sub(1),
+                xml::reader::XmlEvent::EndDocument => {
+                    return Err(XmlReadingError::UnexpectedEvent {
+                        expected: ExpectedXmlEvent::Start(expected_name.to_string()),
+                        got: XmlEvent::EndDocument,
+                    })
+                }
+                _ => continue, // should be uninteresting
             }
-            _ => continue, // should be uninteresting
         }
     }
+
+    Ok(())
 }
 
 /// Iterates through the xml until it finds the closing tag with the given name,
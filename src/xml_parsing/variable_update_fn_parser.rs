@@ -1,5 +1,6 @@
 use std::{
     collections::{HashMap, HashSet},
+    fmt::Debug,
     io::BufRead,
     str::FromStr,
 };
@@ -7,13 +8,19 @@ use std::{
 use xml::reader::XmlEvent;
 
 use crate::{
-    expression_components::expression::Expression,
+    expression_components::{
+        expression::Expression,
+        proposition::{ComparisonOperator, Proposition},
+    },
     update::unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
 };
 
 use super::{
     utils::expect_opening,
-    utils::{expect_closure_of, expect_opening_of, map_list, StartElementWrapper, XmlReadingError},
+    utils::{
+        expect_closure_of, expect_opening_of, map_list, ExpectedXmlEvent, StartElementWrapper,
+        XmlReadingError,
+    },
     xml_reader::XmlReader,
 };
 
@@ -47,12 +54,12 @@ where
         }
 
         // listOfInputs may or may not be present - either case is accepted
-        let input_vars_names = if some_start_element.name.local_name == "listOfInputs" {
+        let declared_input_vars_names = if some_start_element.name.local_name == "listOfInputs" {
             let aux = map_list(xml, "listOfInputs", "input", process_input_var_name_item)?;
             expect_opening_of(xml, "listOfOutputs")?; // must be followed by listOfOutputs
-            aux
+            Some(aux)
         } else {
-            Vec::new()
+            None
         };
 
         let target_vars_names =
@@ -72,6 +79,18 @@ where
 
         expect_closure_of(xml, "transition")?;
 
+        // without the declared inputs, the inputs are the variables the terms depend on
+        let input_vars_names = declared_input_vars_names.unwrap_or_else(|| {
+            let mut referenced = terms
+                .iter()
+                .flat_map(|(_, expression)| expression.referenced_variables())
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            referenced.sort_unstable();
+            referenced.dedup();
+            referenced
+        });
+
         Ok(UnprocessedVariableUpdateFn::new(
             input_vars_names,
             target_variable_name,
@@ -178,9 +197,67 @@ fn result_level_from_attributes<T: FromStr>(
         .map_err(|_| XmlReadingError::ParsingError(attribute_with_result_lvl.value.clone()))
 }
 
-/// Expect the current XML element to be <listOfFunctionTerms>
-/// Loads all contained <functionTerm> elements into a HashMap.
-fn load_all_update_fns<XR, BR, T>(
+/// Expect the current XML element to be <listOfTransitions>
+/// Lazily parses the contained <transition> elements, one per call of `next()`, so that
+/// the whole list never needs to be held in memory at once. The iterator ends at the closing
+/// tag of the list; after the first error, it ends as well.
+pub fn iter_transitions<XR, BR, T>(
+    xml: &mut XR,
+) -> impl Iterator<Item = Result<UnprocessedVariableUpdateFn<T>, XmlReadingError>> + '_
+where
+    XR: XmlReader<BR>,
+    BR: BufRead,
+    T: FromStr,
+{
+    let mut done = false;
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+
+        let next = next_transition(xml).transpose();
+        done = !matches!(next, Some(Ok(_)));
+        next
+    })
+}
+
+/// Parses the next <transition> element of the list, or returns `None` if the end
+/// of <listOfTransitions> is reached instead.
+fn next_transition<XR, BR, T>(
+    xml: &mut XR,
+) -> Result<Option<UnprocessedVariableUpdateFn<T>>, XmlReadingError>
+where
+    XR: XmlReader<BR>,
+    BR: BufRead,
+    T: FromStr,
+{
+    loop {
+        match xml.next()? {
+            XmlEvent::Whitespace(_) => { /* ignore */ }
+            XmlEvent::StartElement { name, .. } if name.local_name == "transition" => {
+                return UnprocessedVariableUpdateFn::try_from_xml(xml).map(Some);
+            }
+            XmlEvent::EndElement { name } if name.local_name == "listOfTransitions" => {
+                return Ok(None);
+            }
+            other => {
+                return Err(XmlReadingError::UnexpectedEvent {
+                    expected: ExpectedXmlEvent::AnyOf(vec![
+                        ExpectedXmlEvent::Start("transition".into()),
+                        ExpectedXmlEvent::End("listOfTransitions".into()),
+                    ]),
+                    got: other,
+                })
+            }
+        }
+    }
+}
+
+/// Expect the current XML element to be <listOfTransitions>
+/// Loads all contained <transition> elements into a HashMap, keyed by the name of the target
+/// variable. Variables that are only inputs of some transitions get an update function
+/// that is constantly `T::default()`.
+pub fn load_all_update_fns<XR, BR, T>(
     xml: &mut XR,
 ) -> Result<HashMap<String, UnprocessedVariableUpdateFn<T>>, XmlReadingError>
 where
@@ -188,15 +265,7 @@ where
     BR: BufRead,
     T: FromStr + Default,
 {
-    let vars_and_their_update_fns = map_list(
-        xml,
-        "listOfTransitions",
-        "transition",
-        |xml, _start_element| UnprocessedVariableUpdateFn::<T>::try_from_xml(xml),
-    )?
-    .into_iter()
-    .map(|update_fn| (update_fn.target_var_name.clone(), update_fn))
-    .collect::<HashMap<_, _>>();
+    let vars_and_their_update_fns = load_transitions(xml)?;
 
     let vars_possibly_without_update_fns = vars_and_their_update_fns
         .values()
@@ -218,9 +287,153 @@ where
         },
     );
 
+    // sorted, so that the reported variable does not depend on the order of the hash map
+    let mut missing = all_vars_and_their_update_fns
+        .values()
+        .flat_map(|update_fn| update_fn.referenced_variables())
+        .filter(|var_name| !all_vars_and_their_update_fns.contains_key(*var_name))
+        .collect::<Vec<_>>();
+    missing.sort_unstable();
+
+    if let Some(missing_var_name) = missing.first() {
+        return Err(XmlReadingError::MissingUpdateFunction(
+            missing_var_name.to_string(),
+        ));
+    }
+
     Ok(all_vars_and_their_update_fns)
 }
 
+/// How [load_all_update_fns_with_inputs] defines the update function of a variable that has
+/// no transition of its own (i.e. it is only an input of some transitions).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputUpdateFn {
+    /// The variable is updated to `T::default()`, as in [load_all_update_fns].
+    #[default]
+    Constant,
+    /// The variable keeps its value, i.e. it is a free input of the system.
+    Identity,
+}
+
+/// The result of [load_all_update_fns_with_inputs].
+#[derive(Debug)]
+pub struct LoadedUpdateFns<T> {
+    pub update_fns: HashMap<String, UnprocessedVariableUpdateFn<T>>,
+    /// the variables without a transition, whose update functions were synthesized
+    /// (sorted by name)
+    pub synthesized: Vec<String>,
+}
+
+/// Like [load_all_update_fns], but every variable referenced by some update function
+/// (either declared as an input of its transition, or only compared within its terms) that
+/// has no transition of its own gets an update function synthesized according to `inputs`,
+/// instead of [XmlReadingError::MissingUpdateFunction] being reported.
+///
+/// The synthesized variables are listed in the result, so that they can be reviewed.
+/// The domain of such a variable is given by the greatest value it is compared with.
+pub fn load_all_update_fns_with_inputs<XR, BR, T>(
+    xml: &mut XR,
+    inputs: InputUpdateFn,
+) -> Result<LoadedUpdateFns<T>, XmlReadingError>
+where
+    XR: XmlReader<BR>,
+    BR: BufRead,
+    T: FromStr + Default + Copy + PartialOrd + From<u8> + std::ops::Add<Output = T>,
+{
+    let mut update_fns = load_transitions(xml)?;
+
+    // the greatest value each variable without a transition is compared with
+    let mut synthesized = HashMap::<String, T>::new();
+    for update_fn in update_fns.values() {
+        let referenced = update_fn
+            .input_vars_names
+            .iter()
+            .map(String::as_str)
+            .chain(update_fn.referenced_variables());
+        for var_name in referenced {
+            if !update_fns.contains_key(var_name) {
+                synthesized.entry(var_name.to_string()).or_default();
+            }
+        }
+
+        let propositions = update_fn
+            .terms
+            .iter()
+            .flat_map(|(_, condition)| condition.propositions());
+        for proposition in propositions {
+            if let Some(max_value) = synthesized.get_mut(&proposition.variable) {
+                if proposition.value > *max_value {
+                    *max_value = proposition.value;
+                }
+            }
+        }
+    }
+
+    let mut synthesized_names = synthesized.keys().cloned().collect::<Vec<_>>();
+    synthesized_names.sort_unstable();
+
+    for (var_name, max_value) in synthesized {
+        let (input_vars_names, terms) = match inputs {
+            InputUpdateFn::Constant => (Vec::new(), Vec::new()),
+            InputUpdateFn::Identity => {
+                // `x = value if x == value` for every value except the default one
+                let mut terms = Vec::new();
+                let mut value = T::default();
+                while value < max_value {
+                    value = value + T::from(1);
+                    terms.push((
+                        value,
+                        Expression::Terminal(Proposition::new(
+                            ComparisonOperator::Eq,
+                            var_name.clone(),
+                            value,
+                        )),
+                    ));
+                }
+                (vec![var_name.clone()], terms)
+            }
+        };
+        let update_fn = UnprocessedVariableUpdateFn::new(
+            input_vars_names,
+            var_name.clone(),
+            terms,
+            T::default(),
+        );
+        update_fns.insert(var_name, update_fn);
+    }
+
+    Ok(LoadedUpdateFns {
+        update_fns,
+        synthesized: synthesized_names,
+    })
+}
+
+/// Loads the <transition> elements of the <listOfTransitions>, keyed by the name of the
+/// target variable; see [load_all_update_fns].
+fn load_transitions<XR, BR, T>(
+    xml: &mut XR,
+) -> Result<HashMap<String, UnprocessedVariableUpdateFn<T>>, XmlReadingError>
+where
+    XR: XmlReader<BR>,
+    BR: BufRead,
+    T: FromStr,
+{
+    iter_transitions(xml).try_fold(
+        HashMap::new(),
+        |mut acc, update_fn: Result<UnprocessedVariableUpdateFn<T>, _>| {
+            let update_fn = update_fn?;
+            // inserting blindly would silently drop the previous definition
+            if acc.contains_key(&update_fn.target_var_name) {
+                return Err(XmlReadingError::DuplicateVariable(
+                    update_fn.target_var_name,
+                ));
+            }
+            acc.insert(update_fn.target_var_name.clone(), update_fn);
+            Ok(acc)
+        },
+    )
+}
+
 use crate::symbolic_domains::symbolic_domain::SymbolicDomainOrd;
 use crate::update::update_fn::{SmartSystemUpdateFn, SystemUpdateFn};
 
@@ -256,4 +469,41 @@ where
     {
         Ok(Self::from_update_fns(load_all_update_fns(xml)?))
     }
+
+    /// Like [SmartSystemUpdateFn::try_from_xml], but the variables without a transition of
+    /// their own get an update function synthesized according to `inputs` (see
+    /// [load_all_update_fns_with_inputs]). Returns the system together with the names of
+    /// these variables.
+    pub fn try_from_xml_with_inputs<XR, BR>(
+        xml: &mut XR,
+        inputs: InputUpdateFn,
+    ) -> Result<(Self, Vec<String>), XmlReadingError>
+    where
+        XR: XmlReader<BR>,
+        BR: BufRead,
+        T: Copy + PartialOrd + From<u8> + std::ops::Add<Output = T>,
+    {
+        let LoadedUpdateFns {
+            update_fns,
+            synthesized,
+        } = load_all_update_fns_with_inputs(xml, inputs)?;
+        Ok((Self::from_update_fns(update_fns), synthesized))
+    }
+
+    /// Like [SmartSystemUpdateFn::try_from_xml], but comparisons with values out of the domain
+    /// of the compared variable are reported as [XmlReadingError::OutOfDomainConstant] (see
+    /// [SmartSystemUpdateFn::try_from_update_fns_strict]).
+    pub fn try_from_xml_strict<XR, BR>(xml: &mut XR) -> Result<Self, XmlReadingError>
+    where
+        XR: XmlReader<BR>,
+        BR: BufRead,
+        T: FromStr + Clone + Debug,
+    {
+        Self::try_from_update_fns_strict(load_all_update_fns(xml)?).map_err(|err| {
+            XmlReadingError::OutOfDomainConstant {
+                variable: err.variable,
+                value: format!("{:?}", err.value),
+            }
+        })
+    }
 }
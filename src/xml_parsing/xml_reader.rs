@@ -18,7 +18,9 @@ impl<BR: BufRead> XmlReader<BR> for EventReader<BR> {
     }
 }
 
-/// used for pretty printing of the read xml during the reading process
+/// used for pretty printing of the read xml during the reading process;
+/// prints every element to stdout, so it is only meant for debugging the parsers
+#[allow(dead_code)] // only used when debugging the parsers
 pub struct LoudReader<BR: BufRead> {
     xml: EventReader<BR>,
     curr_indent: usize,
@@ -68,6 +70,7 @@ impl<BR: BufRead> XmlReader<BR> for LoudReader<BR> {
 
 /// used for counting the line number of the read xml during the reading process
 /// really mostly useless; just use `lines().count()` or smth
+#[allow(dead_code)] // only used when debugging the parsers
 pub struct CountingReader<BR: BufRead> {
     xml: EventReader<BR>,
     pub curr_line: usize,
@@ -0,0 +1,188 @@
+use biodivine_lib_bdd::BddValuation;
+use biodivine_lib_logical_models::prelude::symbolic_domain::SymbolicDomainOrd;
+
+mod common;
+use common::{load, test_all_encodings};
+
+/// The parts of an ASCII AIGER circuit with no outputs & bad states, but with constraints.
+struct Aiger {
+    inputs: Vec<u32>,
+    /// (literal, next state)
+    latches: Vec<(u32, u32)>,
+    constraints: Vec<u32>,
+    and_gates: Vec<(u32, u32, u32)>,
+    input_names: Vec<String>,
+    latch_names: Vec<String>,
+}
+
+impl Aiger {
+    fn parse(text: &str) -> Self {
+        let mut lines = text.lines();
+        let numbers = |line: &str| {
+            line.split(' ')
+                .map(|it| it.parse::<u32>().expect("a number"))
+                .collect::<Vec<_>>()
+        };
+        let header = lines.next().expect("a header");
+        let counts = numbers(header.strip_prefix("aag ").expect("ASCII AIGER"));
+        let [_, inputs, latches, outputs, ands, bad, constraints] = counts[..] else {
+            panic!("unexpected header `{}`", header)
+        };
+        assert_eq!((outputs, bad), (0, 0));
+        let mut take = |count: u32| {
+            (0..count)
+                .map(|_| lines.next().expect("a line"))
+                .collect::<Vec<_>>()
+        };
+
+        let inputs = take(inputs).into_iter().map(|it| numbers(it)[0]).collect();
+        let latches = take(latches)
+            .into_iter()
+            .map(|it| {
+                let latch = numbers(it);
+                // uninitialised
+                assert_eq!(latch[2], latch[0]);
+                (latch[0], latch[1])
+            })
+            .collect();
+        let constraints = take(constraints)
+            .into_iter()
+            .map(|it| numbers(it)[0])
+            .collect();
+        let and_gates = take(ands)
+            .into_iter()
+            .map(|it| {
+                let gate = numbers(it);
+                (gate[0], gate[1], gate[2])
+            })
+            .collect();
+        let symbols = lines.collect::<Vec<_>>();
+        let names = |prefix: char| {
+            symbols
+                .iter()
+                .filter(|line| line.starts_with(prefix))
+                .map(|line| line.split_once(' ').expect("a symbol").1.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        Self {
+            inputs,
+            latches,
+            constraints,
+            and_gates,
+            input_names: names('i'),
+            latch_names: names('l'),
+        }
+    }
+
+    /// Evaluates the constraints & the next state of the latches.
+    fn step(&self, inputs: &[bool], latches: &[bool]) -> (bool, Vec<bool>) {
+        let mut values = vec![false; 1 + self.inputs.len() + latches.len() + self.and_gates.len()];
+        for (literal, value) in self.inputs.iter().zip(inputs) {
+            values[*literal as usize / 2] = *value;
+        }
+        for ((literal, _), value) in self.latches.iter().zip(latches) {
+            values[*literal as usize / 2] = *value;
+        }
+        let eval =
+            |values: &[bool], literal: u32| values[literal as usize / 2] ^ (literal % 2 == 1);
+        // the gates of the exported circuits are ordered
+        for (lhs, rhs0, rhs1) in &self.and_gates {
+            values[*lhs as usize / 2] = eval(&values, *rhs0) && eval(&values, *rhs1);
+        }
+        let valid = self.constraints.iter().all(|it| eval(&values, *it));
+        let next = self
+            .latches
+            .iter()
+            .map(|(_, next)| eval(&values, *next))
+            .collect();
+        (valid, next)
+    }
+}
+
+fn circuit_matches_transitions<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let mut output = Vec::new();
+    system
+        .to_aiger(&mut output)
+        .expect("should be able to export");
+    let aiger = Aiger::parse(&String::from_utf8(output).expect("utf-8"));
+
+    let variables = system.get_system_variables();
+    assert_eq!(
+        aiger.input_names,
+        variables
+            .iter()
+            .map(|name| format!("fire_{}", name))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(aiger.latch_names.len(), system.standard_variables().len());
+    let bdd_variables = system.get_bdd_variable_set();
+    let latch_variables = aiger
+        .latch_names
+        .iter()
+        .map(|name| bdd_variables.var_by_name(name).expect("a known variable"))
+        .collect::<Vec<_>>();
+    // the primed copy of each latch
+    let primed_variables = latch_variables
+        .iter()
+        .map(|var| {
+            variables
+                .iter()
+                .find_map(|name| {
+                    let bits = system.get_domain(name).unwrap().raw_bdd_variables();
+                    let primed = system.get_primed_domain(name).unwrap().raw_bdd_variables();
+                    bits.iter().position(|it| it == var).map(|idx| primed[idx])
+                })
+                .expect("a standard variable")
+        })
+        .collect::<Vec<_>>();
+
+    let unit = system.unit_vertex_set();
+    let latch_count = latch_variables.len();
+    for bits in 0..(1u32 << latch_count) {
+        let latches = (0..latch_count)
+            .map(|idx| bits & (1 << idx) != 0)
+            .collect::<Vec<_>>();
+        let mut valuation = BddValuation::all_false(bdd_variables.num_vars());
+        for (var, value) in latch_variables.iter().zip(&latches) {
+            valuation.set_value(*var, *value);
+        }
+        let is_valid = unit.eval_in(&valuation);
+
+        // without any input, the state is kept
+        let (valid, next) = aiger.step(&vec![false; variables.len()], &latches);
+        assert_eq!(valid, is_valid);
+        assert_eq!(next, latches);
+        if !is_valid {
+            continue;
+        }
+
+        for (idx, name) in variables.iter().enumerate() {
+            // the first input wins, so the later ones do not matter
+            let inputs = (0..variables.len()).map(|it| it >= idx).collect::<Vec<_>>();
+            let (_, next) = aiger.step(&inputs, &latches);
+            let mut edge = valuation.clone();
+            for (primed_var, value) in primed_variables.iter().zip(&next) {
+                edge.set_value(*primed_var, *value);
+            }
+            assert!(
+                system.successor_edges(name, &unit).eval_in(&edge),
+                "wrong successor of {:?} under `{}`",
+                latches,
+                name
+            );
+        }
+    }
+
+    // the merged variable updates either of the original ones, so its update is not a function
+    let merged = system
+        .merge_variables("a", "b", "ab")
+        .expect("should be able to merge");
+    let error = merged
+        .to_aiger(&mut Vec::new())
+        .expect_err("non-deterministic update");
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+test_all_encodings!(circuit_matches_transitions);
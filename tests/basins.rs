@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    expression::Expression,
+    proposition::{ComparisonOperator, Proposition},
+    symbolic_domain::SymbolicDomainOrd,
+    unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+};
+
+mod common;
+use common::{from_dsl, test_all_encodings};
+
+fn update_fn(
+    target: &str,
+    regulator: &str,
+    regulator_value: u8,
+) -> UnprocessedVariableUpdateFn<u8> {
+    UnprocessedVariableUpdateFn::new(
+        vec![regulator.to_string()],
+        target.to_string(),
+        vec![(
+            1,
+            Expression::Terminal(Proposition::new(
+                ComparisonOperator::Eq,
+                regulator.to_string(),
+                regulator_value,
+            )),
+        )],
+        0,
+    )
+}
+
+/// A bistable switch `a = !b`, `b = !a` with a "reporter" `c = a`; the two attractors
+/// are the fixed points `(a, b, c) = (1, 0, 1)` and `(0, 1, 0)`.
+fn bistable_switch<DO: SymbolicDomainOrd<u8>>() -> bio::update_fn::SmartSystemUpdateFn<DO, u8> {
+    bio::update_fn::SmartSystemUpdateFn::from_update_fns(HashMap::from([
+        ("a".to_string(), update_fn("a", "b", 0)),
+        ("b".to_string(), update_fn("b", "a", 0)),
+        ("c".to_string(), update_fn("c", "a", 1)),
+    ]))
+}
+
+fn basins_of_two_attractors<DO: SymbolicDomainOrd<u8>>() {
+    let system = bistable_switch::<DO>();
+    let unit = system.unit_vertex_set();
+    let value = |name: &str, value: u8| system.encode_one(name, &value).and(&unit);
+
+    let first = value("a", 1).and(&value("b", 0)).and(&value("c", 1));
+    let second = value("a", 0).and(&value("b", 1)).and(&value("c", 0));
+
+    let first_weak = system.weak_basin(&first);
+    let second_weak = system.weak_basin(&second);
+    assert_eq!(first_weak.or(&second_weak), unit);
+
+    let first_strong = system.strong_basin(&first);
+    let second_strong = system.strong_basin(&second);
+    // once the switch is decided, only the reporter can still change
+    assert_eq!(first_strong, value("a", 1).and(&value("b", 0)));
+    assert_eq!(second_strong, value("a", 0).and(&value("b", 1)));
+
+    assert!(first_strong.imp(&first_weak).is_true());
+    assert!(second_strong.imp(&second_weak).is_true());
+    assert!(first_strong.and(&second_strong).is_false());
+    // the states outside of both strong basins are exactly those that can reach both attractors
+    assert_eq!(
+        system.complement(&first_strong.or(&second_strong)),
+        first_weak.and(&second_weak)
+    );
+}
+
+test_all_encodings!(basins_of_two_attractors);
+
+/// A negative loop `a = !b`, `b = a` that only oscillates while `c = 0`; `c` switches on
+/// (for good) in the state `a = b = 1`, after which the system settles in `(0, 0, 1)`.
+/// The oscillation is thus a transient cycle: it can be escaped, but does not have to be.
+fn escapable_oscillation<DO: SymbolicDomainOrd<u8>>() -> bio::update_fn::SmartSystemUpdateFn<DO, u8>
+{
+    from_dsl(&[
+        ("a", "1: b == 0 & c == 0; default 0"),
+        ("b", "1: a == 1 & c == 0; default 0"),
+        ("c", "1: c == 1 | a == 1 & b == 1; default 0"),
+    ])
+}
+
+fn terminating_states<DO: SymbolicDomainOrd<u8>>() {
+    let system = escapable_oscillation::<DO>();
+    let unit = system.unit_vertex_set();
+    let value = |name: &str, value: u8| system.encode_one(name, &value).and(&unit);
+
+    let fixed_point = value("a", 0).and(&value("b", 0)).and(&value("c", 1));
+    assert_eq!(system.attractor_states(), fixed_point);
+    // every state can escape the cycle, but only those with `c = 1` are forced to
+    assert_eq!(system.weak_basin(&fixed_point), unit);
+    assert_eq!(system.terminating_states(), value("c", 1));
+
+    // without any cycles, all the states are terminating
+    let switch = bistable_switch::<DO>();
+    let switch_unit = switch.unit_vertex_set();
+    let switch_value = |name: &str, value: u8| switch.encode_one(name, &value).and(&switch_unit);
+    assert_eq!(
+        switch.attractor_states(),
+        switch_value("a", 1)
+            .and(&switch_value("b", 0))
+            .and(&switch_value("c", 1))
+            .or(&switch_value("a", 0)
+                .and(&switch_value("b", 1))
+                .and(&switch_value("c", 0)))
+    );
+    assert_eq!(switch.terminating_states(), switch_unit);
+}
+
+test_all_encodings!(terminating_states);
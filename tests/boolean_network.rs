@@ -0,0 +1,146 @@
+#![cfg(feature = "lib-param-bn")]
+
+use std::collections::HashMap;
+
+use biodivine_lib_logical_models::prelude::{
+    self as bio, boolean_network::ConversionError, symbolic_domain::SymbolicDomainOrd,
+    update_fn_dsl::parse_update_fn,
+};
+
+mod common;
+use common::{from_dsl, test_all_encodings};
+
+/// The update functions of the network agree with those of the system in every state.
+fn boolean_network_matches_system<DO: SymbolicDomainOrd<u8>>() {
+    let system = from_dsl::<DO>(&[
+        ("a", "1: b == 0; default 0"),
+        ("b", "1: a == 1 & c == 1; default 0"),
+        ("c", "0: a == 1 & b == 1; 1: c == 1 | a == 0; default 0"),
+        ("d", "default 1"),
+    ]);
+    let network = system
+        .to_boolean_network()
+        .expect("should be able to convert");
+    let graph = network.as_graph();
+    let variables = system.get_system_variables();
+    assert_eq!(
+        graph
+            .variables()
+            .map(|id| graph.get_variable_name(id).clone())
+            .collect::<Vec<_>>(),
+        variables
+    );
+
+    // each variable is regulated by the variables its function depends on
+    let regulators = |name: &str| {
+        let id = graph.find_variable(name).expect("variable of the network");
+        graph
+            .regulators(id)
+            .into_iter()
+            .map(|id| graph.get_variable_name(id).as_str())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(regulators("a"), vec!["b"]);
+    assert_eq!(regulators("b"), vec!["a", "c"]);
+    assert_eq!(regulators("c"), vec!["a", "b", "c"]);
+    assert!(regulators("d").is_empty());
+
+    let unit = system.unit_vertex_set();
+    for bits in 0..(1u8 << variables.len()) {
+        let state = variables
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (name.as_str(), (bits >> idx) & 1))
+            .collect::<HashMap<_, _>>();
+        let encoded = state.iter().fold(unit.clone(), |acc, (name, value)| {
+            acc.and(&system.encode_one(name, value))
+        });
+        let valuation = state
+            .iter()
+            .map(|(name, value)| {
+                let id = graph.find_variable(name).expect("variable of the network");
+                (id, *value == 1)
+            })
+            .collect::<HashMap<_, _>>();
+
+        for name in &variables {
+            let id = graph.find_variable(name).expect("variable of the network");
+            let function = network
+                .get_update_function(id)
+                .as_ref()
+                .expect("every variable has a function");
+            let updated_to_one = !system
+                .successors_async(name, &encoded)
+                .and(&system.encode_one(name, &1))
+                .is_false();
+            assert_eq!(
+                function.evaluate(&valuation),
+                Some(updated_to_one),
+                "{} in {:?}",
+                name,
+                state
+            );
+        }
+    }
+}
+
+test_all_encodings!(boolean_network_matches_system);
+
+fn multi_valued_variables_are_rejected<DO: SymbolicDomainOrd<u8> + Clone>() {
+    let system = from_dsl::<DO>(&[
+        ("a", "2: b == 1; 1: b == 0; default 0"),
+        ("b", "1: a >= 1; default 0"),
+    ]);
+    assert_eq!(
+        system.to_boolean_network().err(),
+        Some(ConversionError::NotBoolean("a".to_string(), 3))
+    );
+
+    let system = from_dsl::<DO>(&[("a", "1: b == 1; default 0"), ("b", "default 1")]);
+    let renamed = system
+        .rename_variable("a", "a-1")
+        .expect("should be able to rename");
+    assert_eq!(
+        renamed.to_boolean_network().err(),
+        Some(ConversionError::InvalidName("a-1".to_string()))
+    );
+}
+
+test_all_encodings!(multi_valued_variables_are_rejected);
+
+fn parameters_become_constants<DO: SymbolicDomainOrd<u8>>() {
+    let update_fns = [
+        ("x", "1: p == 1 & y == 0; default 0"),
+        ("y", "1: x == 1; default 0"),
+    ]
+    .into_iter()
+    .map(|(target, src)| {
+        let update_fn = parse_update_fn(target, src).expect("should be able to parse");
+        (target.to_string(), update_fn)
+    })
+    .collect();
+    let system = bio::update_fn::SmartSystemUpdateFn::<DO, u8>::from_update_fns_with_parameters(
+        update_fns,
+        &["p".to_string()],
+    );
+    let network = system
+        .to_boolean_network()
+        .expect("should be able to convert");
+
+    assert_eq!(network.num_parameters(), 1);
+    let p = network
+        .find_parameter("p")
+        .expect("parameter of the network");
+    let x = network
+        .as_graph()
+        .find_variable("x")
+        .expect("variable of the network");
+    let function = network
+        .get_update_function(x)
+        .as_ref()
+        .expect("every variable has a function");
+    assert_eq!(function.collect_parameters(), vec![p]);
+    assert_eq!(network.as_graph().regulators(x).len(), 1);
+}
+
+test_all_encodings!(parameters_become_constants);
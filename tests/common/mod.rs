@@ -0,0 +1,84 @@
+// every test crate includes this module, but not all of them use every helper
+#![allow(dead_code)]
+
+use biodivine_lib_logical_models::prelude::{
+    self as bio, symbolic_domain::SymbolicDomainOrd, update_fn_dsl::parse_update_fn,
+};
+
+/// Loads the system of the SBML model at `sbml_path` using the `DO` integer encoding.
+pub fn load<DO: SymbolicDomainOrd<u8>>(
+    sbml_path: &str,
+) -> bio::update_fn::SmartSystemUpdateFn<DO, u8> {
+    let mut xml = transitions_reader(sbml_path);
+    bio::update_fn::SmartSystemUpdateFn::try_from_xml(&mut xml).expect("should be able to parse")
+}
+
+/// Like [load], but loads the plain (non-smart) `SystemUpdateFn`.
+pub fn load_dumb<DO: SymbolicDomainOrd<u8>>(
+    sbml_path: &str,
+) -> bio::update_fn::SystemUpdateFn<DO, u8> {
+    let mut xml = transitions_reader(sbml_path);
+    bio::update_fn::SystemUpdateFn::try_from_xml(&mut xml).expect("should be able to parse")
+}
+
+fn transitions_reader(
+    sbml_path: &str,
+) -> xml::reader::EventReader<std::io::BufReader<std::fs::File>> {
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open(sbml_path).expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, &["listOfTransitions"]).expect("should be able to find");
+    xml
+}
+
+/// Builds the system of the given `(target, update function)` pairs, with the update functions
+/// written in the update function DSL, using the `DO` integer encoding.
+pub fn from_dsl<DO: SymbolicDomainOrd<u8>>(
+    update_fns: &[(&str, &str)],
+) -> bio::update_fn::SmartSystemUpdateFn<DO, u8> {
+    bio::update_fn::SmartSystemUpdateFn::from_update_fns(
+        update_fns
+            .iter()
+            .map(|(target, src)| {
+                let update_fn = parse_update_fn(target, src).expect("should be able to parse");
+                (target.to_string(), update_fn)
+            })
+            .collect(),
+    )
+}
+
+/// Runs each of the given generic tests (`fn test<DO: SymbolicDomainOrd<u8>>()`) with all four
+/// integer encodings, as the tests `test::unary`, `test::binary`, `test::gray` and
+/// `test::petri_net`.
+macro_rules! test_all_encodings {
+    ($($test:ident),+ $(,)?) => {$(
+        mod $test {
+            use biodivine_lib_logical_models::prelude::symbolic_domain::{
+                BinaryIntegerDomain, GrayCodeIntegerDomain, PetriNetIntegerDomain,
+                UnaryIntegerDomain,
+            };
+
+            #[test]
+            fn unary() {
+                super::$test::<UnaryIntegerDomain>();
+            }
+
+            #[test]
+            fn binary() {
+                super::$test::<BinaryIntegerDomain<u8>>();
+            }
+
+            #[test]
+            fn gray() {
+                super::$test::<GrayCodeIntegerDomain<u8>>();
+            }
+
+            #[test]
+            fn petri_net() {
+                super::$test::<PetriNetIntegerDomain>();
+            }
+        }
+    )+};
+}
+
+pub(crate) use test_all_encodings;
@@ -1,13 +1,10 @@
 #![allow(dead_code)]
 
 use biodivine_lib_bdd::Bdd;
-use biodivine_lib_logical_models::prelude::{
-    self as bio,
-    symbolic_domain::{
-        BinaryIntegerDomain, GrayCodeIntegerDomain, PetriNetIntegerDomain, SymbolicDomainOrd,
-        UnaryIntegerDomain,
-    },
-};
+use biodivine_lib_logical_models::prelude::{self as bio, symbolic_domain::SymbolicDomainOrd};
+
+mod common;
+use common::{load, load_dumb, test_all_encodings};
 
 struct TheTwoImpls<DO>
 where
@@ -72,23 +69,9 @@ where
     DO: SymbolicDomainOrd<u8>,
 {
     fn from_path(sbml_path: &str) -> Self {
-        let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
-            std::fs::File::open(sbml_path).expect("should be able to open file"),
-        ));
-        bio::find_start_of(&mut xml, "listOfTransitions").expect("should be able to find");
-        let new_dumb = bio::update_fn::SystemUpdateFn::<DO, u8>::try_from_xml(&mut xml)
-            .expect("should be able to parse");
-
-        let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
-            std::fs::File::open(sbml_path).expect("should be able to open file"),
-        ));
-        bio::find_start_of(&mut xml, "listOfTransitions").expect("should be able to find");
-        let new_smart = bio::update_fn::SmartSystemUpdateFn::<DO, u8>::try_from_xml(&mut xml)
-            .expect("should be able to parse");
-
         Self {
-            new_dumb,
-            new_smart,
+            new_dumb: load_dumb(sbml_path),
+            new_smart: load(sbml_path),
         }
     }
 
@@ -118,9 +101,9 @@ where
         let new_dumb = self
             .new_dumb
             .predecessors_async(transition_variable_name, &source_states_set.new_dumb_bdd);
-        let new_smart = self.new_smart.predecessors_async(
+        let new_smart = self.new_smart.predecessors_under_variable(
             transition_variable_name,
-            source_states_set.new_smart_bdd.clone(),
+            &source_states_set.new_smart_bdd,
         );
 
         TheFourImplsBdd {
@@ -222,42 +205,53 @@ where
         });
 }
 
-#[test]
-fn test_consistency_successosr_unary() {
-    consistency_check::<UnaryIntegerDomain>();
-}
-
-#[test]
-fn test_consistency_successosr_binary() {
-    consistency_check::<BinaryIntegerDomain<u8>>();
-}
-
-#[test]
-fn test_consistency_successosr_petri_net() {
-    consistency_check::<PetriNetIntegerDomain>();
-}
-
-#[test]
-fn test_consistency_successosr_gray() {
-    consistency_check::<GrayCodeIntegerDomain<u8>>();
-}
-
-#[test]
-fn test_consistency_predecessors_unary() {
-    predecessors_consistency_check::<UnaryIntegerDomain>();
-}
+/// Runs a full forward reachability from every "single value of a single variable" set
+/// using both implementations and checks that they agree on the final result.
+/// Uses the small hand-written models, since the reference implementation is rather slow.
+fn reachability_consistency_check<DO>()
+where
+    DO: SymbolicDomainOrd<u8>,
+{
+    std::fs::read_dir("data/manual")
+        .expect("could not read dir")
+        .for_each(|dirent| {
+            let tmp = dirent.expect("could not read dir entry").path();
+            let filepath = tmp.to_str().unwrap();
 
-#[test]
-fn test_consistency_predecessors_binary() {
-    predecessors_consistency_check::<BinaryIntegerDomain<u8>>();
-}
+            println!("dataset {}", filepath);
 
-#[test]
-fn test_consistency_predecessors_petri_net() {
-    predecessors_consistency_check::<PetriNetIntegerDomain>();
+            let the_four = TheTwoImpls::<DO>::from_path(filepath);
+            let variables = the_four.new_smart.get_system_variables();
+
+            for initial_state in the_four.bbd_for_each_value_of_each_variable() {
+                let mut result = initial_state;
+                'fwd: loop {
+                    for variable in variables.iter().rev() {
+                        let successors = the_four.successors_async(variable, &result);
+                        assert!(successors.are_same(&the_four), "successors are same");
+
+                        if !successors
+                            .new_smart_bdd
+                            .imp(&result.new_smart_bdd)
+                            .is_true()
+                        {
+                            result = TheFourImplsBdd {
+                                new_dumb_bdd: result.new_dumb_bdd.or(&successors.new_dumb_bdd),
+                                new_smart_bdd: result.new_smart_bdd.or(&successors.new_smart_bdd),
+                            };
+                            continue 'fwd;
+                        }
+                    }
+                    break;
+                }
+
+                assert!(result.are_same(&the_four), "reachable sets are same");
+            }
+        });
 }
 
-#[test]
-fn test_consistency_predecessors_gray() {
-    predecessors_consistency_check::<GrayCodeIntegerDomain<u8>>();
-}
+test_all_encodings!(
+    consistency_check,
+    predecessors_consistency_check,
+    reachability_consistency_check
+);
@@ -0,0 +1,120 @@
+use biodivine_lib_logical_models::prelude::{self as bio, symbolic_domain::SymbolicDomainOrd};
+
+mod common;
+use common::{from_dsl, test_all_encodings};
+
+/// `a` switches on (for good) and `b` follows it; the transitions between the states `(a, b)`
+/// are `00 -> 10`, `01 -> 00`, `01 -> 11` and `10 -> 11`, with `11` being the only fixed point.
+fn activation<DO: SymbolicDomainOrd<u8>>() -> bio::update_fn::SmartSystemUpdateFn<DO, u8> {
+    from_dsl(&[("a", "default 1"), ("b", "1: a == 1; default 0")])
+}
+
+fn ctl_operators<DO: SymbolicDomainOrd<u8>>() {
+    let system = activation::<DO>();
+    let unit = system.unit_vertex_set();
+    let no = system.get_bdd_variable_set().mk_false();
+    let value = |name: &str, value: u8| system.encode_one(name, &value).and(&unit);
+    let state = |a: u8, b: u8| value("a", a).and(&value("b", b));
+    let states = |list: &[(u8, u8)]| {
+        list.iter()
+            .fold(no.clone(), |acc, (a, b)| acc.or(&state(*a, *b)))
+    };
+
+    // the fixed point is its own successor, the other states are not
+    assert_eq!(system.ex(&state(1, 1)), states(&[(0, 1), (1, 0), (1, 1)]));
+    assert_eq!(system.ex(&state(0, 0)), state(0, 1));
+    assert_eq!(system.ex(&state(0, 1)), no);
+    assert_eq!(system.ex(&unit), unit);
+
+    assert_eq!(system.ef(&state(0, 0)), states(&[(0, 0), (0, 1)]));
+    assert_eq!(system.ef(&state(1, 1)), unit);
+    assert_eq!(system.ef(&no), no);
+
+    // `00` must leave `a == 0`, after which `01` can only go to `00` within it
+    assert_eq!(system.eg(&value("a", 0)), no);
+    assert_eq!(system.eg(&value("b", 1)), states(&[(0, 1), (1, 1)]));
+    assert_eq!(system.eg(&states(&[(0, 0), (1, 0)])), no);
+    assert_eq!(system.eg(&unit), unit);
+}
+
+test_all_encodings!(ctl_operators);
+
+fn self_loop_policy<DO: SymbolicDomainOrd<u8>>() {
+    use bio::update_fn::SelfLoopPolicy::{Exclude, Include};
+
+    let system = activation::<DO>();
+    let unit = system.unit_vertex_set();
+    let no = system.get_bdd_variable_set().mk_false();
+    let value = |name: &str, value: u8| system.encode_one(name, &value).and(&unit);
+    let state = |a: u8, b: u8| value("a", a).and(&value("b", b));
+    let states = |list: &[(u8, u8)]| {
+        list.iter()
+            .fold(no.clone(), |acc, (a, b)| acc.or(&state(*a, *b)))
+    };
+
+    // `a` is kept in `11`, but changed in `00`
+    assert_eq!(
+        system.successors_with_policy("a", &state(1, 1), Include),
+        state(1, 1)
+    );
+    assert_eq!(
+        system.successors_with_policy("a", &state(1, 1), Exclude),
+        no
+    );
+    assert_eq!(
+        system.successors_with_policy("a", &state(0, 0), Exclude),
+        state(1, 0)
+    );
+    assert_eq!(
+        system.predecessors_with_policy("b", &state(1, 1), Include),
+        states(&[(1, 0), (1, 1)])
+    );
+    assert_eq!(
+        system.predecessors_with_policy("b", &state(1, 1), Exclude),
+        state(1, 0)
+    );
+
+    // the explicit methods are the two policies
+    for name in ["a", "b"] {
+        assert_eq!(
+            system.successors_async(name, &unit),
+            system.successors_with_policy(name, &unit, Include)
+        );
+        assert_eq!(
+            system.successors_async_exclude_loops(name, &unit),
+            system.successors_with_policy(name, &unit, Exclude)
+        );
+        assert_eq!(
+            system.predecessors_async(name, unit.clone()),
+            system.predecessors_with_policy(name, &unit, Include)
+        );
+        assert_eq!(
+            system.predecessors_async_exclude_loops(name, &unit),
+            system.predecessors_with_policy(name, &unit, Exclude)
+        );
+    }
+
+    assert_eq!(
+        system.successors_any_with_policy(&state(0, 0), Include),
+        states(&[(0, 0), (1, 0)])
+    );
+    assert_eq!(
+        system.successors_any_with_policy(&state(0, 0), Exclude),
+        state(1, 0)
+    );
+    assert_eq!(
+        system.predecessors_any_with_policy(&state(1, 1), Exclude),
+        states(&[(0, 1), (1, 0)])
+    );
+    assert_eq!(
+        system.predecessors_async_any(&state(1, 1)),
+        states(&[(0, 1), (1, 0), (1, 1)])
+    );
+    // only the fixed point has no proper successor
+    assert_eq!(
+        unit.and_not(&system.predecessors_any_with_policy(&unit, Exclude)),
+        system.deadlock_states()
+    );
+}
+
+test_all_encodings!(self_loop_policy);
@@ -0,0 +1,537 @@
+use std::sync::Arc;
+
+use biodivine_lib_bdd::{
+    Bdd, BddPartialValuation, BddVariable, BddVariableSet, BddVariableSetBuilder,
+};
+use biodivine_lib_logical_models::prelude::domain_builder::DomainBuilder;
+use biodivine_lib_logical_models::prelude::symbolic_domain::{
+    BinaryIntegerDomain, DecodeOneError, DomainEncodingKind, EncodingKind, GrayCodeIntegerDomain,
+    PetriNetIntegerDomain, SymbolicDomain, SymbolicDomainOrd, UnaryIntegerDomain, ValueOutOfDomain,
+};
+use biodivine_lib_logical_models::testing::{verify_domain_roundtrip, RoundtripFailure};
+
+mod common;
+use common::test_all_encodings;
+
+/// Every value up to `max_value` must be encodable, anything above must be rejected
+/// without touching the valuation.
+fn out_of_range_values_are_rejected<DO>()
+where
+    DO: SymbolicDomainOrd<u8>,
+{
+    let max_value = 5;
+    let mut builder = BddVariableSetBuilder::new();
+    let domain = DO::new(&mut builder, "x", &max_value);
+    let _variable_set = builder.build();
+
+    for value in 0..=max_value {
+        let mut valuation = BddPartialValuation::empty();
+        assert!(domain.try_encode_bits(&mut valuation, &value).is_ok());
+    }
+
+    for value in [max_value + 1, 200, u8::MAX] {
+        let mut valuation = BddPartialValuation::empty();
+        assert_eq!(
+            domain.try_encode_bits(&mut valuation, &value),
+            Err(ValueOutOfDomain {
+                variables: domain.raw_bdd_variables(),
+                value,
+                max_value,
+            })
+        );
+        assert_eq!(valuation, BddPartialValuation::empty());
+    }
+}
+
+fn encode_bits_panics_on_out_of_range_value<DO>()
+where
+    DO: SymbolicDomainOrd<u8>,
+{
+    let mut builder = BddVariableSetBuilder::new();
+    let domain = DO::new(&mut builder, "x", &3);
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        domain.encode_bits(&mut BddPartialValuation::empty(), &4)
+    }));
+    assert!(result.is_err(), "encoding 4 into 0..=3 should panic");
+}
+
+/// Checks `encode_lt`, `encode_le`, `encode_gt` and `encode_ge` against the plain integer
+/// comparisons for every threshold in the domain, and that the results contain no invalid
+/// valuations. Comparing every domain against plain integers also means all of them agree
+/// with each other (in particular with the unary encoding).
+fn comparisons_match_integers<DO>()
+where
+    DO: SymbolicDomainOrd<u8>,
+{
+    for max_value in 0..=6u8 {
+        let mut builder = BddVariableSetBuilder::new();
+        let domain = DO::new(&mut builder, "x", &max_value);
+        let variable_set = builder.build();
+        let unit = domain.unit_collection(&variable_set);
+
+        let check = |encoded: Bdd, expected: &dyn Fn(u8) -> bool, operation: &str| {
+            assert!(encoded.imp(&unit).is_true(), "{operation} is not sanitized");
+            let decoded = domain.decode_collection_sorted(&variable_set, &encoded);
+            let expected = (0..=max_value)
+                .filter(|it| expected(*it))
+                .collect::<Vec<_>>();
+            assert_eq!(decoded, expected, "{operation} (max value {max_value})");
+        };
+
+        for threshold in 0..=max_value {
+            let encode =
+                |f: fn(&DO, &BddVariableSet, &u8) -> Bdd| f(&domain, &variable_set, &threshold);
+            check(
+                encode(DO::encode_lt),
+                &|it| it < threshold,
+                &format!("< {threshold}"),
+            );
+            check(
+                encode(DO::encode_le),
+                &|it| it <= threshold,
+                &format!("<= {threshold}"),
+            );
+            check(
+                encode(DO::encode_gt),
+                &|it| it > threshold,
+                &format!("> {threshold}"),
+            );
+            check(
+                encode(DO::encode_ge),
+                &|it| it >= threshold,
+                &format!(">= {threshold}"),
+            );
+        }
+    }
+}
+
+/// Thresholds above the maximum value (including any positive threshold of a zero-bit
+/// domain, which only contains zero) compare against the whole domain.
+fn comparisons_above_max_value<DO>()
+where
+    DO: SymbolicDomainOrd<u8>,
+{
+    for max_value in 0..=6u8 {
+        let mut builder = BddVariableSetBuilder::new();
+        let domain = DO::new(&mut builder, "x", &max_value);
+        let variable_set = builder.build();
+        let unit = domain.unit_collection(&variable_set);
+        let empty = domain.empty_collection(&variable_set);
+
+        // conceptually, the threshold `-1` is covered by `x < 0` and `x >= 0`
+        assert_eq!(domain.encode_lt(&variable_set, &0), empty);
+        assert_eq!(domain.encode_ge(&variable_set, &0), unit);
+
+        for threshold in max_value + 1..=max_value + 2 {
+            let context = format!("threshold {threshold} (max value {max_value})");
+            assert_eq!(
+                domain.encode_lt(&variable_set, &threshold),
+                unit,
+                "{context}"
+            );
+            assert_eq!(
+                domain.encode_le(&variable_set, &threshold),
+                unit,
+                "{context}"
+            );
+            assert_eq!(
+                domain.encode_gt(&variable_set, &threshold),
+                empty,
+                "{context}"
+            );
+            assert_eq!(
+                domain.encode_ge(&variable_set, &threshold),
+                empty,
+                "{context}"
+            );
+        }
+    }
+}
+
+/// `max_value` is the one the domain was created with, and `all_values` enumerates the
+/// same values as decoding the whole unit collection does.
+fn max_value_and_all_values<DO>()
+where
+    DO: SymbolicDomainOrd<u8>,
+{
+    for max_value in [0, 1, 2, 5, 8] {
+        let mut builder = BddVariableSetBuilder::new();
+        let domain = DO::new(&mut builder, "x", &max_value);
+        let variable_set = builder.build();
+
+        assert_eq!(domain.max_value(), max_value);
+        assert_eq!(domain.all_values(), (0..=max_value).collect::<Vec<_>>());
+
+        let unit = domain.unit_collection(&variable_set);
+        assert_eq!(
+            domain.all_values(),
+            domain.decode_collection_sorted(&variable_set, &unit)
+        );
+
+        // the plain decoding yields the same values, just not necessarily sorted
+        let mut decoded = domain.decode_collection(&variable_set, &unit);
+        decoded.sort();
+        assert_eq!(domain.all_values(), decoded);
+    }
+}
+
+/// A single value is decoded regardless of the other variables in the `Bdd`;
+/// empty and multi-value sets are reported as errors.
+fn try_decode_one_rejects_non_single_values<DO>()
+where
+    DO: SymbolicDomainOrd<u8>,
+{
+    let mut builder = BddVariableSetBuilder::new();
+    let domain = DO::new(&mut builder, "x", &4);
+    let other = builder.make_variable("y");
+    let variable_set = builder.build();
+
+    for value in 0..=4 {
+        let encoded = domain.encode_one(&variable_set, &value);
+        assert_eq!(domain.try_decode_one(&variable_set, &encoded), Ok(value));
+
+        let with_other = encoded.and(&variable_set.mk_var(other));
+        assert_eq!(domain.decode_one(&variable_set, &with_other), value);
+    }
+
+    assert_eq!(
+        domain.try_decode_one(&variable_set, &variable_set.mk_false()),
+        Err(DecodeOneError::Empty)
+    );
+    assert_eq!(
+        domain.try_decode_one(&variable_set, &domain.encode_lt(&variable_set, &3)),
+        Err(DecodeOneError::NotSingleValue { cardinality: 3.0 })
+    );
+    assert_eq!(
+        domain.try_decode_one(&variable_set, &variable_set.mk_true()),
+        Err(DecodeOneError::NotSingleValue { cardinality: 5.0 })
+    );
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        domain.decode_one(&variable_set, &variable_set.mk_true())
+    }));
+    assert!(result.is_err(), "decoding a multi-value set should panic");
+}
+
+/// Once the context is set, the collections can be created without passing it around.
+fn collections_in_context<DO>()
+where
+    DO: SymbolicDomainOrd<u8>,
+{
+    let mut builder = BddVariableSetBuilder::new();
+    let mut domain = DO::new(&mut builder, "x", &3);
+    let variable_set = builder.build();
+
+    assert!(domain.context().is_none());
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        domain.unit_collection_in_context()
+    }));
+    assert!(result.is_err(), "there is no context to create the bdd in");
+
+    domain.set_context(Arc::new(variable_set.clone()));
+    assert_eq!(
+        domain.unit_collection_in_context(),
+        domain.unit_collection(&variable_set)
+    );
+    assert_eq!(
+        domain.empty_collection_in_context(),
+        domain.empty_collection(&variable_set)
+    );
+    for value in 0..=3 {
+        assert_eq!(
+            domain.encode_one_in_context(&value),
+            domain.encode_one(&variable_set, &value)
+        );
+    }
+}
+
+test_all_encodings!(comparisons_match_integers);
+
+test_all_encodings!(
+    out_of_range_values_are_rejected,
+    encode_bits_panics_on_out_of_range_value,
+);
+
+/// A unary domain implemented outside of the crate, relying on the provided
+/// `try_encode_bits`.
+struct ExternalDomain(UnaryIntegerDomain);
+
+impl SymbolicDomain<u8> for ExternalDomain {
+    fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &u8) {
+        self.0.encode_bits(bdd_valuation, value)
+    }
+
+    fn empty_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
+        self.0.empty_collection(bdd_variable_set)
+    }
+
+    fn unit_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
+        self.0.unit_collection(bdd_variable_set)
+    }
+
+    fn set_context(&mut self, variables: Arc<BddVariableSet>) {
+        self.0.set_context(variables)
+    }
+
+    fn context(&self) -> Option<&BddVariableSet> {
+        self.0.context()
+    }
+
+    fn raw_bdd_variables_unsorted(&self) -> Vec<BddVariable> {
+        self.0.raw_bdd_variables_unsorted()
+    }
+
+    fn decode_bits(&self, bdd_valuation: &BddPartialValuation) -> u8 {
+        self.0.decode_bits(bdd_valuation)
+    }
+}
+
+impl SymbolicDomainOrd<u8> for ExternalDomain {
+    fn new(builder: &mut BddVariableSetBuilder, name: &str, max_value: &u8) -> Self {
+        Self(UnaryIntegerDomain::new(builder, name, max_value))
+    }
+
+    fn required_bits(max_value: &u8) -> usize {
+        UnaryIntegerDomain::required_bits(max_value)
+    }
+
+    fn encode_lt(&self, bdd_variable_set: &BddVariableSet, exclusive_upper_bound: &u8) -> Bdd {
+        self.0.encode_lt(bdd_variable_set, exclusive_upper_bound)
+    }
+
+    fn cmp(lhs: &u8, rhs: &u8) -> std::cmp::Ordering {
+        UnaryIntegerDomain::cmp(lhs, rhs)
+    }
+
+    fn get_all_possible_values(&self) -> Vec<u8> {
+        self.0.get_all_possible_values()
+    }
+
+    fn max_value(&self) -> u8 {
+        self.0.max_value()
+    }
+}
+
+#[test]
+fn out_of_range_values_are_rejected_by_default() {
+    out_of_range_values_are_rejected::<ExternalDomain>();
+    encode_bits_panics_on_out_of_range_value::<ExternalDomain>();
+}
+
+/// Decoding a BDD built against a different (larger) `BddVariableSet` must not silently
+/// produce a wrong result.
+#[cfg(debug_assertions)]
+fn decoding_foreign_bdd_panics<DO>()
+where
+    DO: SymbolicDomainOrd<u8>,
+{
+    let mut builder = BddVariableSetBuilder::new();
+    let domain = DO::new(&mut builder, "x", &3);
+    let variable_set = builder.build();
+
+    let all_values = domain.unit_collection(&variable_set);
+    let mut decoded = domain.decode_collection(&variable_set, &all_values);
+    decoded.sort_unstable();
+    assert_eq!(decoded, vec![0, 1, 2, 3]);
+
+    let mut foreign_builder = BddVariableSetBuilder::new();
+    let _foreign_domain = DO::new(&mut foreign_builder, "x", &3);
+    let foreign_variable = foreign_builder.make_variable("y");
+    let foreign_set = foreign_builder.build();
+    let foreign_bdd = foreign_set.mk_var(foreign_variable);
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        domain.decode_collection(&variable_set, &foreign_bdd)
+    }));
+    assert!(result.is_err(), "decoding a foreign BDD should panic");
+}
+
+#[cfg(debug_assertions)]
+test_all_encodings!(decoding_foreign_bdd_panics);
+
+test_all_encodings!(comparisons_above_max_value);
+
+test_all_encodings!(max_value_and_all_values);
+
+#[test]
+fn all_binary_values_up_to_u8_max() {
+    // the enumeration stops at the greatest representable value
+    let mut builder = BddVariableSetBuilder::new();
+    let domain = BinaryIntegerDomain::<u8>::new(&mut builder, "x", &u8::MAX);
+    assert_eq!(domain.all_values().len(), 256);
+}
+
+test_all_encodings!(try_decode_one_rejects_non_single_values);
+
+/// Every value of every domain up to some size survives the encoding round-trip.
+fn values_round_trip<DO>()
+where
+    DO: SymbolicDomainOrd<u8>,
+{
+    for max_value in ROUNDTRIP_MAX_VALUES {
+        let mut builder = BddVariableSetBuilder::new();
+        let domain = DO::new(&mut builder, "x", &max_value);
+        let variable_set = builder.build();
+
+        let values = (0..=max_value).collect::<Vec<_>>();
+        assert_eq!(
+            verify_domain_roundtrip(&domain, &variable_set, &values),
+            Ok(()),
+            "max value {max_value}"
+        );
+    }
+}
+
+const ROUNDTRIP_MAX_VALUES: [u8; 7] = [0, 1, 2, 7, 8, 31, u8::MAX];
+
+/// A binary domain with one deliberately broken part, to check the round-trip helper.
+struct BrokenDomain {
+    inner: BinaryIntegerDomain<u8>,
+    flaw: Flaw,
+}
+
+enum Flaw {
+    /// always decodes as `0`
+    Decoding,
+    /// the unit collection is empty
+    UnitCollection,
+    /// ignores the lowest bit of the encoded values
+    Encoding,
+}
+
+impl SymbolicDomain<u8> for BrokenDomain {
+    fn encode_bits(&self, bdd_valuation: &mut BddPartialValuation, value: &u8) {
+        self.inner.encode_bits(bdd_valuation, value);
+        if let Flaw::Encoding = self.flaw {
+            bdd_valuation.unset_value(self.inner.raw_bdd_variables()[0]);
+        }
+    }
+
+    fn empty_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
+        self.inner.empty_collection(bdd_variable_set)
+    }
+
+    fn unit_collection(&self, bdd_variable_set: &BddVariableSet) -> Bdd {
+        match self.flaw {
+            Flaw::UnitCollection => bdd_variable_set.mk_false(),
+            _ => self.inner.unit_collection(bdd_variable_set),
+        }
+    }
+
+    fn set_context(&mut self, variables: Arc<BddVariableSet>) {
+        self.inner.set_context(variables)
+    }
+
+    fn context(&self) -> Option<&BddVariableSet> {
+        self.inner.context()
+    }
+
+    fn raw_bdd_variables_unsorted(&self) -> Vec<BddVariable> {
+        self.inner.raw_bdd_variables_unsorted()
+    }
+
+    fn decode_bits(&self, bdd_valuation: &BddPartialValuation) -> u8 {
+        match self.flaw {
+            Flaw::Decoding => 0,
+            _ => self.inner.decode_bits(bdd_valuation),
+        }
+    }
+}
+
+#[test]
+fn roundtrip_failures_are_reported() {
+    let check = |flaw: Flaw| {
+        let mut builder = BddVariableSetBuilder::new();
+        let inner = BinaryIntegerDomain::<u8>::new(&mut builder, "x", &3);
+        let variable_set = builder.build();
+        let domain = BrokenDomain { inner, flaw };
+        verify_domain_roundtrip(&domain, &variable_set, &[0, 1, 2, 3])
+    };
+
+    assert_eq!(
+        check(Flaw::Decoding),
+        Err(RoundtripFailure::DecodedDifferently {
+            value: 1,
+            decoded: vec![0]
+        })
+    );
+    assert_eq!(
+        check(Flaw::UnitCollection),
+        Err(RoundtripFailure::OutsideUnitCollection { value: 0 })
+    );
+    assert!(matches!(
+        check(Flaw::Encoding),
+        Err(RoundtripFailure::DecodedDifferently { .. })
+    ));
+}
+
+test_all_encodings!(values_round_trip);
+
+test_all_encodings!(collections_in_context);
+
+#[test]
+fn encoding_kinds_are_distinct_and_round_trip() {
+    let kinds = [
+        UnaryIntegerDomain::encoding_kind(),
+        PetriNetIntegerDomain::encoding_kind(),
+        BinaryIntegerDomain::<u8>::encoding_kind(),
+        GrayCodeIntegerDomain::<u8>::encoding_kind(),
+    ];
+    assert_eq!(
+        kinds,
+        [
+            EncodingKind::Unary,
+            EncodingKind::PetriNet,
+            EncodingKind::Binary,
+            EncodingKind::GrayCode,
+        ]
+    );
+    for kind in kinds {
+        assert_eq!(kind.to_string().parse::<EncodingKind>(), Ok(kind));
+    }
+    assert!("ternary".parse::<EncodingKind>().is_err());
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_variable_aliases() {
+    let mut builder = BddVariableSetBuilder::new();
+    let domain = BinaryIntegerDomain::<u8>::new(&mut builder, "x", &5);
+    assert_eq!(domain.symbolic_variables(), domain.raw_bdd_variables());
+    assert_eq!(domain.symbolic_size(), domain.raw_bdd_variables().len());
+}
+
+/// The unit collections translated from the per-max-value templates must be the same as
+/// those computed by the domains themselves, also for the domains made after the template.
+fn domain_builder_unit_collections<DO>()
+where
+    DO: SymbolicDomainOrd<u8>,
+{
+    let max_values = [1, 3, 1, 5, 3, 1, 0];
+    let mut builder = DomainBuilder::<DO, u8>::new();
+    let domains = max_values
+        .iter()
+        .enumerate()
+        .map(|(idx, max_value)| builder.make_domain(&format!("x{}", idx), max_value))
+        .collect::<Vec<_>>();
+    let variable_set = builder.build();
+
+    for domain in &domains {
+        assert_eq!(
+            builder.unit_collection(&variable_set, domain),
+            domain.unit_collection(&variable_set)
+        );
+    }
+
+    // a domain made elsewhere falls back to its own unit collection if there is no template
+    let mut other = BddVariableSetBuilder::new();
+    let foreign = DO::new(&mut other, "y", &4);
+    let other_set = other.build();
+    assert_eq!(
+        builder.unit_collection(&other_set, &foreign),
+        foreign.unit_collection(&other_set)
+    );
+}
+
+test_all_encodings!(domain_builder_unit_collections);
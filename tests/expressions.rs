@@ -0,0 +1,334 @@
+use std::collections::HashSet;
+
+use biodivine_lib_logical_models::prelude::{
+    expression::Expression,
+    find_start_of,
+    proposition::{ComparisonOperator, Proposition, VariableComparison},
+    XmlReadingError,
+};
+
+fn terminal<T>(operator: ComparisonOperator, variable: &str, value: T) -> Expression<T> {
+    Expression::Terminal(Proposition::new(operator, variable.to_string(), value))
+}
+
+#[test]
+fn map_values_keeps_the_structure() {
+    let expression: Expression<u8> = Expression::Or(vec![
+        Expression::Not(Box::new(terminal(ComparisonOperator::Eq, "a", 1))),
+        Expression::And(vec![
+            terminal(ComparisonOperator::Geq, "b", 2),
+            Expression::Xor(
+                Box::new(terminal(ComparisonOperator::Lt, "c", 3)),
+                Box::new(Expression::Implies(
+                    Box::new(terminal(ComparisonOperator::Neq, "a", 0)),
+                    Box::new(terminal(ComparisonOperator::Gt, "b", 255)),
+                )),
+            ),
+        ]),
+    ]);
+
+    let expected: Expression<u16> = Expression::Or(vec![
+        Expression::Not(Box::new(terminal(ComparisonOperator::Eq, "a", 10))),
+        Expression::And(vec![
+            terminal(ComparisonOperator::Geq, "b", 20),
+            Expression::Xor(
+                Box::new(terminal(ComparisonOperator::Lt, "c", 30)),
+                Box::new(Expression::Implies(
+                    Box::new(terminal(ComparisonOperator::Neq, "a", 0)),
+                    Box::new(terminal(ComparisonOperator::Gt, "b", 2550)),
+                )),
+            ),
+        ]),
+    ]);
+
+    assert_eq!(
+        expression.map_values(|value| u16::from(value) * 10),
+        expected
+    );
+}
+
+#[test]
+fn map_values_of_proposition() {
+    let proposition = Proposition::new(ComparisonOperator::Leq, "a".to_string(), 2u8);
+    assert_eq!(
+        proposition.map_values(|value| value.to_string()),
+        Proposition::new(ComparisonOperator::Leq, "a".to_string(), "2".to_string())
+    );
+}
+
+#[test]
+fn variable_comparison_references_both_operands() {
+    let expression: Expression<u8> = Expression::And(vec![
+        terminal(ComparisonOperator::Eq, "a", 1),
+        Expression::VariableComparison(VariableComparison::new(
+            ComparisonOperator::Gt,
+            "b".to_string(),
+            "c".to_string(),
+        )),
+    ]);
+    assert_eq!(
+        expression.referenced_variables(),
+        HashSet::from(["a", "b", "c"])
+    );
+    assert_eq!(expression.propositions().len(), 1);
+    assert_eq!(expression.clone().map_values(|value| value), expression);
+}
+
+#[test]
+fn comparison_operators_parse_and_negate() {
+    use ComparisonOperator::*;
+
+    let spellings = [
+        ("=", Eq),
+        ("==", Eq),
+        ("!=", Neq),
+        ("<>", Neq),
+        ("<", Lt),
+        ("<=", Leq),
+        (">", Gt),
+        (">=", Geq),
+    ];
+    for (spelling, operator) in spellings {
+        assert_eq!(spelling.parse::<ComparisonOperator>(), Ok(operator));
+    }
+    assert!("=>".parse::<ComparisonOperator>().is_err());
+
+    let all = [Eq, Neq, Lt, Leq, Gt, Geq];
+    assert_eq!(HashSet::from(all).len(), all.len());
+    for operator in all {
+        assert_eq!(
+            operator.to_string().parse(),
+            Ok(operator),
+            "display round trips"
+        );
+        assert_eq!(operator.negate().negate(), operator);
+        for lhs in 0u8..3 {
+            for rhs in 0u8..3 {
+                assert_ne!(
+                    operator.holds_for(lhs.cmp(&rhs)),
+                    operator.negate().holds_for(lhs.cmp(&rhs))
+                );
+            }
+        }
+    }
+    assert_eq!(Lt.negate(), Geq);
+}
+
+#[test]
+fn flatten_merges_nested_clauses() {
+    let a = || terminal(ComparisonOperator::Eq, "a", 1);
+    let b = || terminal(ComparisonOperator::Eq, "b", 1);
+    let c = || terminal(ComparisonOperator::Lt, "c", 2);
+
+    let nested: Expression<u8> = Expression::And(vec![
+        a(),
+        Expression::And(vec![b(), Expression::And(vec![c()])]),
+        Expression::Or(vec![Expression::Or(vec![a(), b()]), Expression::Or(vec![])]),
+        Expression::Not(Box::new(Expression::Or(vec![Expression::And(vec![c()])]))),
+    ]);
+    let expected = Expression::And(vec![
+        a(),
+        b(),
+        c(),
+        Expression::Or(vec![a(), b()]),
+        Expression::Not(Box::new(c())),
+    ]);
+    assert_eq!(nested.flatten(), expected);
+
+    // an empty `Or` (constant false) must not be dropped from a conjunction
+    let with_false: Expression<u8> = Expression::And(vec![a(), Expression::Or(vec![])]);
+    assert_eq!(with_false.clone().flatten(), with_false);
+}
+
+#[test]
+fn display_is_readable() {
+    use biodivine_lib_logical_models::prelude::unprocessed_variable_update_function::UnprocessedVariableUpdateFn;
+
+    assert_eq!(format!("{}", ComparisonOperator::Geq), "geq");
+    assert_eq!(format!("{:#}", ComparisonOperator::Geq), ">=");
+    for operator in [
+        ComparisonOperator::Eq,
+        ComparisonOperator::Neq,
+        ComparisonOperator::Lt,
+        ComparisonOperator::Gt,
+        ComparisonOperator::Leq,
+        ComparisonOperator::Geq,
+    ] {
+        assert_eq!(format!("{:#}", operator).parse(), Ok(operator));
+    }
+
+    let expression: Expression<u8> = Expression::And(vec![
+        terminal(ComparisonOperator::Geq, "A", 1),
+        Expression::Or(vec![
+            terminal(ComparisonOperator::Lt, "B", 3),
+            Expression::Not(Box::new(terminal(ComparisonOperator::Eq, "C", 0))),
+            Expression::VariableComparison(VariableComparison::new(
+                ComparisonOperator::Gt,
+                "A".to_string(),
+                "B".to_string(),
+            )),
+        ]),
+        Expression::Implies(
+            Box::new(Expression::Or(vec![])),
+            Box::new(Expression::Xor(
+                Box::new(terminal(ComparisonOperator::Neq, "A", 2)),
+                Box::new(Expression::And(vec![])),
+            )),
+        ),
+    ]);
+    assert_eq!(
+        expression.to_string(),
+        "A >= 1 & (B < 3 | !(C == 0) | A > B) & (false => (A != 2 ^ true))"
+    );
+
+    let update_fn = UnprocessedVariableUpdateFn::new(
+        vec!["A".to_string(), "B".to_string(), "C".to_string()],
+        "target".to_string(),
+        vec![
+            (
+                2u8,
+                Expression::And(vec![
+                    terminal(ComparisonOperator::Geq, "A", 1),
+                    terminal(ComparisonOperator::Lt, "B", 3),
+                ]),
+            ),
+            (1, terminal(ComparisonOperator::Eq, "C", 0)),
+        ],
+        0,
+    );
+    assert_eq!(
+        update_fn.to_string(),
+        "f(target) = 2 if (A >= 1 & B < 3); 1 if (C == 0); else 0"
+    );
+
+    let constant = UnprocessedVariableUpdateFn::<u8>::new(vec![], "c".to_string(), vec![], 1);
+    assert_eq!(constant.to_string(), "f(c) = 1");
+}
+
+/// Parses the condition of a function term, i.e. the `<math>` element.
+fn parse_mathml(math: &str) -> Expression<u8> {
+    try_parse_mathml(math).unwrap_or_else(|error| panic!("cannot parse {}: {}", math, error))
+}
+
+/// Like `parse_mathml`, but returns the error (if any) instead.
+fn mathml_error(math: &str) -> Option<XmlReadingError> {
+    try_parse_mathml(math).err()
+}
+
+#[allow(clippy::result_large_err)]
+fn try_parse_mathml(math: &str) -> Result<Expression<u8>, XmlReadingError> {
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(math.as_bytes()));
+    find_start_of(&mut xml, &["apply"]).expect("there is an apply element");
+    Expression::try_from_xml(&mut xml)
+}
+
+#[test]
+fn mathml_comparisons() {
+    use ComparisonOperator::*;
+
+    // the function term of the SBML-qual specification example
+    let geq = parse_mathml(
+        r#"<math xmlns="http://www.w3.org/1998/Math/MathML">
+             <apply>
+               <geq/>
+               <ci> A </ci>
+               <cn type="integer"> 1 </cn>
+             </apply>
+           </math>"#,
+    );
+    assert_eq!(geq, terminal(Geq, "A", 1));
+
+    for (name, operator) in [
+        ("eq", Eq),
+        ("neq", Neq),
+        ("lt", Lt),
+        ("gt", Gt),
+        ("leq", Leq),
+        ("geq", Geq),
+    ] {
+        let variable_first =
+            format!(r#"<math><apply><{name}/><ci>A</ci><cn type="integer">2</cn></apply></math>"#);
+        assert_eq!(parse_mathml(&variable_first), terminal(operator, "A", 2));
+
+        // `2 op A` is the same as `A op.flip() 2`
+        let constant_first =
+            format!(r#"<math><apply><{name}/><cn type="integer">2</cn><ci>A</ci></apply></math>"#);
+        assert_eq!(
+            parse_mathml(&constant_first),
+            terminal(operator.flip(), "A", 2)
+        );
+
+        let two_variables = format!(r#"<math><apply><{name}/><ci>A</ci><ci>B</ci></apply></math>"#);
+        assert_eq!(
+            parse_mathml(&two_variables),
+            Expression::VariableComparison(VariableComparison::new(
+                operator,
+                "A".to_string(),
+                "B".to_string()
+            ))
+        );
+    }
+}
+
+#[test]
+fn mathml_logical_connectives() {
+    use ComparisonOperator::*;
+
+    let a = || terminal(Eq, "A", 1);
+    let b = || terminal(Lt, "B", 2);
+    let c = || terminal(Geq, "C", 1);
+    let (a_xml, b_xml, c_xml) = (
+        r#"<apply><eq/><ci>A</ci><cn type="integer">1</cn></apply>"#,
+        r#"<apply><lt/><ci>B</ci><cn type="integer">2</cn></apply>"#,
+        r#"<apply><geq/><ci>C</ci><cn type="integer">1</cn></apply>"#,
+    );
+    let parse = |apply: String| parse_mathml(&format!("<math>{apply}</math>"));
+
+    assert_eq!(
+        parse(format!("<apply><not/>{a_xml}</apply>")),
+        Expression::Not(Box::new(a()))
+    );
+    assert_eq!(
+        parse(format!("<apply><and/>{a_xml}{b_xml}{c_xml}</apply>")),
+        Expression::And(vec![a(), b(), c()])
+    );
+    assert_eq!(
+        parse(format!("<apply><or/>{a_xml}{b_xml}</apply>")),
+        Expression::Or(vec![a(), b()])
+    );
+    assert_eq!(
+        parse(format!("<apply><xor/>{a_xml}{b_xml}</apply>")),
+        Expression::Xor(Box::new(a()), Box::new(b()))
+    );
+    assert_eq!(
+        parse(format!("<apply><implies/>{a_xml}{b_xml}</apply>")),
+        Expression::Implies(Box::new(a()), Box::new(b()))
+    );
+
+    // (A = 1 and not (B < 2)) or C >= 1, with whitespace as in real files
+    let nested = format!(
+        "<apply>\n  <or/>\n  <apply>\n    <and/>\n    {a_xml}\n    <apply>\n      <not/>\n      \
+        {b_xml}\n    </apply>\n  </apply>\n  {c_xml}\n</apply>"
+    );
+    assert_eq!(
+        parse(nested),
+        Expression::Or(vec![
+            Expression::And(vec![a(), Expression::Not(Box::new(b()))]),
+            c()
+        ])
+    );
+}
+
+#[test]
+fn mathml_unsupported_operator() {
+    let error =
+        mathml_error(r#"<math><apply><plus/><ci>A</ci><cn type="integer">1</cn></apply></math>"#);
+    match error {
+        Some(XmlReadingError::UnsupportedOperator(ref operator)) => {
+            assert_eq!(operator, "plus");
+            assert!(error.unwrap().to_string().contains("plus"));
+        }
+        Some(other) => panic!("unexpected error: {}", other),
+        None => panic!("arithmetic should not be accepted"),
+    }
+}
@@ -0,0 +1,214 @@
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    symbolic_domain::{SymbolicDomainOrd, UnaryIntegerDomain},
+    XmlReadingError,
+};
+
+/// Tries to load the model, returning the error if the loading fails.
+fn loading_error(sbml_path: &str) -> Option<XmlReadingError> {
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open(sbml_path).expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, &["listOfTransitions"]).expect("should be able to find");
+    bio::update_fn::SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::try_from_xml(&mut xml).err()
+}
+
+#[test]
+fn duplicate_variable_is_reported() {
+    match loading_error("data/invalid/duplicate_variable.sbml") {
+        Some(XmlReadingError::DuplicateVariable(name)) => assert_eq!(name, "a"),
+        Some(other) => panic!("unexpected error: {}", other),
+        None => panic!("duplicate variable should not be accepted"),
+    }
+}
+
+#[test]
+fn missing_update_function_is_reported() {
+    match loading_error("data/invalid/missing_update_fn.sbml") {
+        Some(XmlReadingError::MissingUpdateFunction(name)) => assert_eq!(name, "b"),
+        Some(other) => panic!("unexpected error: {}", other),
+        None => panic!("variable without update function should not be accepted"),
+    }
+}
+
+#[test]
+fn valid_models_are_still_accepted() {
+    std::fs::read_dir("data/manual")
+        .expect("could not read dir")
+        .chain(std::fs::read_dir("data/test-models").expect("could not read dir"))
+        .for_each(|dirent| {
+            let tmp = dirent.expect("could not read dir entry").path();
+            let filepath = tmp.to_str().unwrap();
+            if let Some(err) = loading_error(filepath) {
+                panic!("{} should load, but got: {}", filepath, err);
+            }
+        });
+}
+
+/// Like [loading_error], but loads the model in the strict mode.
+fn strict_loading_error(sbml_path: &str) -> Option<XmlReadingError> {
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open(sbml_path).expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, &["listOfTransitions"]).expect("should be able to find");
+    bio::update_fn::SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::try_from_xml_strict(&mut xml)
+        .err()
+}
+
+#[test]
+fn out_of_domain_constant_is_reported_in_strict_mode() {
+    let path = "data/invalid/out_of_domain_constant.sbml";
+    assert!(
+        loading_error(path).is_none(),
+        "relaxed mode enlarges the domain"
+    );
+
+    match strict_loading_error(path) {
+        Some(XmlReadingError::OutOfDomainConstant { variable, value }) => {
+            assert_eq!(variable, "a");
+            assert_eq!(value, "200");
+        }
+        Some(other) => panic!("unexpected error: {}", other),
+        None => panic!("out of domain constant should not be accepted"),
+    }
+}
+
+#[test]
+fn consistent_models_are_accepted_in_strict_mode() {
+    // note that e.g. `handbook_example.sbml` is rejected, since it compares variables that are
+    // only ever updated to 0 with 1
+    [
+        "data/manual/three_level_variable.sbml",
+        "data/manual/signed_influences.sbml",
+        "data/manual/constant_variables.sbml",
+    ]
+    .into_iter()
+    .for_each(|filepath| {
+        if let Some(err) = strict_loading_error(filepath) {
+            panic!("{} should load, but got: {}", filepath, err);
+        }
+    });
+}
+
+#[test]
+fn undeclared_inputs_are_inferred_from_the_terms() {
+    let path = "data/manual/inferred_inputs.sbml";
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open(path).expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, &["listOfTransitions"]).expect("should be able to find");
+    let update_fns = bio::load_all_update_fns::<_, _, u8>(&mut xml).expect("should load");
+    assert_eq!(update_fns["x"].input_vars_names, vec!["a", "b"]);
+
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open(path).expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, &["listOfTransitions"]).expect("should be able to find");
+    let system =
+        bio::update_fn::SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::try_from_xml(&mut xml)
+            .expect("should load");
+    assert!(system.get_domain("a").is_some());
+    assert!(system.get_domain("b").is_some());
+}
+
+/// Loads the model, synthesizing the update functions of the variables without a transition.
+fn load_with_inputs(
+    sbml_path: &str,
+    inputs: bio::InputUpdateFn,
+) -> (
+    bio::update_fn::SmartSystemUpdateFn<UnaryIntegerDomain, u8>,
+    Vec<String>,
+) {
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open(sbml_path).expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, &["listOfTransitions"]).expect("should be able to find");
+    bio::update_fn::SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::try_from_xml_with_inputs(
+        &mut xml, inputs,
+    )
+    .expect("should load")
+}
+
+#[test]
+fn missing_update_functions_can_be_synthesized() {
+    let path = "data/invalid/missing_update_fn.sbml";
+
+    let (constant, synthesized) = load_with_inputs(path, bio::InputUpdateFn::Constant);
+    assert_eq!(synthesized, vec!["b"]);
+    let b_is_one = constant
+        .encode_one("b", &1)
+        .and(&constant.unit_vertex_set());
+    let b_is_zero = constant
+        .encode_one("b", &0)
+        .and(&constant.unit_vertex_set());
+    assert_eq!(
+        constant.transition_under_variable("b", &b_is_one),
+        b_is_zero
+    );
+
+    let (identity, synthesized) = load_with_inputs(path, bio::InputUpdateFn::Identity);
+    assert_eq!(synthesized, vec!["b"]);
+    let b_is_one = identity
+        .encode_one("b", &1)
+        .and(&identity.unit_vertex_set());
+    assert_eq!(identity.transition_under_variable("b", &b_is_one), b_is_one);
+    // the domain of `b` is the same as when the function is constant
+    assert_eq!(
+        identity
+            .get_domain("b")
+            .map(|domain| domain.get_all_possible_values()),
+        constant
+            .get_domain("b")
+            .map(|domain| domain.get_all_possible_values())
+    );
+}
+
+#[test]
+fn only_variables_without_transition_are_synthesized() {
+    let (_, synthesized) = load_with_inputs(
+        "data/manual/inferred_inputs.sbml",
+        bio::InputUpdateFn::Identity,
+    );
+    assert_eq!(synthesized, vec!["a", "b"]);
+
+    let (_, synthesized) = load_with_inputs(
+        "data/manual/handbook_example.sbml",
+        bio::InputUpdateFn::Identity,
+    );
+    assert!(synthesized.is_empty());
+}
+
+#[test]
+fn nested_path_is_found() {
+    let document = r#"<sbml>
+        <listOfTransitions><outside/></listOfTransitions>
+        <model><wrapper><listOfTransitions><inside/></listOfTransitions></wrapper></model>
+    </sbml>"#;
+    let reader = || xml::reader::EventReader::new(std::io::BufReader::new(document.as_bytes()));
+    let next_element = |xml: &mut xml::reader::EventReader<_>| loop {
+        match xml.next().expect("should be able to read") {
+            xml::reader::XmlEvent::StartElement { name, .. } => return name.local_name,
+            xml::reader::XmlEvent::EndDocument => panic!("no element left"),
+            _ => continue,
+        }
+    };
+
+    let mut xml = reader();
+    bio::find_start_of(&mut xml, &["listOfTransitions"]).expect("should be able to find");
+    assert_eq!(next_element(&mut xml), "outside");
+
+    // the nested elements need not be direct children
+    let mut xml = reader();
+    bio::find_start_of(&mut xml, &["model", "listOfTransitions"]).expect("should be able to find");
+    assert_eq!(next_element(&mut xml), "inside");
+
+    // an element missing inside its parent is not searched for past the parent's end
+    let mut xml = reader();
+    match bio::find_start_of(&mut xml, &["wrapper", "outside"]) {
+        Err(XmlReadingError::UnexpectedEvent {
+            got: xml::reader::XmlEvent::EndElement { name },
+            ..
+        }) => assert_eq!(name.local_name, "wrapper"),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
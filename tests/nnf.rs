@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use biodivine_lib_bdd::{Bdd, BddVariableSet, BddVariableSetBuilder};
+use biodivine_lib_logical_models::prelude::{
+    expression::Expression,
+    proposition::{ComparisonOperator, Proposition, VariableComparison},
+    symbolic_domain::SymbolicDomainOrd,
+    unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+    update_fn::variable_update_fn::VariableUpdateFn,
+};
+
+mod common;
+use common::test_all_encodings;
+
+fn terminal(operator: ComparisonOperator, variable: &str, value: u8) -> Expression<u8> {
+    Expression::Terminal(Proposition::new(operator, variable.to_string(), value))
+}
+
+fn not(inner: Expression<u8>) -> Expression<u8> {
+    Expression::Not(Box::new(inner))
+}
+
+fn is_nnf(expression: &Expression<u8>) -> bool {
+    match expression {
+        Expression::Terminal(_) | Expression::VariableComparison(_) => true,
+        Expression::And(clauses) | Expression::Or(clauses) => clauses.iter().all(is_nnf),
+        Expression::Not(_) | Expression::Xor(..) | Expression::Implies(..) => false,
+    }
+}
+
+/// The set of (valid) valuations of `a` & `b` satisfying the `expression`.
+fn valuations<DO: SymbolicDomainOrd<u8>>(
+    expression: Expression<u8>,
+    variables: &BddVariableSet,
+    domains: &HashMap<&str, &DO>,
+    unit: &Bdd,
+) -> Bdd {
+    let update_fn = UnprocessedVariableUpdateFn::new(
+        vec!["a".to_string(), "b".to_string()],
+        "a".to_string(),
+        vec![(1, expression)],
+        0,
+    );
+    VariableUpdateFn::from_update_fn(&update_fn, "a", variables, domains)
+        .condition_for_value(&1)
+        .map(|condition| condition.and(unit))
+        .unwrap_or_else(|| variables.mk_false())
+}
+
+fn nnf_is_equivalent<DO: SymbolicDomainOrd<u8>>() {
+    use ComparisonOperator::*;
+
+    let mut builder = BddVariableSetBuilder::new();
+    let a = DO::new(&mut builder, "a", &2);
+    let b = DO::new(&mut builder, "b", &2);
+    let variables = builder.build();
+    let domains = HashMap::from([("a", &a), ("b", &b)]);
+    let unit = a
+        .unit_collection(&variables)
+        .and(&b.unit_collection(&variables));
+
+    let a_lt_2 = || terminal(Lt, "a", 2);
+    let b_eq_1 = || terminal(Eq, "b", 1);
+    let a_gt_b = || {
+        Expression::VariableComparison(VariableComparison::new(
+            Gt,
+            "a".to_string(),
+            "b".to_string(),
+        ))
+    };
+
+    let battery = vec![
+        not(a_lt_2()),
+        not(not(b_eq_1())),
+        not(a_gt_b()),
+        not(Expression::And(vec![a_lt_2(), b_eq_1(), a_gt_b()])),
+        not(Expression::Or(vec![a_lt_2(), not(b_eq_1())])),
+        Expression::Xor(Box::new(a_lt_2()), Box::new(b_eq_1())),
+        not(Expression::Xor(Box::new(a_gt_b()), Box::new(not(b_eq_1())))),
+        Expression::Implies(Box::new(a_lt_2()), Box::new(not(a_gt_b()))),
+        not(Expression::Implies(
+            Box::new(Expression::Or(vec![b_eq_1(), terminal(Geq, "a", 1)])),
+            Box::new(Expression::Xor(Box::new(a_lt_2()), Box::new(a_gt_b()))),
+        )),
+        not(Expression::And(vec![])),
+        not(Expression::Or(vec![])),
+    ];
+
+    for expression in battery {
+        let nnf = expression.clone().to_nnf();
+        assert!(is_nnf(&nnf), "{:?} is not in nnf", nnf);
+        assert_eq!(
+            valuations(expression.clone(), &variables, &domains, &unit),
+            valuations(nnf, &variables, &domains, &unit),
+            "nnf of {:?} is not equivalent",
+            expression
+        );
+    }
+}
+
+test_all_encodings!(nnf_is_equivalent);
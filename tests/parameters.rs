@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    expression::Expression,
+    proposition::{ComparisonOperator, Proposition},
+    symbolic_domain::{BinaryIntegerDomain, SymbolicDomainOrd},
+    unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+};
+
+mod common;
+use common::test_all_encodings;
+
+fn eq(variable: &str, value: u8) -> Expression<u8> {
+    Expression::Terminal(Proposition::new(
+        ComparisonOperator::Eq,
+        variable.to_string(),
+        value,
+    ))
+}
+
+/// `x' = 1 if p == 1`, `y' = 1 if x == 1`, where `p` is a parameter
+fn parametrised_system<DO: SymbolicDomainOrd<u8>>() -> bio::update_fn::SmartSystemUpdateFn<DO, u8> {
+    let update_fns = HashMap::from([
+        (
+            "x".to_string(),
+            UnprocessedVariableUpdateFn::new(
+                vec!["p".to_string()],
+                "x".to_string(),
+                vec![(1, eq("p", 1))],
+                0,
+            ),
+        ),
+        (
+            "y".to_string(),
+            UnprocessedVariableUpdateFn::new(
+                vec!["x".to_string()],
+                "y".to_string(),
+                vec![(1, eq("x", 1))],
+                0,
+            ),
+        ),
+    ]);
+
+    bio::update_fn::SmartSystemUpdateFn::from_update_fns_with_parameters(
+        update_fns,
+        &["p".to_string()],
+    )
+}
+
+fn coloured_successors<DO: SymbolicDomainOrd<u8>>() {
+    let system = parametrised_system::<DO>();
+    assert_eq!(system.get_system_variables(), vec!["x", "y"]);
+    assert_eq!(system.get_parameters(), vec!["p"]);
+
+    let unit = system.unit_vertex_set();
+    let p_is_one = system.encode_parameter("p", &1).and(&unit);
+    let p_is_zero = system.encode_parameter("p", &0).and(&unit);
+    assert_eq!(p_is_one.or(&p_is_zero), unit, "p is boolean");
+
+    let initial = system
+        .encode_one("x", &0)
+        .and(&system.encode_one("y", &0))
+        .and(&unit);
+
+    // the successor depends on the colour; the colour itself is never changed
+    let successors = system.successors_async("x", &initial);
+    let expected = system
+        .encode_one("x", &1)
+        .and(&system.encode_one("y", &0))
+        .and(&p_is_one)
+        .or(&initial.and(&p_is_zero));
+    assert_eq!(successors, expected);
+
+    let y_successors = system.successors_async("y", &successors.and(&p_is_one));
+    assert_eq!(
+        y_successors,
+        system
+            .encode_one("x", &1)
+            .and(&system.encode_one("y", &1))
+            .and(&p_is_one)
+    );
+
+    let predecessors = system.predecessors_async("x", successors.and(&p_is_one));
+    assert!(predecessors.imp(&p_is_one).is_true());
+    assert!(initial.and(&p_is_one).imp(&predecessors).is_true());
+
+    // the deadlocks of `p = 0` and `p = 1` differ
+    let deadlocks = system.deadlock_states();
+    assert_eq!(deadlocks.and(&p_is_zero), initial.and(&p_is_zero));
+    assert_eq!(
+        deadlocks.and(&p_is_one),
+        system
+            .encode_one("x", &1)
+            .and(&system.encode_one("y", &1))
+            .and(&p_is_one)
+    );
+}
+
+test_all_encodings!(coloured_successors);
+
+#[test]
+#[should_panic(expected = "cannot have an update function")]
+fn parameter_with_update_fn_is_rejected() {
+    let update_fns = HashMap::from([(
+        "x".to_string(),
+        UnprocessedVariableUpdateFn::new(vec![], "x".to_string(), vec![], 0u8),
+    )]);
+    bio::update_fn::SmartSystemUpdateFn::<BinaryIntegerDomain<u8>, u8>::from_update_fns_with_parameters(
+        update_fns,
+        &["x".to_string()],
+    );
+}
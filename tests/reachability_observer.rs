@@ -0,0 +1,251 @@
+use biodivine_lib_bdd::{Bdd, BddPartialValuation, BddValuation, BddVariableSet};
+use biodivine_lib_logical_models::benchmarks::{
+    observer::ReachabilityObserver,
+    rewritten_reachability::{
+        log_percent, reach_bwd, reach_bwd_ordered, reach_fwd, reach_fwd_frontier, reach_fwd_ordered,
+    },
+};
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    symbolic_domain::{SymbolicDomainOrd, UnaryIntegerDomain},
+};
+
+mod common;
+use common::{load, test_all_encodings};
+
+/// Records everything it is told.
+#[derive(Default)]
+struct Recorder {
+    iterations: Vec<(usize, Bdd, usize)>,
+    done: Vec<usize>,
+    images: usize,
+}
+
+impl ReachabilityObserver for Recorder {
+    fn on_iteration(&mut self, step: usize, result: &Bdd, bdd_size: usize) {
+        self.iterations.push((step, result.clone(), bdd_size));
+    }
+
+    fn on_done(&mut self, total_steps: usize) {
+        self.done.push(total_steps);
+    }
+
+    fn on_image(&mut self, _variable: &str) {
+        self.images += 1;
+    }
+}
+
+fn observer_sees_every_iteration<DO: SymbolicDomainOrd<u8> + std::fmt::Debug>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+
+    let initial = system
+        .encode_one("a", &0)
+        .and(&system.encode_one("b", &0))
+        .and(&system.unit_vertex_set());
+
+    for forward in [true, false] {
+        let mut recorder = Recorder::default();
+        let result = if forward {
+            reach_fwd(&system, &initial, Some(&mut recorder))
+        } else {
+            reach_bwd(&system, &initial, Some(&mut recorder))
+        };
+
+        assert_eq!(recorder.done, vec![recorder.iterations.len() - 1]);
+        assert_eq!(recorder.iterations[0].1, initial);
+        assert_eq!(recorder.iterations.last().unwrap().1, result);
+        for (idx, (step, set, size)) in recorder.iterations.iter().enumerate() {
+            assert_eq!(*step, idx);
+            assert_eq!(*size, set.size());
+        }
+        // every reported step strictly extends the result
+        for window in recorder.iterations.windows(2) {
+            assert!(window[0].1.imp(&window[1].1).is_true());
+            assert_ne!(window[0].1, window[1].1);
+        }
+
+        // the observer does not affect the result
+        let unobserved = if forward {
+            reach_fwd(&system, &initial, None)
+        } else {
+            reach_bwd(&system, &initial, None)
+        };
+        assert_eq!(result, unobserved);
+    }
+}
+
+test_all_encodings!(observer_sees_every_iteration);
+
+/// The frontier-based forward reachability must reach the same states as [reach_fwd];
+/// since it works in breadth-first rounds, it never needs more rounds than [reach_fwd]
+/// needs (single-variable) steps.
+fn frontier_matches_reach_fwd<DO: SymbolicDomainOrd<u8> + std::fmt::Debug>() {
+    std::fs::read_dir("data/manual")
+        .expect("could not read dir")
+        .for_each(|dirent| {
+            let tmp = dirent.expect("could not read dir entry").path();
+            let filepath = tmp.to_str().unwrap();
+
+            let system = load::<DO>(filepath);
+
+            for (name, domain) in system.standard_variables_names_and_domains() {
+                for value in domain.get_all_possible_values() {
+                    let initial = system
+                        .encode_one(name, &value)
+                        .and(&system.unit_vertex_set());
+
+                    let mut fwd_recorder = Recorder::default();
+                    let expected = reach_fwd(&system, &initial, Some(&mut fwd_recorder));
+                    let mut frontier_recorder = Recorder::default();
+                    let result =
+                        reach_fwd_frontier(&system, &initial, Some(&mut frontier_recorder));
+
+                    assert_eq!(result, expected, "{} {}={}", filepath, name, value);
+                    assert!(frontier_recorder.done[0] <= fwd_recorder.done[0]);
+                    assert_eq!(
+                        frontier_recorder.iterations.last().unwrap().1,
+                        result,
+                        "{}",
+                        filepath
+                    );
+                }
+            }
+        });
+}
+
+/// On the real models, the frontier-based reachability computes substantially fewer images
+/// (i.e. calls of the successor function) than [reach_fwd], which recomputes the successors
+/// of the whole result under every variable tried since the last extension.
+///
+/// Only the models of `data/large` that converge within a second in a debug build are used.
+fn frontier_computes_fewer_images<DO: SymbolicDomainOrd<u8> + std::fmt::Debug>() {
+    let (mut fwd_images, mut frontier_images) = (0, 0);
+    [
+        "data/large/157_CONTROL-OF-TH-DIFFERENTATION.sbml",
+        "data/large/175_SEA-URCHIN.sbml",
+        "data/large/178-mast-cell-activation.sbml",
+        "data/large/183-alterations-in-bladder.sbml",
+        "data/large/196-T-lymphocyte-specification.sbml",
+        "data/large/Leukaemia.free-inputs.sbml",
+    ]
+    .into_iter()
+    .for_each(|filepath| {
+        let system = load::<DO>(filepath);
+
+        let initial = system
+            .standard_variables_names_and_domains()
+            .iter()
+            .fold(system.unit_vertex_set(), |acc, (name, _)| {
+                acc.and(&system.encode_one(name, &0))
+            });
+
+        let mut fwd_recorder = Recorder::default();
+        let expected = reach_fwd(&system, &initial, Some(&mut fwd_recorder));
+        let mut frontier_recorder = Recorder::default();
+        let result = reach_fwd_frontier(&system, &initial, Some(&mut frontier_recorder));
+
+        assert_eq!(result, expected, "{}", filepath);
+        fwd_images += fwd_recorder.images;
+        frontier_images += frontier_recorder.images;
+    });
+
+    assert!(
+        2 * frontier_images <= fwd_images,
+        "{} images (reach_fwd) vs {} images (frontier)",
+        fwd_images,
+        frontier_images
+    );
+}
+
+test_all_encodings!(frontier_computes_fewer_images, frontier_matches_reach_fwd);
+
+/// The order in which the variables are tried affects only the number of the steps,
+/// not the computed closure.
+fn orderings_produce_identical_closures<DO: SymbolicDomainOrd<u8> + std::fmt::Debug>() {
+    std::fs::read_dir("data/manual")
+        .expect("could not read dir")
+        .for_each(|dirent| {
+            let tmp = dirent.expect("could not read dir entry").path();
+            let filepath = tmp.to_str().unwrap();
+
+            let system = load::<DO>(filepath);
+
+            let ascending = system.get_system_variables();
+            let mut rotated = ascending.clone();
+            rotated.rotate_left(ascending.len() / 2);
+
+            for (name, domain) in system.standard_variables_names_and_domains() {
+                for value in domain.get_all_possible_values() {
+                    let initial = system
+                        .encode_one(name, &value)
+                        .and(&system.unit_vertex_set());
+
+                    let fwd = reach_fwd(&system, &initial, None);
+                    let bwd = reach_bwd(&system, &initial, None);
+                    for order in [&ascending, &rotated] {
+                        let context = format!("{} {}={} {:?}", filepath, name, value, order);
+                        assert_eq!(
+                            reach_fwd_ordered(&system, &initial, order, None),
+                            fwd,
+                            "{}",
+                            context
+                        );
+                        assert_eq!(
+                            reach_bwd_ordered(&system, &initial, order, None),
+                            bwd,
+                            "{}",
+                            context
+                        );
+                    }
+                }
+            }
+        });
+}
+
+test_all_encodings!(orderings_produce_identical_closures);
+
+#[test]
+#[should_panic(expected = "missing from the order")]
+fn incomplete_order_is_rejected() {
+    let system = load::<UnaryIntegerDomain>("data/manual/three_level_variable.sbml");
+
+    let initial = system.encode_one("a", &0).and(&system.unit_vertex_set());
+    reach_fwd_ordered(&system, &initial, &["a".to_string()], None);
+}
+
+#[test]
+fn log_percent_edge_cases() {
+    let system_log_percent =
+        bio::update_fn::SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::log_percent;
+
+    let variables = BddVariableSet::new_anonymous(4);
+    let everything = variables.mk_true();
+    let single_state = variables.mk_conjunctive_clause(&BddPartialValuation::from(
+        BddValuation::all_true(variables.num_vars()),
+    ));
+    let empty = variables.mk_false();
+
+    for percent in [log_percent, system_log_percent] {
+        // the empty set (in any universe)
+        assert_eq!(percent(&empty, &everything), 0.0);
+        assert_eq!(percent(&empty, &single_state), 0.0);
+        assert_eq!(percent(&empty, &empty), 0.0);
+
+        // the single-state universe
+        assert_eq!(percent(&single_state, &single_state), 100.0);
+
+        // a universe without any variables
+        let no_variables = BddVariableSet::new_anonymous(0);
+        assert_eq!(
+            percent(&no_variables.mk_true(), &no_variables.mk_true()),
+            100.0
+        );
+
+        // the regular case: 2^2 of 2^4 valuations
+        let half = variables
+            .mk_var(variables.variables()[0])
+            .and(&variables.mk_var(variables.variables()[1]));
+        assert_eq!(percent(&half, &everything), 50.0);
+        assert_eq!(percent(&everything, &everything), 100.0);
+    }
+}
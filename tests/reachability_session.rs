@@ -0,0 +1,75 @@
+use biodivine_lib_logical_models::prelude::{
+    reachability::ReachabilitySession, symbolic_domain::SymbolicDomainOrd,
+};
+use num_bigint::BigInt;
+
+mod common;
+use common::{load, test_all_encodings};
+
+/// Explores the whole state space of every manual model in waves and checks that every
+/// finished wave is closed under the successors (or predecessors, when going backward).
+fn explore_in_waves<DO: SymbolicDomainOrd<u8>>(backward: bool) {
+    std::fs::read_dir("data/manual")
+        .expect("could not read dir")
+        .for_each(|dirent| {
+            let tmp = dirent.expect("could not read dir entry").path();
+            let filepath = tmp.to_str().unwrap();
+
+            let mut session = ReachabilitySession::new(load::<DO>(filepath));
+            assert!(session.current().is_false());
+            assert!(!session.is_done());
+
+            let mut remaining = session.remaining();
+            while !session.is_done() {
+                session.initialize();
+                let initial = session.current().clone();
+                assert_eq!(
+                    session.remaining(),
+                    &remaining - 1,
+                    "a wave starts from a single state"
+                );
+
+                while if backward {
+                    session.step_bwd()
+                } else {
+                    session.step_fwd()
+                } {
+                    assert!(!session.can_initialize());
+                }
+                assert!(session.can_initialize());
+                assert!(initial.imp(session.current()).is_true());
+
+                for variable in session.system().get_system_variables() {
+                    let next = if backward {
+                        session
+                            .system()
+                            .predecessors_under_variable(&variable, session.current())
+                    } else {
+                        session
+                            .system()
+                            .transition_under_variable(&variable, session.current())
+                    };
+                    assert!(
+                        next.imp(session.current()).is_true(),
+                        "wave in {} is not closed",
+                        filepath
+                    );
+                }
+
+                assert!(session.remaining() < remaining, "every wave makes progress");
+                remaining = session.remaining();
+            }
+
+            assert_eq!(session.remaining(), BigInt::from(0));
+        });
+}
+
+fn explore_forward_in_waves<DO: SymbolicDomainOrd<u8>>() {
+    explore_in_waves::<DO>(false);
+}
+
+fn explore_backward_in_waves<DO: SymbolicDomainOrd<u8>>() {
+    explore_in_waves::<DO>(true);
+}
+
+test_all_encodings!(explore_forward_in_waves, explore_backward_in_waves);
@@ -0,0 +1,89 @@
+use biodivine_lib_bdd::Bdd;
+use biodivine_lib_logical_models::prelude::symbolic_domain::SymbolicDomainOrd;
+
+mod common;
+use common::{load, test_all_encodings};
+
+/// Saturates `initial` using the given `step` function (successors or predecessors).
+fn saturate(variables: &[String], initial: &Bdd, step: impl Fn(&str, &Bdd) -> Bdd) -> Bdd {
+    let mut result = initial.clone();
+    'outer: loop {
+        for variable in variables {
+            let next = step(variable, &result);
+            if !next.imp(&result).is_true() {
+                result = result.or(&next);
+                continue 'outer;
+            }
+        }
+        return result;
+    }
+}
+
+fn reachability_does_not_escape_universe<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let variables = system.get_system_variables();
+    let universe = system.encode_one("b", &1).and(&system.unit_vertex_set());
+    let restricted = system.restrict_to(&universe);
+    assert_eq!(restricted.universe(), &universe);
+
+    let initial = system.encode_one("a", &0).and(&universe);
+
+    let unrestricted_fwd = saturate(&variables, &initial, |var, set| {
+        system.transition_under_variable(var, set)
+    });
+    assert!(
+        !unrestricted_fwd.imp(&universe).is_true(),
+        "the unrestricted reachability should escape"
+    );
+
+    let restricted_fwd = saturate(&variables, &initial, |var, set| {
+        restricted.successors_async(var, set)
+    });
+    assert!(restricted_fwd.imp(&universe).is_true());
+    // (a=0, b=1) -> (a=2, b=1); then only `b` can change, leaving the universe
+    assert_eq!(
+        restricted_fwd,
+        initial.or(&system.encode_one("a", &2).and(&universe))
+    );
+
+    let restricted_bwd = saturate(&variables, &initial, |var, set| {
+        restricted.predecessors_async(var, set)
+    });
+    assert!(restricted_bwd.imp(&universe).is_true());
+}
+
+test_all_encodings!(reachability_does_not_escape_universe);
+
+fn constrained_transitions_stay_safe<DO: SymbolicDomainOrd<u8> + Clone>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let safe = system.encode_one("b", &1).and(&system.unit_vertex_set());
+    let constrained = system.constrain_transitions(&safe);
+
+    let initial = system.encode_one("a", &0).and(&safe);
+    let reachable = constrained.reachable_from(&initial);
+    assert!(reachable.imp(&safe).is_true());
+    // the same as the reachability within the restricted view
+    assert_eq!(
+        reachable,
+        initial.or(&system.encode_one("a", &2).and(&safe))
+    );
+
+    for variable in system.get_system_variables() {
+        // no transition starts outside of the safe set
+        let unsafe_states = system.unit_vertex_set().and_not(&safe);
+        assert!(constrained
+            .transition_under_variable(&variable, &unsafe_states)
+            .is_false());
+
+        // the kept transitions are those of the original system
+        let successors = constrained.transition_under_variable(&variable, &safe);
+        assert_eq!(
+            successors,
+            system
+                .transition_under_variable(&variable, &safe)
+                .and(&safe)
+        );
+    }
+}
+
+test_all_encodings!(constrained_transitions_stay_safe);
@@ -0,0 +1,1727 @@
+use num_bigint::BigInt;
+
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    symbolic_domain::{BinaryIntegerDomain, SymbolicDomainOrd, UnaryIntegerDomain},
+    update_fn::{ImportError, MergeError, RenameError, StateCount, TooMany, WidenError},
+    update_fn_dsl::parse_update_fn,
+};
+
+mod common;
+use common::{from_dsl, load, test_all_encodings};
+
+fn sanitize_and_project<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let unit = system.unit_vertex_set();
+
+    let everything = system.get_bdd_variable_set().mk_true();
+    assert_eq!(system.sanitize(&everything), unit);
+
+    let a_is_one_b_is_zero = system
+        .encode_one("a", &1)
+        .and(&system.encode_one("b", &0))
+        .and(&unit);
+    let projected = system.project_to_variable(&a_is_one_b_is_zero, "a");
+    assert_eq!(projected, system.encode_one("a", &1).and(&unit));
+    assert!(projected.imp(&unit).is_true(), "projection is sanitized");
+}
+
+test_all_encodings!(sanitize_and_project);
+
+fn constant_variables<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/constant_variables.sbml");
+    assert_eq!(
+        system.constant_variables(),
+        vec![("c".to_string(), Some(1)), ("id".to_string(), None)]
+    );
+
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    assert_eq!(system.constant_variables(), vec![]);
+}
+
+test_all_encodings!(constant_variables);
+
+fn input_variables<DO: SymbolicDomainOrd<u8>>() {
+    // c = 1 (always), id keeps its value, x = 1 if c == 1
+    let system = load::<DO>("data/manual/constant_variables.sbml");
+    assert_eq!(system.input_variables(), vec!["id".to_string()]);
+
+    // no state can change its value of an input variable
+    let unit = system.unit_vertex_set();
+    for variable in ["id", "x"] {
+        let set = system.encode_one(variable, &0).and(&unit);
+        let successors = system.successors_async(variable, &set);
+        assert_eq!(successors == set, variable == "id");
+    }
+
+    // a variable with a single value is trivially an input
+    let system = load::<DO>("data/manual/zero_level_variable.sbml");
+    assert_eq!(system.input_variables(), vec!["z".to_string()]);
+
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    assert_eq!(system.input_variables(), Vec::<String>::new());
+}
+
+test_all_encodings!(input_variables);
+
+fn influence_graph<DO: SymbolicDomainOrd<u8>>() {
+    let edges = |edges: &[(&str, &str)]| {
+        edges
+            .iter()
+            .map(|(regulator, target)| (regulator.to_string(), target.to_string()))
+            .collect::<Vec<_>>()
+    };
+
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    assert_eq!(
+        system.influence_graph(),
+        edges(&[("a", "a"), ("b", "a"), ("a", "b")])
+    );
+
+    // `c` is an input of its own update function, but the function is constant
+    let system = load::<DO>("data/manual/constant_variables.sbml");
+    assert_eq!(system.influence_graph(), edges(&[("id", "id"), ("c", "x")]));
+}
+
+test_all_encodings!(influence_graph);
+
+fn signed_influence_graph<DO: SymbolicDomainOrd<u8>>() {
+    use bio::update_fn::Monotonicity::{self, *};
+
+    let edges = |edges: &[(&str, &str, Monotonicity)]| {
+        edges
+            .iter()
+            .map(|(regulator, target, sign)| (regulator.to_string(), target.to_string(), *sign))
+            .collect::<Vec<_>>()
+    };
+
+    let system = load::<DO>("data/manual/signed_influences.sbml");
+    assert_eq!(
+        system.signed_influence_graph(),
+        edges(&[
+            ("b", "a", Inhibition),
+            ("a", "b", Activation),
+            ("a", "x", Dual),
+            ("b", "x", Dual),
+        ])
+    );
+
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    assert_eq!(
+        system.signed_influence_graph(),
+        edges(&[
+            ("a", "a", Inhibition),
+            ("b", "a", Activation),
+            ("a", "b", Inhibition),
+        ])
+    );
+
+    // `x` is active only for the middle value of `c`
+    let system = load::<DO>("data/manual/constant_variables.sbml");
+    assert_eq!(
+        system.signed_influence_graph(),
+        edges(&[("id", "id", Activation), ("c", "x", Unknown)])
+    );
+}
+
+test_all_encodings!(signed_influence_graph);
+
+fn complement<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let unit = system.unit_vertex_set();
+    let a_is_one = system.encode_one("a", &1).and(&unit);
+
+    let complement = system.complement(&a_is_one);
+    assert!(complement.imp(&unit).is_true());
+    assert!(complement.and(&a_is_one).is_false());
+    assert_eq!(complement.or(&a_is_one), unit);
+    assert_eq!(system.complement(&complement), a_is_one);
+    assert_eq!(
+        complement,
+        system
+            .encode_one("a", &0)
+            .or(&system.encode_one("a", &2))
+            .and(&unit)
+    );
+}
+
+test_all_encodings!(complement);
+
+#[test]
+fn complement_excludes_invalid_binary_values() {
+    // `a` has max value 2, so its binary encoding contains an invalid value (3),
+    // which is included in the plain negation
+    let system = load::<BinaryIntegerDomain<u8>>("data/manual/three_level_variable.sbml");
+    let a_is_one = system.encode_one("a", &1).and(&system.unit_vertex_set());
+    let negation = a_is_one.not();
+    assert_ne!(negation, system.complement(&a_is_one));
+    assert_eq!(system.sanitize(&negation), system.complement(&a_is_one));
+}
+
+fn symmetric_difference_and_subset<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let unit = system.unit_vertex_set();
+    let a_is_one = system.encode_one("a", &1);
+    let a_is_two = system.encode_one("a", &2);
+    let a_is_at_least_one = a_is_one.or(&a_is_two);
+
+    let difference = system.symmetric_difference(&a_is_one, &a_is_at_least_one);
+    assert!(difference.imp(&unit).is_true());
+    assert_eq!(difference, a_is_two.and(&unit));
+    assert_eq!(
+        system.symmetric_difference(&a_is_one, &system.complement(&a_is_one)),
+        unit
+    );
+    assert!(system.symmetric_difference(&a_is_one, &a_is_one).is_false());
+
+    assert!(system.is_subset(&a_is_one, &a_is_at_least_one));
+    assert!(!system.is_subset(&a_is_at_least_one, &a_is_one));
+    assert!(system.is_subset(&unit, &unit));
+    // the invalid valuations outside of the unit set are not considered
+    assert!(system.is_subset(&unit.not(), &a_is_one));
+}
+
+test_all_encodings!(symmetric_difference_and_subset);
+
+fn estimate_symbolic_size<DO: SymbolicDomainOrd<u8>>() {
+    for filepath in [
+        "data/manual/three_level_variable.sbml",
+        "data/manual/zero_level_variable.sbml",
+        "data/manual/constant_variables.sbml",
+    ] {
+        let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+            std::fs::File::open(filepath).expect("should be able to open file"),
+        ));
+        bio::find_start_of(&mut xml, &["listOfTransitions"]).expect("should be able to find");
+        let update_fns =
+            bio::load_all_update_fns::<_, _, u8>(&mut xml).expect("should be able to parse");
+
+        let estimate =
+            bio::update_fn::SmartSystemUpdateFn::<DO, u8>::estimate_symbolic_size(&update_fns);
+        let system = bio::update_fn::SmartSystemUpdateFn::<DO, u8>::from_update_fns(update_fns);
+        assert_eq!(
+            estimate,
+            system.get_bdd_variable_set().num_vars() as usize,
+            "{}",
+            filepath
+        );
+    }
+}
+
+test_all_encodings!(estimate_symbolic_size);
+
+/// Checks `deadlock_states` against an explicit enumeration of the fixed points, i.e. the states
+/// that are their own only successor under every variable.
+fn deadlock_states<DO: SymbolicDomainOrd<u8>>() {
+    for filepath in [
+        "data/manual/three_level_variable.sbml",
+        "data/manual/handbook_example.sbml",
+        "data/manual/constant_variables.sbml",
+    ] {
+        let system = load::<DO>(filepath);
+        let deadlocks = system.deadlock_states();
+        assert!(deadlocks.imp(&system.unit_vertex_set()).is_true());
+
+        let mut fixed_points = system.get_bdd_variable_set().mk_false();
+        let mut remaining = system.unit_vertex_set();
+        while !remaining.is_false() {
+            let state = system.pick_state_bdd(&remaining);
+            remaining = remaining.and_not(&state);
+            if system
+                .get_system_variables()
+                .iter()
+                .all(|var| system.transition_under_variable(var, &state) == state)
+            {
+                fixed_points = fixed_points.or(&state);
+            }
+        }
+
+        assert_eq!(deadlocks, fixed_points, "{}", filepath);
+    }
+}
+
+test_all_encodings!(deadlock_states);
+
+fn enabled_variables<DO: SymbolicDomainOrd<u8>>() {
+    for filepath in [
+        "data/manual/three_level_variable.sbml",
+        "data/manual/handbook_example.sbml",
+    ] {
+        let system = load::<DO>(filepath);
+        let deadlocks = system.deadlock_states();
+        if !deadlocks.is_false() {
+            let fixed_point = system.pick_state_bdd(&deadlocks);
+            assert!(
+                system.enabled_variables(&fixed_point).is_empty(),
+                "{}",
+                filepath
+            );
+        }
+
+        let transient = system.unit_vertex_set().and_not(&deadlocks);
+        if !transient.is_false() {
+            let state = system.pick_state_bdd(&transient);
+            let enabled = system.enabled_variables(&state);
+            assert!(!enabled.is_empty(), "{}", filepath);
+            for var in system.get_system_variables() {
+                let fires = system.transition_under_variable(&var, &state) != state;
+                assert_eq!(enabled.contains(&var), fires, "{} {}", filepath, var);
+            }
+        }
+    }
+}
+
+test_all_encodings!(enabled_variables);
+
+fn enumerate_fixed_points<DO: SymbolicDomainOrd<u8>>() {
+    for filepath in [
+        "data/manual/three_level_variable.sbml",
+        "data/manual/handbook_example.sbml",
+        "data/manual/constant_variables.sbml",
+    ] {
+        let system = load::<DO>(filepath);
+        let deadlocks = system.deadlock_states();
+
+        let enumerated = system.enumerate_fixed_points(usize::MAX).expect("no limit");
+        let encoded = enumerated
+            .iter()
+            .fold(system.get_bdd_variable_set().mk_false(), |acc, state| {
+                acc.or(&system.encode_state(state).expect("valid state"))
+            });
+        assert_eq!(encoded, deadlocks, "{}", filepath);
+
+        if let Some(max) = enumerated.len().checked_sub(1) {
+            assert_eq!(
+                system.enumerate_fixed_points(max),
+                Err(TooMany {
+                    count: enumerated.len().into(),
+                    max
+                }),
+                "{}",
+                filepath
+            );
+        }
+    }
+}
+
+test_all_encodings!(enumerate_fixed_points);
+
+fn encode_state<DO: SymbolicDomainOrd<u8>>() {
+    use bio::symbolic_domain::ValueOutOfDomain;
+    use bio::update_fn::StateEncodingError;
+
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let assignment = |values: &[(&str, u8)]| {
+        values
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect::<std::collections::HashMap<_, _>>()
+    };
+
+    let state = system
+        .encode_state(&assignment(&[("a", 2), ("b", 1)]))
+        .expect("valid state");
+    assert_eq!(
+        state,
+        system
+            .encode_one("a", &2)
+            .and(&system.encode_one("b", &1))
+            .and(&system.unit_vertex_set())
+    );
+    assert_eq!(system.pick_state_bdd(&state), state, "a single state");
+
+    assert_eq!(
+        system.encode_state(&assignment(&[("a", 2)])),
+        Err(StateEncodingError::MissingVariable("b".to_string()))
+    );
+    assert_eq!(
+        system.encode_state(&assignment(&[("a", 2), ("b", 1), ("c", 0)])),
+        Err(StateEncodingError::UnknownVariable("c".to_string()))
+    );
+    assert_eq!(
+        system.encode_state(&assignment(&[("a", 3), ("b", 1)])),
+        Err(StateEncodingError::ValueOutOfDomain(ValueOutOfDomain {
+            variables: system
+                .get_domain("a")
+                .expect("known variable")
+                .raw_bdd_variables(),
+            value: 3,
+            max_value: 2,
+        }))
+    );
+}
+
+test_all_encodings!(encode_state);
+
+fn encode_subspace<DO: SymbolicDomainOrd<u8>>() {
+    use bio::update_fn::StateEncodingError;
+
+    let system = load::<DO>("data/manual/constant_variables.sbml");
+    let fixed = |values: &[(&str, u8)]| {
+        values
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect::<std::collections::HashMap<_, _>>()
+    };
+
+    let unit = system.unit_vertex_set();
+    assert_eq!(system.encode_subspace(&fixed(&[])), Ok(unit.clone()));
+
+    let subspace = system
+        .encode_subspace(&fixed(&[("c", 2), ("x", 0)]))
+        .expect("valid subspace");
+    assert_eq!(
+        subspace,
+        system
+            .encode_one("c", &2)
+            .and(&system.encode_one("x", &0))
+            .and(&unit)
+    );
+    // `id` is free
+    assert_eq!(system.project_to_variable(&subspace, "id"), unit);
+
+    let full = fixed(&[("c", 2), ("x", 0), ("id", 1)]);
+    assert_eq!(system.encode_subspace(&full), system.encode_state(&full));
+
+    assert_eq!(
+        system.encode_subspace(&fixed(&[("y", 0)])),
+        Err(StateEncodingError::UnknownVariable("y".to_string()))
+    );
+}
+
+test_all_encodings!(encode_subspace);
+
+fn pick_subspace<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let unit = system.unit_vertex_set();
+    let state = |a: u8, b: u8| {
+        system
+            .encode_one("a", &a)
+            .and(&system.encode_one("b", &b))
+            .and(&unit)
+    };
+
+    // the whole subspace is returned, not just a single state of it
+    let a_is_one = system.encode_one("a", &1).and(&unit);
+    assert_eq!(system.pick_subspace(&a_is_one), a_is_one);
+    assert_eq!(system.pick_subspace(&unit), unit);
+    let subspace = state(0, 0).or(&state(2, 0));
+    assert_eq!(system.pick_subspace(&subspace), subspace);
+
+    // a diagonal contains no subspace but its single states
+    let diagonal = state(0, 0).or(&state(1, 1));
+    let picked = system.pick_subspace(&diagonal);
+    assert!(picked == state(0, 0) || picked == state(1, 1));
+
+    // one of the two maximal subspaces avoiding a corner, depending on the initial state
+    let set = unit.and_not(&state(2, 1));
+    let picked = system.pick_subspace(&set);
+    let b_is_zero = system.encode_one("b", &0).and(&unit);
+    let a_is_not_two = unit.and_not(&system.encode_one("a", &2));
+    assert!(picked == b_is_zero || picked == a_is_not_two);
+}
+
+test_all_encodings!(pick_subspace);
+
+fn variable_comparison<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/variable_comparison.sbml");
+    let unit = system.unit_vertex_set();
+    let state = |a: u8, b: u8| {
+        system
+            .encode_one("a", &a)
+            .and(&system.encode_one("b", &b))
+            .and(&unit)
+    };
+
+    // b = 1 if a > b; 0 otherwise
+    assert_eq!(system.successors_async("b", &state(0, 0)), state(0, 0));
+    assert_eq!(system.successors_async("b", &state(1, 0)), state(1, 1));
+    assert_eq!(system.successors_async("b", &state(1, 1)), state(1, 0));
+    assert_eq!(system.successors_async("b", &state(2, 1)), state(2, 1));
+}
+
+test_all_encodings!(variable_comparison);
+
+fn apostrophe_in_name<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/apostrophe_in_name.sbml");
+    assert_eq!(system.get_system_variables(), vec!["a", "a'"]);
+
+    let unit = system.unit_vertex_set();
+    let state = |a: u8, a_prime: u8| {
+        system
+            .encode_one("a", &a)
+            .and(&system.encode_one("a'", &a_prime))
+            .and(&unit)
+    };
+
+    // a = 1 if a' == 0; a' = 1 if a == 1
+    assert_eq!(system.successors_async("a", &state(0, 0)), state(1, 0));
+    assert_eq!(system.successors_async("a'", &state(1, 0)), state(1, 1));
+    assert_eq!(system.successors_async("a", &state(1, 1)), state(0, 1));
+}
+
+test_all_encodings!(apostrophe_in_name);
+
+#[test]
+fn internal_names_round_trip() {
+    use bio::update_fn::{display_name, internal_name};
+
+    for name in ["a", "a'", "a''", "'a", "it's_a'_b"] {
+        assert_eq!(display_name(&internal_name(name)).as_deref(), Some(name));
+        let primed = format!("{}'", internal_name(name));
+        assert_eq!(display_name(&primed), None, "{} is primed", primed);
+    }
+}
+
+fn describe_variable<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+
+    for (name, domain) in system.standard_variables_names_and_domains() {
+        for (bit_index, bdd_variable) in domain.raw_bdd_variables().into_iter().enumerate() {
+            assert_eq!(
+                system.describe_variable(bdd_variable),
+                Some((name.to_string(), bit_index, false))
+            );
+        }
+    }
+
+    let primed = system.primed_variables();
+    for bdd_variable in system.get_bdd_variable_set().variables() {
+        let (name, _, is_primed) = system
+            .describe_variable(bdd_variable)
+            .expect("every variable of the system is described");
+        assert_eq!(is_primed, primed.contains(&bdd_variable));
+        assert!(system.get_system_variables().contains(&name));
+    }
+    // the primed domains mirror the layout of the un-primed ones
+    for (name, domain) in system.iter_domains() {
+        let (same_domain, primed_domain) = system.get_domains(name).expect("known variable");
+        assert_eq!(same_domain.raw_bdd_variables(), domain.raw_bdd_variables());
+        assert_eq!(
+            system
+                .get_primed_domain(name)
+                .map(|it| it.raw_bdd_variables()),
+            Some(primed_domain.raw_bdd_variables())
+        );
+
+        let primed_bits = primed_domain.raw_bdd_variables();
+        assert_eq!(primed_bits.len(), domain.raw_bdd_variables().len());
+        for (bit_index, bdd_variable) in primed_bits.into_iter().enumerate() {
+            assert_eq!(
+                system.describe_variable(bdd_variable),
+                Some((name.to_string(), bit_index, true))
+            );
+        }
+        for value in domain.get_all_possible_values() {
+            assert_eq!(
+                domain.raw_bdd_variables_encode(&value),
+                primed_domain.raw_bdd_variables_encode(&value)
+            );
+        }
+    }
+    assert!(system.get_domains("unknown").is_none());
+    assert!(system.get_primed_domain("unknown").is_none());
+}
+
+test_all_encodings!(describe_variable);
+
+/// For every pair of states `s`, `t` and every variable, `t` is a successor of `s`
+/// if and only if `s` is a predecessor of `t`.
+fn successors_and_predecessors_agree<DO: SymbolicDomainOrd<u8>>() {
+    for path in [
+        "data/manual/three_level_variable.sbml",
+        "data/manual/variable_comparison.sbml",
+    ] {
+        let system = load::<DO>(path);
+        let unit = system.unit_vertex_set();
+        let states = {
+            let mut states = Vec::new();
+            let mut remaining = unit.clone();
+            while !remaining.is_false() {
+                let state = system.pick_state_bdd(&remaining).and(&unit);
+                remaining = remaining.and_not(&state);
+                states.push(state);
+            }
+            states
+        };
+
+        for variable in system.get_system_variables() {
+            for source in &states {
+                let successors = system.successors_async(&variable, source);
+                for target in &states {
+                    let predecessors = system.predecessors_under_variable(&variable, target);
+                    assert_eq!(
+                        target.imp(&successors).is_true(),
+                        source.imp(&predecessors).is_true(),
+                        "{}: successors and predecessors under `{}` disagree",
+                        path,
+                        variable
+                    );
+                }
+            }
+        }
+    }
+}
+
+test_all_encodings!(successors_and_predecessors_agree);
+
+fn successor_edges<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let unit = system.unit_vertex_set();
+
+    // a = 2 if b == 1; 1 if a == 0; 0 otherwise
+    let edges = system.successor_edges("a", &unit);
+    let mut decoded = system.decode_edges(&edges);
+    decoded.sort_by_key(|(source, _)| (source["a"], source["b"]));
+    let expected_a = [
+        (0, 0, 1),
+        (0, 1, 2),
+        (1, 0, 0),
+        (1, 1, 2),
+        (2, 0, 0),
+        (2, 1, 2),
+    ];
+    assert_eq!(decoded.len(), expected_a.len());
+    for ((source, target), (a, b, next_a)) in decoded.iter().zip(expected_a) {
+        assert_eq!((source["a"], source["b"]), (a, b));
+        assert_eq!((target["a"], target["b"]), (next_a, b));
+    }
+
+    // the targets of the edges are exactly the successors
+    let source = system.encode_one("b", &1).and(&unit);
+    let targets = system
+        .decode_edges(&system.successor_edges("a", &source))
+        .into_iter()
+        .map(|(_, target)| target)
+        .collect::<Vec<_>>();
+    let successors = system.successors_async("a", &source);
+    let from_edges = targets
+        .iter()
+        .fold(system.get_bdd_variable_set().mk_false(), |acc, state| {
+            acc.or(&system
+                .encode_one("a", &state["a"])
+                .and(&system.encode_one("b", &state["b"]))
+                .and(&unit))
+        });
+    assert_eq!(from_edges, successors);
+}
+
+test_all_encodings!(successor_edges);
+
+/// Checks `count_transitions` against an explicit enumeration of the proper edges.
+fn count_transitions<DO: SymbolicDomainOrd<u8>>() {
+    for path in [
+        "data/manual/handbook_example.sbml",
+        "data/manual/variable_comparison.sbml",
+    ] {
+        let system = load::<DO>(path);
+        let unit = system.unit_vertex_set();
+        let sorted = |state: std::collections::HashMap<String, u8>| {
+            let mut state = state.into_iter().collect::<Vec<_>>();
+            state.sort();
+            state
+        };
+
+        let edges = system
+            .get_system_variables()
+            .iter()
+            .flat_map(|name| system.decode_edges(&system.successor_edges(name, &unit)))
+            .filter(|(source, target)| source != target)
+            .map(|(source, target)| (sorted(source), sorted(target)))
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(
+            system.count_transitions(&unit),
+            BigInt::from(edges.len()),
+            "{}",
+            path
+        );
+    }
+
+    // a = 2 if b == 1; 1 if a == 0; 0 otherwise, b = 1 if a != 2; 0 otherwise
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let unit = system.unit_vertex_set();
+    let state = |a: u8, b: u8| {
+        system
+            .encode_one("a", &a)
+            .and(&system.encode_one("b", &b))
+            .and(&unit)
+    };
+    // `(0, 0) -> (1, 0)` and `(0, 0) -> (0, 1)`
+    assert_eq!(system.count_transitions(&state(0, 0)), BigInt::from(2));
+    // `(2, 0) -> (0, 0)` only; `b` stays
+    assert_eq!(system.count_transitions(&state(2, 0)), BigInt::from(1));
+    assert_eq!(
+        system.count_transitions(&system.get_bdd_variable_set().mk_false()),
+        BigInt::from(0)
+    );
+}
+
+test_all_encodings!(count_transitions);
+
+fn zero_level_variable<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/zero_level_variable.sbml");
+    let unit = system.unit_vertex_set();
+
+    // `z` can only ever be zero, which holds in every state
+    let z_is_zero = system.encode_one("z", &0).and(&unit);
+    assert_eq!(z_is_zero, unit);
+    assert_eq!(
+        system
+            .standard_variables_names_and_domains()
+            .into_iter()
+            .find(|(name, _)| *name == "z")
+            .expect("z is a variable")
+            .1
+            .get_all_possible_values(),
+        vec![0]
+    );
+
+    // x = 1 if z == 0, i.e. always
+    let x_is_one = system.encode_one("x", &1).and(&unit);
+    assert_eq!(system.successors_async("z", &unit), unit);
+    assert_eq!(system.successors_async("x", &unit), x_is_one);
+    assert_eq!(system.predecessors_async("x", x_is_one.clone()), unit);
+    assert_eq!(system.deadlock_states(), x_is_one);
+
+    let edges = system.decode_edges(&system.successor_edges("z", &unit));
+    assert_eq!(edges.len(), 2);
+    for (source, target) in edges {
+        assert_eq!((source["z"], target["z"]), (0, 0));
+        assert_eq!(source["x"], target["x"]);
+    }
+}
+
+test_all_encodings!(zero_level_variable);
+
+fn async_any_is_union_over_variables<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let unit = system.unit_vertex_set();
+    let empty = system.get_bdd_variable_set().mk_false();
+
+    for (variable, value) in [("a", 0), ("a", 2), ("b", 1)] {
+        let set = system.encode_one(variable, &value).and(&unit);
+        let successors = system
+            .get_system_variables()
+            .iter()
+            .fold(empty.clone(), |acc, var| {
+                acc.or(&system.successors_async(var, &set))
+            });
+        let predecessors = system
+            .get_system_variables()
+            .iter()
+            .fold(empty.clone(), |acc, var| {
+                acc.or(&system.predecessors_async(var, set.clone()))
+            });
+        assert_eq!(system.successors_async_any(&set), successors);
+        assert_eq!(system.predecessors_async_any(&set), predecessors);
+    }
+    assert!(system.successors_async_any(&empty).is_false());
+}
+
+test_all_encodings!(async_any_is_union_over_variables);
+
+#[cfg(feature = "parallel")]
+fn par_async_any_matches_sequential<DO: SymbolicDomainOrd<u8> + Sync>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let unit = system.unit_vertex_set();
+
+    for (variable, value) in [("a", 0), ("a", 2), ("b", 1)] {
+        let set = system.encode_one(variable, &value).and(&unit);
+        assert_eq!(
+            system.par_successors_async_any(&set),
+            system.successors_async_any(&set)
+        );
+        assert_eq!(
+            system.par_predecessors_async_any(&set),
+            system.predecessors_async_any(&set)
+        );
+    }
+}
+
+#[cfg(feature = "parallel")]
+test_all_encodings!(par_async_any_matches_sequential);
+
+fn subspace_trap_and_closure<DO: SymbolicDomainOrd<u8>>() {
+    let subspace = |values: &[(&str, u8)]| {
+        values
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect::<std::collections::HashMap<_, _>>()
+    };
+
+    // c = 1 (always), id keeps its value, x = 1 if c == 1
+    let system = load::<DO>("data/manual/constant_variables.sbml");
+    assert!(system.subspace_is_trap(&subspace(&[])));
+    assert!(system.subspace_is_trap(&subspace(&[("c", 1)])));
+    assert!(system.subspace_is_trap(&subspace(&[("c", 1), ("x", 1)])));
+    assert!(system.subspace_is_trap(&subspace(&[("id", 0)])));
+    assert!(!system.subspace_is_trap(&subspace(&[("c", 0)])));
+    assert!(!system.subspace_is_trap(&subspace(&[("c", 1), ("x", 0)])));
+
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    for fixed in [
+        subspace(&[]),
+        subspace(&[("a", 0)]),
+        subspace(&[("a", 2), ("b", 0)]),
+        subspace(&[("b", 1)]),
+    ] {
+        let initial = system.encode_subspace(&fixed).expect("valid subspace");
+
+        // plain forward reachability, one variable at a time
+        let mut reachable = initial.clone();
+        'fwd: loop {
+            for variable in system.get_system_variables() {
+                let successors = system.successors_async(&variable, &reachable);
+                if !successors.imp(&reachable).is_true() {
+                    reachable = reachable.or(&successors);
+                    continue 'fwd;
+                }
+            }
+            break;
+        }
+
+        let closure = system.subspace_closure(&fixed);
+        assert_eq!(closure, reachable);
+        assert_eq!(system.subspace_is_trap(&fixed), closure == initial);
+    }
+}
+
+test_all_encodings!(subspace_trap_and_closure);
+
+fn can_reach_matches_forward_reachability<DO: SymbolicDomainOrd<u8>>() {
+    for path in [
+        "data/manual/three_level_variable.sbml",
+        "data/manual/constant_variables.sbml",
+    ] {
+        let system = load::<DO>(path);
+        let unit = system.unit_vertex_set();
+        let sets = system
+            .standard_variables_names_and_domains()
+            .into_iter()
+            .flat_map(|(name, domain)| {
+                domain
+                    .get_all_possible_values()
+                    .into_iter()
+                    .map(|value| system.encode_one(name, &value).and(&unit))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // the whole state space and a universe with a "hole" in it
+        let universes = [unit.clone(), system.complement(&sets[1])];
+        for universe in universes.iter() {
+            for from in sets.iter() {
+                let mut reachable = from.and(universe);
+                loop {
+                    let successors = system.successors_async_any(&reachable).and(universe);
+                    if successors.imp(&reachable).is_true() {
+                        break;
+                    }
+                    reachable = reachable.or(&successors);
+                }
+
+                for to in sets.iter() {
+                    assert_eq!(
+                        system.can_reach(from, to, universe),
+                        !reachable.and(to).is_false(),
+                        "{path}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+test_all_encodings!(can_reach_matches_forward_reachability);
+
+fn iter_domains_follows_bdd_layout<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/handbook_example.sbml");
+
+    let names = system
+        .iter_domains()
+        .map(|(name, _)| name.to_string())
+        .collect::<Vec<_>>();
+    let mut sorted = names.clone();
+    sorted.sort();
+    assert_eq!(names, sorted);
+    assert_eq!(names, system.get_system_variables());
+    assert_eq!(
+        system
+            .iter_domains()
+            .map(|(_, domain)| domain.raw_bdd_variables())
+            .collect::<Vec<_>>(),
+        system
+            .standard_domains()
+            .into_iter()
+            .map(|domain| domain.raw_bdd_variables())
+            .collect::<Vec<_>>()
+    );
+
+    // the encodings of the variables follow each other in the same order
+    let encodings = system
+        .iter_domains()
+        .map(|(_, domain)| domain.raw_bdd_variables())
+        .collect::<Vec<_>>();
+    for pair in encodings.windows(2) {
+        if let (Some(last), Some(first)) = (pair[0].last(), pair[1].first()) {
+            assert!(last < first);
+        }
+    }
+}
+
+test_all_encodings!(iter_domains_follows_bdd_layout);
+
+fn cached_unit_is_product_of_domains<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let variables = system.get_bdd_variable_set();
+
+    let product = system
+        .standard_domains()
+        .into_iter()
+        .fold(variables.mk_true(), |acc, domain| {
+            acc.and(&domain.unit_collection(variables))
+        });
+    assert_eq!(system.cached_unit(), &product);
+    assert_eq!(system.cached_unit(), &system.unit_vertex_set());
+
+    // the domains of the system know the context they are encoded in
+    for (_, domain) in system.iter_domains() {
+        assert_eq!(
+            domain.unit_collection_in_context(),
+            domain.unit_collection(variables)
+        );
+    }
+}
+
+test_all_encodings!(cached_unit_is_product_of_domains);
+
+fn reachable_from_is_closed<DO: SymbolicDomainOrd<u8>>() {
+    for filepath in [
+        "data/manual/three_level_variable.sbml",
+        "data/manual/handbook_example.sbml",
+    ] {
+        let system = load::<DO>(filepath);
+        let unit = system.unit_vertex_set();
+        for (name, domain) in system.iter_domains() {
+            for value in domain.get_all_possible_values() {
+                let initial = system.encode_one(name, &value).and(&unit);
+                let reachable = system.reachable_from(&initial);
+
+                assert!(initial.imp(&reachable).is_true(), "{}", filepath);
+                assert!(reachable.imp(&unit).is_true(), "{}", filepath);
+                assert!(
+                    system
+                        .successors_async_any(&reachable)
+                        .imp(&reachable)
+                        .is_true(),
+                    "{}",
+                    filepath
+                );
+            }
+        }
+    }
+}
+
+test_all_encodings!(reachable_from_is_closed);
+
+fn tighten_domains_preserves_reachability<DO: SymbolicDomainOrd<u8>>() {
+    type State = Vec<(String, u8)>;
+    let sorted = |state: std::collections::HashMap<String, u8>| {
+        let mut state = state.into_iter().collect::<State>();
+        state.sort();
+        state
+    };
+
+    for filepath in [
+        "data/manual/three_level_variable.sbml",
+        "data/manual/constant_variables.sbml",
+        "data/manual/zero_level_variable.sbml",
+    ] {
+        let system = load::<DO>(filepath);
+        let initial = system.pick_state_bdd(&system.unit_vertex_set());
+        let reachable = system.reachable_from(&initial);
+        let tightened = system.tighten_domains(&reachable);
+
+        // the value `i` of a tightened variable is its `i`-th smallest reachable value
+        let taken_values = system
+            .iter_domains()
+            .map(|(name, domain)| {
+                let values =
+                    domain.decode_collection_sorted(system.get_bdd_variable_set(), &reachable);
+                (name.to_string(), values)
+            })
+            .collect::<std::collections::HashMap<_, _>>();
+        for (name, domain) in tightened.iter_domains() {
+            assert_eq!(
+                domain.max_value() as usize,
+                taken_values[name].len() - 1,
+                "{}",
+                filepath
+            );
+        }
+        let remap = |state: State| {
+            state
+                .into_iter()
+                .map(|(name, value)| {
+                    let index = taken_values[&name].iter().position(|it| *it == value);
+                    (name, index.expect("a reachable value") as u8)
+                })
+                .collect::<State>()
+        };
+
+        let initial_state = system
+            .iter_domains()
+            .map(|(name, domain)| {
+                let value = domain.decode_one(system.get_bdd_variable_set(), &initial);
+                (name.to_string(), value)
+            })
+            .collect::<State>();
+        let tightened_initial = tightened
+            .encode_state(&remap(initial_state).into_iter().collect())
+            .expect("valid state");
+        let tightened_reachable = tightened.reachable_from(&tightened_initial);
+
+        for variable in system.get_system_variables() {
+            let mut edges = system
+                .decode_edges(&system.successor_edges(&variable, &reachable))
+                .into_iter()
+                .map(|(source, target)| (remap(sorted(source)), remap(sorted(target))))
+                .collect::<Vec<_>>();
+            let mut tightened_edges = tightened
+                .decode_edges(&tightened.successor_edges(&variable, &tightened_reachable))
+                .into_iter()
+                .map(|(source, target)| (sorted(source), sorted(target)))
+                .collect::<Vec<_>>();
+            edges.sort();
+            tightened_edges.sort();
+            assert_eq!(edges, tightened_edges, "{} ({})", filepath, variable);
+        }
+    }
+}
+
+test_all_encodings!(tighten_domains_preserves_reachability);
+
+fn states_csv_round_trip<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let unit = system.unit_vertex_set();
+    let set = system
+        .encode_one("a", &1)
+        .or(&system.encode_one("b", &0))
+        .and(&unit);
+
+    let mut csv = Vec::new();
+    system
+        .export_states_csv(&set, usize::MAX, &mut csv)
+        .expect("writing into a vector should not fail");
+    let text = String::from_utf8(csv.clone()).expect("should be valid utf-8");
+    let mut lines = text.lines();
+    assert_eq!(
+        lines.next(),
+        Some(system.get_system_variables().join(",").as_str())
+    );
+    let states = set.exact_cardinality() >> system.primed_variables().len();
+    assert_eq!(BigInt::from(lines.count()), states);
+
+    let imported = system
+        .import_states_csv(&mut csv.as_slice())
+        .expect("should be able to import");
+    assert_eq!(imported, set);
+
+    // the row cap limits the number of exported states
+    let mut capped = Vec::new();
+    system
+        .export_states_csv(&set, 1, &mut capped)
+        .expect("writing into a vector should not fail");
+    let imported = system
+        .import_states_csv(&mut capped.as_slice())
+        .expect("should be able to import");
+    assert_eq!(imported, system.pick_state_bdd(&imported).and(&unit));
+    assert!(imported.imp(&set).is_true());
+
+    let header = system.get_system_variables().join(",");
+    let too_few_values = format!("{header}\n1\n");
+    assert!(matches!(
+        system.import_states_csv(&mut too_few_values.as_bytes()),
+        Err(ImportError::WrongColumnCount { line: 2, .. })
+    ));
+    let not_a_number = format!("{header}\n{}\n", ["x"; 2].join(","));
+    assert!(matches!(
+        system.import_states_csv(&mut not_a_number.as_bytes()),
+        Err(ImportError::InvalidValue { line: 2, .. })
+    ));
+    assert!(matches!(
+        system.import_states_csv(&mut "".as_bytes()),
+        Err(ImportError::MissingHeader)
+    ));
+}
+
+test_all_encodings!(states_csv_round_trip);
+
+fn state_count_category<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let unit = system.unit_vertex_set();
+
+    assert_eq!(
+        system.state_count_category(&system.get_bdd_variable_set().mk_false()),
+        StateCount::Empty
+    );
+
+    // the picked state leaves the primed variables unconstrained, which does not matter
+    let state = system.pick_state_bdd(&unit);
+    assert_eq!(system.state_count_category(&state), StateCount::Single);
+
+    let everything = system.get_bdd_variable_set().mk_true();
+    let states = unit.exact_cardinality() >> system.primed_variables().len();
+    assert_eq!(
+        system.state_count_category(&everything),
+        StateCount::Many(states)
+    );
+    let two_states = state.or(&system.pick_state_bdd(&unit.and_not(&state)));
+    assert_eq!(
+        system.state_count_category(&two_states),
+        StateCount::Many(BigInt::from(2))
+    );
+}
+
+test_all_encodings!(state_count_category);
+
+fn union_and_intersection_of_many<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let unit = system.unit_vertex_set();
+    let a_is = |value: u8| system.encode_one("a", &value);
+    let b_is = |value: u8| system.encode_one("b", &value);
+
+    assert!(system.union_all([]).is_false());
+    assert_eq!(system.intersect_all([]), unit);
+
+    // unsanitized inputs produce sanitized results
+    let sets = [a_is(0), a_is(1), a_is(2), b_is(0), b_is(1)];
+    let union = system.union_all(&sets);
+    assert_eq!(union, unit);
+    assert_eq!(
+        system.union_all(&sets[..3]),
+        sets[..3]
+            .iter()
+            .fold(system.get_bdd_variable_set().mk_false(), |acc, set| acc
+                .or(set))
+            .and(&unit)
+    );
+    assert_eq!(system.union_all([&a_is(1)]), a_is(1).and(&unit));
+
+    assert_eq!(
+        system.intersect_all([&a_is(1), &b_is(0)]),
+        a_is(1).and(&b_is(0)).and(&unit)
+    );
+    assert!(system.intersect_all(&sets).is_false());
+    assert_eq!(system.intersect_all([&a_is(2)]), a_is(2).and(&unit));
+}
+
+test_all_encodings!(union_and_intersection_of_many);
+
+fn merge_variables_preserves_dynamics<DO: SymbolicDomainOrd<u8>>() {
+    type State = Vec<(String, u8)>;
+    let system = bio::update_fn::SmartSystemUpdateFn::<DO, u8>::from_update_fns(
+        [
+            ("a", "1: b == 0 | c == 2; default 0"),
+            ("b", "1: a == 1; default 0"),
+            ("c", "2: a == 1 & b == 1; 1: a == 1 | d == 1; default 0"),
+            ("d", "1: c >= 1; default 0"),
+        ]
+        .into_iter()
+        .map(|(name, src)| {
+            let update_fn = parse_update_fn(name, src).expect("should be able to parse");
+            (name.to_string(), update_fn)
+        })
+        .collect(),
+    );
+
+    for (x, y, merged_name, max_value) in [("a", "b", "ab", 3), ("c", "d", "d", 5)] {
+        let merged = system
+            .merge_variables(x, y, merged_name)
+            .expect("should be able to merge");
+        assert_eq!(
+            merged
+                .get_domain(merged_name)
+                .map(|domain| domain.max_value()),
+            Some(max_value)
+        );
+        let y_count = system
+            .get_domain(y)
+            .unwrap()
+            .get_all_possible_values()
+            .len() as u8;
+
+        // both variables are replaced by the merged one, the others are kept
+        let remap = |state: std::collections::HashMap<String, u8>| {
+            let merged_value = state[x] * y_count + state[y];
+            let mut state = state
+                .into_iter()
+                .filter(|(name, _)| name != x && name != y)
+                .chain(std::iter::once((merged_name.to_string(), merged_value)))
+                .collect::<State>();
+            state.sort();
+            state
+        };
+        let all_edges = |system: &bio::update_fn::SmartSystemUpdateFn<DO, u8>,
+                         variables: &[&str]| {
+            variables
+                .iter()
+                .flat_map(|variable| {
+                    system
+                        .decode_edges(&system.successor_edges(variable, &system.unit_vertex_set()))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut edges = all_edges(&system, &[x, y])
+            .into_iter()
+            .map(|(source, target)| (remap(source), remap(target)))
+            .collect::<Vec<_>>();
+        let mut merged_edges = all_edges(&merged, &[merged_name])
+            .into_iter()
+            .map(|(source, target)| (remap_identity(source), remap_identity(target)))
+            .collect::<Vec<_>>();
+        edges.sort();
+        edges.dedup();
+        merged_edges.sort();
+        assert_eq!(edges, merged_edges, "merged {} & {}", x, y);
+
+        for variable in system
+            .get_system_variables()
+            .into_iter()
+            .filter(|variable| variable != x && variable != y)
+        {
+            let mut edges = all_edges(&system, &[&variable])
+                .into_iter()
+                .map(|(source, target)| (remap(source), remap(target)))
+                .collect::<Vec<_>>();
+            let mut merged_edges = all_edges(&merged, &[&variable])
+                .into_iter()
+                .map(|(source, target)| (remap_identity(source), remap_identity(target)))
+                .collect::<Vec<_>>();
+            edges.sort();
+            merged_edges.sort();
+            assert_eq!(edges, merged_edges, "merged {} & {} ({})", x, y, variable);
+        }
+
+        // the merged value 0 stands for both of the variables being 0
+        let zeros = |system: &bio::update_fn::SmartSystemUpdateFn<DO, u8>| {
+            let state = system
+                .get_system_variables()
+                .into_iter()
+                .map(|variable| (variable, 0))
+                .collect();
+            system.encode_state(&state).expect("valid state")
+        };
+        let (initial, merged_initial) = (zeros(&system), zeros(&merged));
+        assert_eq!(
+            system.state_count_category(&system.reachable_from(&initial)),
+            merged.state_count_category(&merged.reachable_from(&merged_initial)),
+        );
+    }
+
+    assert_eq!(
+        system.merge_variables("a", "x", "ax").err(),
+        Some(MergeError::UnknownVariable("x".to_string()))
+    );
+    assert_eq!(
+        system.merge_variables("a", "a", "aa").err(),
+        Some(MergeError::SameVariable("a".to_string()))
+    );
+    assert_eq!(
+        system.merge_variables("a", "b", "c").err(),
+        Some(MergeError::NameTaken("c".to_string()))
+    );
+}
+
+fn remap_identity(state: std::collections::HashMap<String, u8>) -> Vec<(String, u8)> {
+    let mut state = state.into_iter().collect::<Vec<_>>();
+    state.sort();
+    state
+}
+
+test_all_encodings!(merge_variables_preserves_dynamics);
+
+/// 256 values still fit into `u8`, so they can be counted without overflowing.
+fn merge_and_tighten_up_to_256_values<DO: SymbolicDomainOrd<u8>>() {
+    // the unary and Petri net encodings of 256 values take 255 bits, which makes the system
+    // construction take minutes in a debug build; the counting does not depend on the encoding
+    if DO::required_bits(&255) > 8 {
+        return;
+    }
+
+    let system_of = |update_fns: &[(&str, &str)]| {
+        bio::update_fn::SmartSystemUpdateFn::<DO, u8>::from_update_fns(
+            update_fns
+                .iter()
+                .map(|(name, src)| {
+                    let update_fn = parse_update_fn(name, src).expect("should be able to parse");
+                    (name.to_string(), update_fn)
+                })
+                .collect(),
+        )
+    };
+
+    // 16 * 16 values
+    let system = system_of(&[
+        ("a", "15: b == 0; default 0"),
+        ("b", "15: a == 0; default 0"),
+    ]);
+    let merged = system
+        .merge_variables("a", "b", "ab")
+        .expect("should be able to merge");
+    assert_eq!(
+        merged.get_domain("ab").map(|domain| domain.max_value()),
+        Some(255)
+    );
+    assert_eq!(
+        merged.state_count_category(&merged.unit_vertex_set()),
+        StateCount::Many(BigInt::from(256))
+    );
+
+    // 17 * 16 values
+    let system = system_of(&[
+        ("a", "16: b == 0; default 0"),
+        ("b", "15: a == 0; default 0"),
+    ]);
+    assert_eq!(
+        system.merge_variables("a", "b", "ab").err(),
+        Some(MergeError::TooManyValues(272))
+    );
+
+    // all the 256 values are taken
+    let system = system_of(&[("a", "255: a == 0; default 0")]);
+    let tightened = system.tighten_domains(&system.unit_vertex_set());
+    assert_eq!(
+        tightened.get_domain("a").map(|domain| domain.max_value()),
+        Some(255)
+    );
+    assert_eq!(
+        tightened.state_count_category(&tightened.unit_vertex_set()),
+        StateCount::Many(BigInt::from(256))
+    );
+}
+
+test_all_encodings!(merge_and_tighten_up_to_256_values);
+
+fn widen_domain_maps_new_values_to_default<DO: SymbolicDomainOrd<u8>>() {
+    let system = bio::update_fn::SmartSystemUpdateFn::<DO, u8>::from_update_fns(
+        [
+            ("a", "1: b >= 1; default 0"),
+            ("b", "1: a == 1; default 0"),
+            ("c", "1: a == 1; default 0"),
+        ]
+        .into_iter()
+        .map(|(name, src)| {
+            let update_fn = parse_update_fn(name, src).expect("should be able to parse");
+            (name.to_string(), update_fn)
+        })
+        .collect(),
+    );
+    let widened = system
+        .widen_domain("b", 3)
+        .expect("should be able to widen");
+    assert_eq!(
+        widened.get_domain("b").map(|domain| domain.max_value()),
+        Some(3)
+    );
+    assert_eq!(
+        widened.state_count_category(&widened.unit_vertex_set()),
+        StateCount::Many(BigInt::from(16))
+    );
+
+    // the proper (non-loop) transitions of `a` & `b` as sorted `(a, b) -> (a, b)` pairs
+    let proper_edges = |system: &bio::update_fn::SmartSystemUpdateFn<DO, u8>| {
+        let mut edges = ["a", "b"]
+            .iter()
+            .flat_map(|variable| {
+                system.decode_edges(&system.successor_edges(variable, &system.unit_vertex_set()))
+            })
+            .filter(|(source, target)| source != target)
+            .map(|(source, target)| ((source["a"], source["b"]), (target["a"], target["b"])))
+            .collect::<Vec<_>>();
+        edges.sort();
+        edges.dedup();
+        edges
+    };
+    let widened_edges = proper_edges(&widened);
+
+    // the old values behave as before
+    assert_eq!(
+        widened_edges
+            .iter()
+            .filter(|((_, b), _)| *b <= 1)
+            .copied()
+            .collect::<Vec<_>>(),
+        proper_edges(&system)
+    );
+    // for the new values, `a` (which reads `b`) & `b` itself are updated to their defaults
+    assert_eq!(
+        widened_edges
+            .into_iter()
+            .filter(|((_, b), _)| *b > 1)
+            .collect::<Vec<_>>(),
+        vec![
+            ((0, 2), (0, 0)),
+            ((0, 3), (0, 0)),
+            ((1, 2), (0, 2)),
+            ((1, 2), (1, 0)),
+            ((1, 3), (0, 3)),
+            ((1, 3), (1, 0)),
+        ]
+    );
+    // whereas `c` (which does not read `b`) is updated as for the old values
+    let state = |a: u8, b: u8, c: u8| {
+        let assignment = [("a", a), ("b", b), ("c", c)]
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        widened.encode_state(&assignment).expect("valid state")
+    };
+    assert_eq!(
+        widened.successors_async("c", &state(1, 3, 0)),
+        state(1, 3, 1)
+    );
+    assert_eq!(
+        widened.successors_async("c", &state(0, 2, 1)),
+        state(0, 2, 0)
+    );
+
+    assert_eq!(
+        system.widen_domain("x", 3).err(),
+        Some(WidenError::UnknownVariable("x".to_string()))
+    );
+    assert_eq!(
+        widened.widen_domain("b", 2).err(),
+        Some(WidenError::Narrowing("b".to_string()))
+    );
+}
+
+test_all_encodings!(widen_domain_maps_new_values_to_default);
+
+fn empty_and_universe<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let unit = system.unit_vertex_set();
+    let everything = system.get_bdd_variable_set().mk_true();
+    let nothing = system.get_bdd_variable_set().mk_false();
+
+    assert!(system.is_universe(&unit));
+    assert!(system.is_universe(&everything));
+    assert!(!system.is_empty(&unit));
+    assert!(system.is_empty(&nothing));
+    assert!(!system.is_universe(&nothing));
+
+    // only the invalid encodings (if the encoding has any) are no states at all
+    let invalid = everything.and_not(&unit);
+    assert!(system.is_empty(&invalid));
+
+    let a_is_one = system.encode_one("a", &1).and(&unit);
+    assert!(!system.is_empty(&a_is_one));
+    assert!(!system.is_universe(&a_is_one));
+    assert!(system.is_universe(&a_is_one.or(&system.complement(&a_is_one))));
+}
+
+test_all_encodings!(empty_and_universe);
+
+fn rename_variable_keeps_sets<DO: SymbolicDomainOrd<u8> + Clone>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let renamed = system
+        .rename_variable("a", "z")
+        .expect("should be able to rename");
+
+    // the variables stay sorted by name
+    assert_eq!(renamed.get_system_variables(), vec!["b", "z"]);
+    assert!(renamed.get_domain("a").is_none());
+    assert_eq!(
+        renamed.get_domain("z").map(|domain| domain.max_value()),
+        system.get_domain("a").map(|domain| domain.max_value())
+    );
+
+    // the sets of the original system are valid in the renamed one
+    let a_is_one = system.encode_one("a", &1).and(&system.unit_vertex_set());
+    assert_eq!(
+        renamed.encode_one("z", &1).and(&renamed.unit_vertex_set()),
+        a_is_one
+    );
+    assert_eq!(
+        renamed.successors_async("z", &a_is_one),
+        system.successors_async("a", &a_is_one)
+    );
+    assert_eq!(
+        renamed.predecessors_async("b", a_is_one.clone()),
+        system.predecessors_async("b", a_is_one.clone())
+    );
+
+    // renaming back restores the original names
+    let restored = renamed
+        .rename_variable("z", "a")
+        .expect("should be able to rename");
+    assert_eq!(
+        restored.get_system_variables(),
+        system.get_system_variables()
+    );
+    assert!(system.rename_variable("a", "a").is_ok());
+
+    assert_eq!(
+        system.rename_variable("x", "y").err(),
+        Some(RenameError::UnknownVariable("x".to_string()))
+    );
+    assert_eq!(
+        system.rename_variable("a", "b").err(),
+        Some(RenameError::NameTaken("b".to_string()))
+    );
+
+    // ticks are escaped, like in the names of the loaded variables
+    let ticked = system
+        .rename_variable("a", "a'")
+        .expect("should be able to rename");
+    assert!(ticked.get_system_variables().contains(&"a'".to_string()));
+    assert_eq!(
+        ticked.successors_async("a'", &a_is_one),
+        system.successors_async("a", &a_is_one)
+    );
+}
+
+test_all_encodings!(rename_variable_keeps_sets);
+
+/// The systems derived from a renamed system behave like those derived from the original one.
+fn rename_variable_then_rebuild<DO: SymbolicDomainOrd<u8> + Clone>() {
+    type System<DO> = bio::update_fn::SmartSystemUpdateFn<DO, u8>;
+    let reachable_count = |system: &System<DO>, name: &str| {
+        let initial = system.encode_one(name, &1).and(&system.unit_vertex_set());
+        match system.state_count_category(&system.reachable_from(&initial)) {
+            StateCount::Empty => BigInt::from(0),
+            StateCount::Single => BigInt::from(1),
+            StateCount::Many(count) => count,
+        }
+    };
+
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let renamed = system
+        .rename_variable("a", "z")
+        .expect("should be able to rename");
+
+    let tightened = renamed.tighten_domains(&renamed.unit_vertex_set());
+    let reference = system.tighten_domains(&system.unit_vertex_set());
+    assert_eq!(tightened.get_system_variables(), vec!["b", "z"]);
+    assert_eq!(
+        reachable_count(&tightened, "z"),
+        reachable_count(&reference, "a")
+    );
+
+    // `c` oscillates independently of the rest
+    let other = from_dsl::<DO>(&[("c", "1: c == 0; default 0")]);
+    let composed = renamed.compose(&other).expect("no variable is shared");
+    assert_eq!(composed.get_system_variables(), vec!["b", "c", "z"]);
+    assert_eq!(
+        reachable_count(&composed, "z"),
+        reachable_count(&renamed, "z") * 2
+    );
+    assert!(renamed.compose(&renamed).is_ok());
+
+    let merged = renamed
+        .merge_variables("z", "b", "m")
+        .expect("should be able to merge");
+    let reference = system
+        .merge_variables("a", "b", "m")
+        .expect("should be able to merge");
+    assert_eq!(merged.get_system_variables(), vec!["m"]);
+    assert_eq!(
+        reachable_count(&merged, "m"),
+        reachable_count(&reference, "m")
+    );
+}
+
+test_all_encodings!(rename_variable_then_rebuild);
+
+fn variables_in_bdd_order<DO: SymbolicDomainOrd<u8> + Clone>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    assert_eq!(system.variables_in_bdd_order(), vec!["a", "b"]);
+
+    // `z` is now the last name, but its bits still come first
+    let renamed = system
+        .rename_variable("a", "z")
+        .expect("should be able to rename");
+    assert_eq!(renamed.variables_in_bdd_order(), vec!["z", "b"]);
+
+    let first_bit = |name: &str| {
+        renamed
+            .get_domain(name)
+            .and_then(|domain| domain.raw_bdd_variables().into_iter().min())
+    };
+    let order = renamed.variables_in_bdd_order();
+    assert!(order
+        .windows(2)
+        .all(|pair| first_bit(&pair[0]) < first_bit(&pair[1])));
+
+    // the order does not change the results of the reachability
+    let a_is_one = system.encode_one("a", &1).and(&system.unit_vertex_set());
+    assert_eq!(
+        renamed.reachable_from(&a_is_one),
+        system.reachable_from(&a_is_one)
+    );
+}
+
+test_all_encodings!(variables_in_bdd_order);
+
+fn priorities_keep_reachability<DO: SymbolicDomainOrd<u8>>() {
+    let update_fns = || {
+        let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+            std::fs::File::open("data/manual/three_level_variable.sbml")
+                .expect("should be able to open file"),
+        ));
+        bio::find_start_of(&mut xml, &["listOfTransitions"]).expect("should be able to find");
+        bio::load_all_update_fns(&mut xml).expect("should be able to load")
+    };
+    let system = bio::update_fn::SmartSystemUpdateFn::<DO, u8>::from_update_fns(update_fns());
+    let prioritized =
+        bio::update_fn::SmartSystemUpdateFn::<DO, u8>::from_update_fns_with_priorities(
+            update_fns(),
+            &std::collections::HashMap::from([("b".to_string(), 1)]),
+        );
+
+    // the bits of `b` now come first, but the variables are still sorted by name
+    assert_eq!(system.variables_in_bdd_order(), vec!["a", "b"]);
+    assert_eq!(prioritized.variables_in_bdd_order(), vec!["b", "a"]);
+    assert_eq!(prioritized.get_system_variables(), vec!["a", "b"]);
+
+    let state = |system: &bio::update_fn::SmartSystemUpdateFn<DO, u8>, a: u8, b: u8| {
+        system
+            .encode_one("a", &a)
+            .and(&system.encode_one("b", &b))
+            .and(&system.unit_vertex_set())
+    };
+    let states = (0..=2).flat_map(|a| (0..=1).map(move |b| (a, b)));
+    for (a, b) in states.clone() {
+        let reachable = system.reachable_from(&state(&system, a, b));
+        let prioritized_reachable = prioritized.reachable_from(&state(&prioritized, a, b));
+        for (target_a, target_b) in states.clone() {
+            assert_eq!(
+                reachable
+                    .and(&state(&system, target_a, target_b))
+                    .is_false(),
+                prioritized_reachable
+                    .and(&state(&prioritized, target_a, target_b))
+                    .is_false(),
+                "({}, {}) -> ({}, {})",
+                a,
+                b,
+                target_a,
+                target_b
+            );
+        }
+    }
+}
+
+test_all_encodings!(priorities_keep_reachability);
+
+#[test]
+#[should_panic(expected = "cannot prioritize `c`, which is not a variable")]
+fn unknown_priority_is_rejected() {
+    let mut xml = xml::reader::EventReader::new(std::io::BufReader::new(
+        std::fs::File::open("data/manual/three_level_variable.sbml")
+            .expect("should be able to open file"),
+    ));
+    bio::find_start_of(&mut xml, &["listOfTransitions"]).expect("should be able to find");
+    let update_fns = bio::load_all_update_fns(&mut xml).expect("should be able to load");
+    bio::update_fn::SmartSystemUpdateFn::<UnaryIntegerDomain, u8>::from_update_fns_with_priorities(
+        update_fns,
+        &std::collections::HashMap::from([("c".to_string(), 1)]),
+    );
+}
+
+fn try_successors_and_predecessors<DO: SymbolicDomainOrd<u8>>() {
+    use bio::update_fn::UnknownVariable;
+
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let a_is_one = system.encode_one("a", &1).and(&system.unit_vertex_set());
+
+    assert_eq!(
+        system.try_successors_async("a", &a_is_one),
+        Ok(system.successors_async("a", &a_is_one))
+    );
+    assert_eq!(
+        system.try_predecessors_async("b", a_is_one.clone()),
+        Ok(system.predecessors_async("b", a_is_one.clone()))
+    );
+
+    let unknown = UnknownVariable {
+        name: "c".to_string(),
+        available: vec!["a".to_string(), "b".to_string()],
+    };
+    assert_eq!(
+        system.try_successors_async("c", &a_is_one),
+        Err(unknown.clone())
+    );
+    assert_eq!(
+        system.try_predecessors_async("c", a_is_one.clone()),
+        Err(unknown.clone())
+    );
+    assert_eq!(
+        unknown.to_string(),
+        "no update function for variable c; only [a, b] are available"
+    );
+}
+
+test_all_encodings!(try_successors_and_predecessors);
+
+#[test]
+#[should_panic(expected = "no update function for variable c; only [a, b] are available")]
+fn unknown_transition_variable_panics() {
+    let system = load::<UnaryIntegerDomain>("data/manual/three_level_variable.sbml");
+    system.successors_async("c", &system.unit_vertex_set());
+}
+
+fn invalid_encoding_count<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>("data/manual/three_level_variable.sbml");
+    let unit = system.unit_vertex_set();
+    let everything = system.get_bdd_variable_set().mk_true();
+
+    assert_eq!(system.invalid_encoding_count(&unit), BigInt::from(0));
+    assert_eq!(
+        system.invalid_encoding_count(&system.sanitize(&everything)),
+        BigInt::from(0)
+    );
+    // `a` has three values, `b` two
+    assert_eq!(
+        system.invalid_encoding_count(&everything),
+        (BigInt::from(1) << system.standard_variables().len()) - 6
+    );
+
+    let a_is_one = system.encode_one("a", &1).and(&unit);
+    assert_eq!(
+        system.invalid_encoding_count(&a_is_one.not()),
+        system.invalid_encoding_count(&everything)
+    );
+    assert_eq!(
+        system.invalid_encoding_count(&system.complement(&a_is_one)),
+        BigInt::from(0)
+    );
+}
+
+test_all_encodings!(invalid_encoding_count);
+
+#[test]
+fn invalid_encoding_count_of_binary_values() {
+    // only the value 3 of `a` is invalid (with either value of `b`)
+    let system = load::<BinaryIntegerDomain<u8>>("data/manual/three_level_variable.sbml");
+    let everything = system.get_bdd_variable_set().mk_true();
+    assert_eq!(system.invalid_encoding_count(&everything), BigInt::from(2));
+}
+
+#[test]
+#[should_panic]
+fn predecessors_of_set_depending_on_primed_bits_are_rejected() {
+    let system = load::<BinaryIntegerDomain<u8>>("data/manual/three_level_variable.sbml");
+    // a set that (unlike any set of states) depends on a primed bit of `a`; renaming the
+    // un-primed bits of `a` onto the primed ones would conflate the two
+    let primed_bit = system
+        .primed_variables()
+        .into_iter()
+        .find(|var| matches!(system.describe_variable(*var), Some((name, _, true)) if name == "a"))
+        .expect("`a` has some primed bit");
+    let set = system
+        .unit_vertex_set()
+        .and(&system.get_bdd_variable_set().mk_var(primed_bit));
+    system.predecessors_async("a", set);
+}
@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+
+use biodivine_lib_bdd::{BddVariableSet, BddVariableSetBuilder};
+use biodivine_lib_logical_models::prelude::{
+    self as bio,
+    expression::Expression,
+    proposition::{ComparisonOperator, Proposition},
+    symbolic_domain::SymbolicDomainOrd,
+    unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+    update_fn::{BuildError, ComposeError},
+};
+
+mod common;
+use common::test_all_encodings;
+
+type UpdateFns = HashMap<String, UnprocessedVariableUpdateFn<u8>>;
+
+/// `target = 1 if regulator == regulator_value; 0 otherwise`
+fn update_fn(
+    target: &str,
+    regulator: &str,
+    regulator_value: u8,
+) -> UnprocessedVariableUpdateFn<u8> {
+    UnprocessedVariableUpdateFn::new(
+        vec![regulator.to_string()],
+        target.to_string(),
+        vec![(
+            1,
+            Expression::Terminal(Proposition::new(
+                ComparisonOperator::Eq,
+                regulator.to_string(),
+                regulator_value,
+            )),
+        )],
+        0,
+    )
+}
+
+/// Boolean domains of `a` & `b` (and their primed copies), allocated in the same order
+/// as `SmartSystemUpdateFn::from_update_fns` does.
+fn shared_domains<DO: SymbolicDomainOrd<u8>>(
+) -> (HashMap<String, DO>, HashMap<String, DO>, BddVariableSet) {
+    let mut builder = BddVariableSetBuilder::new();
+    let mut domains = HashMap::new();
+    let mut primed_domains = HashMap::new();
+    for name in ["a", "b"] {
+        domains.insert(name.to_string(), DO::new(&mut builder, name, &1));
+        primed_domains.insert(
+            name.to_string(),
+            DO::new(&mut builder, &format!("{}'", name), &1),
+        );
+    }
+    (domains, primed_domains, builder.build())
+}
+
+fn oscillator() -> UpdateFns {
+    HashMap::from([
+        ("a".to_string(), update_fn("a", "b", 0)),
+        ("b".to_string(), update_fn("b", "a", 1)),
+    ])
+}
+
+fn switch() -> UpdateFns {
+    HashMap::from([
+        ("a".to_string(), update_fn("a", "b", 0)),
+        ("b".to_string(), update_fn("b", "a", 0)),
+    ])
+}
+
+fn systems_share_variable_set<DO: SymbolicDomainOrd<u8> + Clone>() {
+    let (domains, primed_domains, variable_set) = shared_domains::<DO>();
+
+    let oscillator_system = bio::update_fn::SmartSystemUpdateFn::from_update_fns_with_context(
+        oscillator(),
+        domains.clone(),
+        primed_domains.clone(),
+        variable_set.clone(),
+    )
+    .expect("domains cover all the variables");
+    let switch_system = bio::update_fn::SmartSystemUpdateFn::from_update_fns_with_context(
+        switch(),
+        domains,
+        primed_domains,
+        variable_set,
+    )
+    .expect("domains cover all the variables");
+
+    // same as allocating the domains internally
+    let reference = bio::update_fn::SmartSystemUpdateFn::<DO, u8>::from_update_fns(oscillator());
+    let unit = reference.unit_vertex_set();
+    assert_eq!(oscillator_system.unit_vertex_set(), unit);
+    for variable in reference.get_system_variables() {
+        for value in [0, 1] {
+            let set = reference.encode_one(&variable, &value).and(&unit);
+            assert_eq!(
+                oscillator_system.successors_async(&variable, &set),
+                reference.successors_async(&variable, &set)
+            );
+        }
+    }
+
+    // the BDDs of the two systems can be combined directly
+    let state = switch_system
+        .encode_one("a", &0)
+        .and(&switch_system.encode_one("b", &0))
+        .and(&unit);
+    assert_eq!(
+        oscillator_system.successors_async("a", &state),
+        switch_system.successors_async("a", &state)
+    );
+    assert_ne!(
+        oscillator_system.successors_async("b", &state),
+        switch_system.successors_async("b", &state)
+    );
+}
+
+fn missing_domains_are_reported<DO: SymbolicDomainOrd<u8>>() {
+    let (mut domains, primed_domains, variable_set) = shared_domains::<DO>();
+    domains.remove("b");
+    let result = bio::update_fn::SmartSystemUpdateFn::from_update_fns_with_context(
+        oscillator(),
+        domains,
+        primed_domains,
+        variable_set,
+    );
+    assert!(matches!(result, Err(BuildError::MissingDomain(name)) if name == "b"));
+
+    let (domains, mut primed_domains, variable_set) = shared_domains::<DO>();
+    primed_domains.remove("a");
+    let result = bio::update_fn::SmartSystemUpdateFn::from_update_fns_with_context(
+        oscillator(),
+        domains,
+        primed_domains,
+        variable_set,
+    );
+    assert!(matches!(result, Err(BuildError::MissingPrimedDomain(name)) if name == "a"));
+
+    let (domains, primed_domains, variable_set) = shared_domains::<DO>();
+    let result = bio::update_fn::SmartSystemUpdateFn::from_update_fns_with_context(
+        HashMap::from([("a".to_string(), update_fn("a", "c", 0))]),
+        domains,
+        primed_domains,
+        variable_set,
+    );
+    assert!(matches!(result, Err(BuildError::MissingUpdateFunction(name)) if name == "c"));
+}
+
+fn rebuild_in_shared_builder<DO: SymbolicDomainOrd<u8>>() {
+    let oscillator_system =
+        bio::update_fn::SmartSystemUpdateFn::<DO, u8>::from_update_fns(oscillator());
+    let other_system =
+        bio::update_fn::SmartSystemUpdateFn::<DO, u8>::from_update_fns(HashMap::from([
+            ("c".to_string(), update_fn("c", "d", 1)),
+            ("d".to_string(), update_fn("d", "c", 1)),
+        ]));
+
+    // an unrelated variable shifts all the rebuilt variables
+    let mut builder = BddVariableSetBuilder::new();
+    builder.make_variable("z");
+    let rebuilt_oscillator = oscillator_system.rebuild_in(&mut builder);
+    let rebuilt_other = other_system.rebuild_in(&mut builder);
+
+    // the rebuilt system behaves exactly like the original one
+    let unit = oscillator_system.unit_vertex_set();
+    let rebuilt_unit = rebuilt_oscillator.unit_vertex_set();
+    assert_eq!(
+        oscillator_system.bdd_to_dot_string(&unit),
+        rebuilt_oscillator.bdd_to_dot_string(&rebuilt_unit)
+    );
+    for variable in oscillator_system.get_system_variables() {
+        for value in [0, 1] {
+            let set = oscillator_system.encode_one(&variable, &value).and(&unit);
+            let rebuilt_set = rebuilt_oscillator
+                .encode_one(&variable, &value)
+                .and(&rebuilt_unit);
+            assert_eq!(
+                oscillator_system
+                    .bdd_to_dot_string(&oscillator_system.successors_async(&variable, &set)),
+                rebuilt_oscillator.bdd_to_dot_string(
+                    &rebuilt_oscillator.successors_async(&variable, &rebuilt_set)
+                )
+            );
+        }
+    }
+
+    // the sets of the first system can be moved into the context of the second one
+    let context = rebuilt_other.get_bdd_variable_set();
+    let oscillator_state = rebuilt_oscillator
+        .encode_one("a", &1)
+        .and(&rebuilt_oscillator.encode_one("b", &0))
+        .and(&rebuilt_unit);
+    let transferred = context
+        .transfer_from(&oscillator_state, rebuilt_oscillator.get_bdd_variable_set())
+        .expect("variables of the first system are in the shared context");
+    let product_state = transferred
+        .and(&rebuilt_other.encode_one("c", &0))
+        .and(&rebuilt_other.unit_vertex_set());
+    assert!(!product_state.is_false());
+    // `c` (and `d`) do not change the oscillator variables
+    assert!(rebuilt_other
+        .successors_async("c", &product_state)
+        .imp(&transferred)
+        .is_true());
+}
+
+fn compose_systems_sharing_a_variable<DO: SymbolicDomainOrd<u8>>() {
+    let system = bio::update_fn::SmartSystemUpdateFn::<DO, u8>::from_update_fns;
+    // `s` keeps its value in both of the systems
+    let first = system(HashMap::from([
+        ("a".to_string(), update_fn("a", "s", 1)),
+        ("s".to_string(), update_fn("s", "s", 1)),
+    ]));
+    let second = system(HashMap::from([
+        ("b".to_string(), update_fn("b", "s", 0)),
+        ("s".to_string(), update_fn("s", "s", 1)),
+    ]));
+
+    let composed = first
+        .compose(&second)
+        .expect("the definitions of `s` agree");
+    assert_eq!(composed.get_system_variables(), vec!["a", "b", "s"]);
+
+    let unit = composed.unit_vertex_set();
+    let state = |a: u8, b: u8, s: u8| {
+        composed
+            .encode_one("a", &a)
+            .and(&composed.encode_one("b", &b))
+            .and(&composed.encode_one("s", &s))
+            .and(&unit)
+    };
+    // a = 1 if s == 1 (from the first system), b = 1 if s == 0 (from the second system)
+    assert_eq!(
+        composed.successors_async("a", &state(0, 0, 1)),
+        state(1, 0, 1)
+    );
+    assert_eq!(
+        composed.successors_async("b", &state(0, 0, 1)),
+        state(0, 0, 1)
+    );
+    assert_eq!(
+        composed.successors_async("b", &state(1, 0, 0)),
+        state(1, 1, 0)
+    );
+    assert_eq!(
+        composed.successors_async("s", &state(1, 1, 0)),
+        state(1, 1, 0)
+    );
+    assert_eq!(
+        composed.predecessors_async("a", state(1, 0, 1)),
+        state(0, 0, 1).or(&state(1, 0, 1))
+    );
+    // composing with a system already contained in the composition changes nothing
+    let recomposed = composed.compose(&first).expect("the definitions agree");
+    assert_eq!(recomposed.get_system_variables(), vec!["a", "b", "s"]);
+
+    // `s` negates itself
+    let conflicting_update = system(HashMap::from([("s".to_string(), update_fn("s", "s", 0))]));
+    assert_eq!(
+        first.compose(&conflicting_update).err(),
+        Some(ComposeError::ConflictingDefinition("s".to_string()))
+    );
+
+    // `s` has three levels
+    let conflicting_domain = system(HashMap::from([(
+        "s".to_string(),
+        UnprocessedVariableUpdateFn::new(vec![], "s".to_string(), vec![], 2),
+    )]));
+    assert_eq!(
+        first.compose(&conflicting_domain).err(),
+        Some(ComposeError::ConflictingDefinition("s".to_string()))
+    );
+}
+
+/// For every state of `a` & `b` (with the `fixed` values of the other variables) and each of
+/// `a` & `b`, the states of `a` & `b` reachable in a single step.
+fn oscillator_successors<DO: SymbolicDomainOrd<u8>>(
+    system: &bio::update_fn::SmartSystemUpdateFn<DO, u8>,
+    fixed: &[(&str, u8)],
+) -> Vec<Vec<(u8, u8)>> {
+    let state = |a: u8, b: u8| {
+        fixed.iter().fold(
+            system
+                .encode_one("a", &a)
+                .and(&system.encode_one("b", &b))
+                .and(&system.unit_vertex_set()),
+            |acc, (name, value)| acc.and(&system.encode_one(name, value)),
+        )
+    };
+    let states = [(0, 0), (0, 1), (1, 0), (1, 1)];
+    states
+        .iter()
+        .flat_map(|(a, b)| ["a", "b"].map(|variable| (variable, state(*a, *b))))
+        .map(|(variable, source)| {
+            let successors = system.successors_async(variable, &source);
+            states
+                .iter()
+                .copied()
+                .filter(|(a, b)| !successors.and(&state(*a, *b)).is_false())
+                .collect()
+        })
+        .collect()
+}
+
+fn prioritized_systems_are_rebuilt_and_composed<DO: SymbolicDomainOrd<u8>>() {
+    let system = bio::update_fn::SmartSystemUpdateFn::<DO, u8>::from_update_fns(oscillator());
+    let prioritized =
+        bio::update_fn::SmartSystemUpdateFn::<DO, u8>::from_update_fns_with_priorities(
+            oscillator(),
+            &HashMap::from([("b".to_string(), 5)]),
+        );
+    assert_eq!(prioritized.variables_in_bdd_order(), vec!["b", "a"]);
+    let expected = oscillator_successors(&system, &[]);
+    assert_eq!(oscillator_successors(&prioritized, &[]), expected);
+
+    let mut builder = BddVariableSetBuilder::new();
+    builder.make_variable("z");
+    let rebuilt = prioritized.rebuild_in(&mut builder);
+    assert_eq!(rebuilt.variables_in_bdd_order(), vec!["b", "a"]);
+    assert_eq!(oscillator_successors(&rebuilt, &[]), expected);
+
+    // the two systems order the shared variables differently
+    for composed in [
+        prioritized.compose(&system),
+        system.compose(&prioritized),
+        prioritized.compose(&prioritized),
+    ] {
+        let composed = composed.expect("the definitions agree");
+        assert_eq!(oscillator_successors(&composed, &[]), expected);
+    }
+
+    let other = bio::update_fn::SmartSystemUpdateFn::<DO, u8>::from_update_fns(HashMap::from([(
+        "c".to_string(),
+        update_fn("c", "c", 1),
+    )]));
+    let composed = prioritized.compose(&other).expect("no variable is shared");
+    assert_eq!(composed.get_system_variables(), vec!["a", "b", "c"]);
+    for c in [0, 1] {
+        assert_eq!(oscillator_successors(&composed, &[("c", c)]), expected);
+    }
+}
+
+test_all_encodings!(
+    systems_share_variable_set,
+    missing_domains_are_reported,
+    rebuild_in_shared_builder,
+    compose_systems_sharing_a_variable,
+    prioritized_systems_are_rebuilt_and_composed,
+);
@@ -0,0 +1,65 @@
+use biodivine_lib_logical_models::benchmarks::{
+    reachability,
+    rewritten_reachability::reachability_benchmark_with_picker,
+    state_picker::{RandomStatePicker, SatWitnessPicker, StatePicker},
+};
+use biodivine_lib_logical_models::prelude::{
+    symbolic_domain::{BinaryIntegerDomain, SymbolicDomainOrd, UnaryIntegerDomain},
+    update_fn::StateCount,
+};
+
+mod common;
+use common::{load, test_all_encodings};
+
+const MODEL: &str = "data/manual/three_level_variable.sbml";
+
+fn pickers_pick_a_single_state<DO: SymbolicDomainOrd<u8>>() {
+    let system = load::<DO>(MODEL);
+    let unit = system.unit_vertex_set();
+    let a_is_not_zero = system.complement(&system.encode_one("a", &0));
+
+    assert_eq!(
+        SatWitnessPicker.pick(&system, &a_is_not_zero),
+        system.pick_state_bdd(&a_is_not_zero)
+    );
+
+    let picker = RandomStatePicker::new(42);
+    let mut picked = Vec::new();
+    for set in [&unit, &a_is_not_zero].into_iter().cycle().take(20) {
+        let state = picker.pick(&system, set);
+        assert_eq!(system.state_count_category(&state), StateCount::Single);
+        assert!(system.is_subset(&state, set));
+        picked.push(state);
+    }
+
+    // the picks are reproducible ...
+    let again = RandomStatePicker::new(42);
+    for (set, state) in [&unit, &a_is_not_zero].into_iter().cycle().zip(&picked) {
+        assert_eq!(&again.pick(&system, set), state);
+    }
+    // ... but not all the same
+    assert!(picked.iter().any(|state| state != &picked[0]));
+}
+
+test_all_encodings!(pickers_pick_a_single_state);
+
+#[test]
+fn scc_search_terminates_with_random_seeds() {
+    reachability_benchmark_with_picker::<UnaryIntegerDomain, _>(MODEL, &RandomStatePicker::new(7));
+    reachability_benchmark_with_picker::<BinaryIntegerDomain<u8>, _>(
+        MODEL,
+        &RandomStatePicker::new(7),
+    );
+}
+
+#[test]
+fn original_scc_search_terminates_with_random_seeds() {
+    reachability::reachability_benchmark_with_picker::<UnaryIntegerDomain, _>(
+        MODEL,
+        &RandomStatePicker::new(7),
+    );
+    reachability::reachability_benchmark_with_picker::<BinaryIntegerDomain<u8>, _>(
+        MODEL,
+        &RandomStatePicker::new(7),
+    );
+}
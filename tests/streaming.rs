@@ -0,0 +1,52 @@
+use biodivine_lib_logical_models::prelude as bio;
+use xml::reader::XmlEvent;
+
+fn reader(sbml: &str) -> xml::reader::EventReader<std::io::BufReader<&[u8]>> {
+    xml::reader::EventReader::new(std::io::BufReader::new(sbml.as_bytes()))
+}
+
+#[test]
+fn transitions_are_streamed_until_the_end_of_the_list() {
+    let sbml = std::fs::read_to_string("data/manual/three_level_variable.sbml")
+        .expect("should be able to read file");
+    let mut xml = reader(&sbml);
+    bio::find_start_of(&mut xml, &["listOfTransitions"]).expect("should be able to find");
+
+    let targets = bio::iter_transitions::<_, _, u8>(&mut xml)
+        .map(|update_fn| update_fn.expect("should be able to parse").target_var_name)
+        .collect::<Vec<_>>();
+    assert_eq!(targets, vec!["a", "b"]);
+
+    // the iterator must stop right after the closing tag of the list
+    let next_closing_tag = loop {
+        match xml.next().expect("should be able to read") {
+            XmlEvent::Whitespace(_) => continue,
+            other => break other,
+        }
+    };
+    assert!(
+        matches!(next_closing_tag, XmlEvent::EndElement { ref name } if name.local_name == "model"),
+        "unexpected event {:?}",
+        next_closing_tag
+    );
+}
+
+#[test]
+fn streaming_stops_after_error() {
+    let sbml = r#"<?xml version='1.0' encoding='UTF-8'?>
+        <sbml>
+            <listOfTransitions>
+                <notATransition />
+                <transition />
+            </listOfTransitions>
+        </sbml>"#;
+    let mut xml = reader(sbml);
+    bio::find_start_of(&mut xml, &["listOfTransitions"]).expect("should be able to find");
+
+    let mut transitions = bio::iter_transitions::<_, _, u8>(&mut xml);
+    assert!(matches!(
+        transitions.next(),
+        Some(Err(bio::XmlReadingError::UnexpectedEvent { .. }))
+    ));
+    assert!(transitions.next().is_none());
+}
@@ -0,0 +1,67 @@
+use biodivine_lib_bdd::Bdd;
+use biodivine_lib_logical_models::prelude::{
+    symbolic_domain::SymbolicDomainOrd, transition_system::SymbolicTransitionSystem,
+};
+
+mod common;
+use common::{load, test_all_encodings};
+
+/// Forward and backward reachability written only against the trait, the way a downstream
+/// algorithm would use it.
+fn forward(system: &impl SymbolicTransitionSystem, initial: &Bdd) -> Bdd {
+    let mut result = initial.and(&system.unit());
+    loop {
+        let next = result.or(&system.post(&result));
+        if next == result {
+            return result;
+        }
+        result = next;
+    }
+}
+
+fn backward(system: &impl SymbolicTransitionSystem, initial: &Bdd) -> Bdd {
+    let mut result = initial.and(&system.unit());
+    loop {
+        let next = result.or(&system.pre(&result));
+        if next == result {
+            return result;
+        }
+        result = next;
+    }
+}
+
+fn matches_concrete_system<DO: SymbolicDomainOrd<u8>>() {
+    std::fs::read_dir("data/manual")
+        .expect("could not read dir")
+        .for_each(|dirent| {
+            let tmp = dirent.expect("could not read dir entry").path();
+            let filepath = tmp.to_str().unwrap();
+
+            let system = load::<DO>(filepath);
+            assert_eq!(system.variables(), system.get_system_variables().as_slice());
+            assert_eq!(
+                SymbolicTransitionSystem::unit(&system),
+                system.unit_vertex_set()
+            );
+
+            let initial = system.pick_state_bdd(&system.unit_vertex_set());
+            assert_eq!(
+                system.post(&initial),
+                system.successors_async_any(&initial),
+                "{}",
+                filepath
+            );
+            assert_eq!(
+                system.pre(&initial),
+                system.predecessors_async_any(&initial),
+                "{}",
+                filepath
+            );
+
+            let reachable = forward(&system, &initial);
+            assert_eq!(reachable, system.reachable_from(&initial), "{}", filepath);
+            assert!(initial.imp(&backward(&system, &reachable)).is_true());
+        });
+}
+
+test_all_encodings!(matches_concrete_system);
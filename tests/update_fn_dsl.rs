@@ -0,0 +1,163 @@
+use biodivine_lib_logical_models::prelude::{
+    expression::Expression,
+    proposition::{ComparisonOperator, Proposition, VariableComparison},
+    update_fn_dsl::{parse_update_fn, ParseError},
+};
+
+fn prop(variable: &str, operator: ComparisonOperator, value: u8) -> Expression<u8> {
+    Expression::Terminal(Proposition::new(operator, variable.to_string(), value))
+}
+
+fn condition(src: &str) -> Expression<u8> {
+    let update_fn =
+        parse_update_fn("X", &format!("1: {src}; default 0")).expect("should be able to parse");
+    assert_eq!(update_fn.terms.len(), 1);
+    update_fn.terms.into_iter().next().unwrap().1
+}
+
+#[test]
+fn terms_and_default() {
+    let update_fn =
+        parse_update_fn("X", "2: A>=1 & B<3; 1: C==0; default 0").expect("should be able to parse");
+
+    assert_eq!(update_fn.target_var_name, "X");
+    assert_eq!(update_fn.input_vars_names, vec!["A", "B", "C"]);
+    assert_eq!(update_fn.default, 0);
+    assert_eq!(
+        update_fn.terms,
+        vec![
+            (
+                2,
+                Expression::And(vec![
+                    prop("A", ComparisonOperator::Geq, 1),
+                    prop("B", ComparisonOperator::Lt, 3),
+                ])
+            ),
+            (1, prop("C", ComparisonOperator::Eq, 0)),
+        ]
+    );
+
+    // the default can be anywhere, and a trailing semicolon is fine
+    let reordered = parse_update_fn("X", "default 0; 2: A>=1 & B<3; 1: C==0;")
+        .expect("should be able to parse");
+    assert_eq!(reordered.terms, update_fn.terms);
+    assert_eq!(reordered.default, 0);
+
+    let constant = parse_update_fn("X", "default 3").expect("should be able to parse");
+    assert!(constant.terms.is_empty());
+    assert!(constant.input_vars_names.is_empty());
+    assert_eq!(constant.default, 3);
+}
+
+#[test]
+fn precedence_of_connectives() {
+    let a = || prop("A", ComparisonOperator::Eq, 1);
+    let b = || prop("B", ComparisonOperator::Eq, 1);
+    let c = || prop("C", ComparisonOperator::Eq, 1);
+
+    // `&` binds tighter than `|`
+    assert_eq!(
+        condition("A == 1 | B == 1 & C == 1"),
+        Expression::Or(vec![a(), Expression::And(vec![b(), c()])])
+    );
+    // ... unless parenthesized
+    assert_eq!(
+        condition("(A == 1 | B == 1) & C == 1"),
+        Expression::And(vec![Expression::Or(vec![a(), b()]), c()])
+    );
+    // `!` binds the tightest
+    assert_eq!(
+        condition("!A == 1 & B == 1"),
+        Expression::And(vec![Expression::Not(Box::new(a())), b()])
+    );
+    // `|` binds tighter than `^`, which binds tighter than `=>`
+    assert_eq!(
+        condition("A == 1 => B == 1 ^ C == 1 | A == 1"),
+        Expression::Implies(
+            Box::new(a()),
+            Box::new(Expression::Xor(
+                Box::new(b()),
+                Box::new(Expression::Or(vec![c(), a()]))
+            ))
+        )
+    );
+    // `=>` is right associative
+    assert_eq!(
+        condition("A == 1 => B == 1 => C == 1"),
+        Expression::Implies(
+            Box::new(a()),
+            Box::new(Expression::Implies(Box::new(b()), Box::new(c())))
+        )
+    );
+    // `^` is left associative
+    assert_eq!(
+        condition("A == 1 ^ B == 1 ^ C == 1"),
+        Expression::Xor(
+            Box::new(Expression::Xor(Box::new(a()), Box::new(b()))),
+            Box::new(c())
+        )
+    );
+}
+
+#[test]
+fn comparisons_and_constants() {
+    assert_eq!(condition("2 > A"), prop("A", ComparisonOperator::Lt, 2));
+    assert_eq!(condition("A != 1"), prop("A", ComparisonOperator::Neq, 1));
+    assert_eq!(condition("A <> 1"), prop("A", ComparisonOperator::Neq, 1));
+    assert_eq!(condition("A = 1"), prop("A", ComparisonOperator::Eq, 1));
+    assert_eq!(
+        condition("A <= B"),
+        Expression::VariableComparison(VariableComparison::new(
+            ComparisonOperator::Leq,
+            "A".to_string(),
+            "B".to_string()
+        ))
+    );
+    assert_eq!(condition("true"), Expression::And(vec![]));
+    assert_eq!(condition("false"), Expression::Or(vec![]));
+}
+
+#[test]
+fn displayed_expressions_parse_back() {
+    let expression = condition("A >= 1 & (B < 3 | !(C == 0)) => A != 2 ^ B > 0");
+    assert_eq!(condition(&expression.to_string()), expression);
+}
+
+#[test]
+fn malformed_sources_are_rejected() {
+    let error = |src: &str| parse_update_fn("X", src).err();
+
+    assert_eq!(error("1: A == 1"), Some(ParseError::MissingDefault));
+    assert_eq!(
+        error("default 0; default 1"),
+        Some(ParseError::DuplicateDefault { position: 11 })
+    );
+    assert_eq!(
+        error("1: A == 300; default 0"),
+        Some(ParseError::InvalidValue {
+            position: 8,
+            value: "300".to_string()
+        })
+    );
+    assert_eq!(
+        error("1: A # 1; default 0"),
+        Some(ParseError::UnexpectedCharacter {
+            position: 5,
+            character: '#'
+        })
+    );
+    assert_eq!(
+        error("1: (A == 1; default 0"),
+        Some(ParseError::UnexpectedToken {
+            position: 10,
+            expected: "`)`",
+            found: ";".to_string()
+        })
+    );
+    assert_eq!(
+        error("1: A == 1 &"),
+        Some(ParseError::UnexpectedEnd {
+            expected: "a condition"
+        })
+    );
+}
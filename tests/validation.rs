@@ -0,0 +1,81 @@
+use biodivine_lib_logical_models::validation::{
+    assert_encodings_agree, assert_encodings_agree_deep, DeepMismatch, Mismatch,
+};
+use num_bigint::BigInt;
+
+#[test]
+fn encodings_agree_on_manual_models() {
+    std::fs::read_dir("data/manual")
+        .expect("could not read dir")
+        .for_each(|dirent| {
+            let tmp = dirent.expect("could not read dir entry").path();
+            let filepath = tmp.to_str().unwrap();
+
+            assert_eq!(
+                assert_encodings_agree(filepath, 100),
+                Ok(()),
+                "{}",
+                filepath
+            );
+        });
+}
+
+#[test]
+fn encodings_agree_deeply_on_manual_models() {
+    std::fs::read_dir("data/manual")
+        .expect("could not read dir")
+        .for_each(|dirent| {
+            let tmp = dirent.expect("could not read dir entry").path();
+            let filepath = tmp.to_str().unwrap();
+
+            assert_eq!(
+                assert_encodings_agree_deep(filepath, 100),
+                Ok(()),
+                "{}",
+                filepath
+            );
+        });
+}
+
+#[test]
+fn zero_steps_is_trivially_ok() {
+    assert_eq!(
+        assert_encodings_agree("data/manual/handbook_example.sbml", 0),
+        Ok(())
+    );
+}
+
+#[test]
+fn mismatch_names_the_step_and_counts() {
+    let mismatch = Mismatch {
+        step: 3,
+        counts: [4, 4, 5, 4].into_iter().map(BigInt::from).collect(),
+    };
+    assert_eq!(
+        mismatch.to_string(),
+        "encodings disagree at step 3: 4 (unary) <> 4 (binary) <> 5 (gray) <> 4 (petri net)"
+    );
+}
+
+#[test]
+fn deep_mismatch_names_the_state() {
+    let mismatch = DeepMismatch::States {
+        step: 2,
+        encoding: "gray",
+        state: vec![0, 2, 1],
+    };
+    assert_eq!(
+        mismatch.to_string(),
+        "encodings disagree at step 2: state [0, 2, 1] is reached by only one of unary and gray"
+    );
+
+    // count mismatches are reported as they are
+    let mismatch = Mismatch {
+        step: 1,
+        counts: [1, 2, 1, 1].into_iter().map(BigInt::from).collect(),
+    };
+    assert_eq!(
+        DeepMismatch::from(mismatch.clone()).to_string(),
+        mismatch.to_string()
+    );
+}
@@ -0,0 +1,408 @@
+use std::collections::HashMap;
+
+use biodivine_lib_bdd::BddVariableSetBuilder;
+use biodivine_lib_logical_models::prelude::{
+    expression::Expression,
+    proposition::{ComparisonOperator, Proposition},
+    symbolic_domain::SymbolicDomainOrd,
+    unprocessed_variable_update_function::UnprocessedVariableUpdateFn,
+    update_fn::variable_update_fn::{OverlappingTerms, TermSemantics, VariableUpdateFn},
+};
+
+mod common;
+use common::test_all_encodings;
+
+fn eq(variable: &str, value: u8) -> Expression<u8> {
+    Expression::Terminal(Proposition::new(
+        ComparisonOperator::Eq,
+        variable.to_string(),
+        value,
+    ))
+}
+
+fn conditions_of_output_values<DO: SymbolicDomainOrd<u8>>() {
+    let mut builder = BddVariableSetBuilder::new();
+    let a = DO::new(&mut builder, "a", &2);
+    let b = DO::new(&mut builder, "b", &1);
+    let variables = builder.build();
+    let domains = HashMap::from([("a", &a), ("b", &b)]);
+    let unit = a
+        .unit_collection(&variables)
+        .and(&b.unit_collection(&variables));
+
+    // a = 2 if b == 1; 1 if a == 0; 0 otherwise
+    let update_fn = UnprocessedVariableUpdateFn::new(
+        vec!["a".to_string(), "b".to_string()],
+        "a".to_string(),
+        vec![(2, eq("b", 1)), (1, eq("a", 0))],
+        0,
+    );
+    let compiled = VariableUpdateFn::from_update_fn(&update_fn, "a", &variables, &domains);
+
+    let b_is_one = b.encode_one(&variables, &1);
+    let a_is_zero = a.encode_one(&variables, &0);
+    assert_eq!(condition_of(&compiled, 2).and(&unit), b_is_one.and(&unit));
+    assert_eq!(
+        condition_of(&compiled, 1).and(&unit),
+        a_is_zero.and_not(&b_is_one).and(&unit)
+    );
+    assert_eq!(
+        condition_of(&compiled, 0).and(&unit),
+        unit.and_not(&a_is_zero).and_not(&b_is_one)
+    );
+    assert!(compiled.condition_for_value(&3).is_none());
+
+    // a = 1 if b == 1; 1 if b == 0; 0 otherwise; the conditions of equal outputs are merged
+    let update_fn = UnprocessedVariableUpdateFn::new(
+        vec!["b".to_string()],
+        "a".to_string(),
+        vec![(1, eq("b", 1)), (1, eq("b", 0))],
+        0,
+    );
+    let compiled = VariableUpdateFn::from_update_fn(&update_fn, "a", &variables, &domains);
+    assert_eq!(condition_of(&compiled, 1).and(&unit), unit);
+    assert!(condition_of(&compiled, 0).and(&unit).is_false());
+    assert!(compiled.condition_for_value(&2).is_none());
+}
+
+fn condition_of(compiled: &VariableUpdateFn<u8>, value: u8) -> biodivine_lib_bdd::Bdd {
+    compiled
+        .condition_for_value(&value)
+        .expect("value is an output")
+        .clone()
+}
+
+/// The transition relation built from the bits as documented equals the one built from
+/// the conditions of the output values.
+fn bit_answering_bdds_form_the_relation<DO: SymbolicDomainOrd<u8>>() {
+    let mut builder = BddVariableSetBuilder::new();
+    let a = DO::new(&mut builder, "a", &2);
+    let a_primed = DO::new(&mut builder, "a'", &2);
+    let b = DO::new(&mut builder, "b", &1);
+    let variables = builder.build();
+    let domains = HashMap::from([("a", &a), ("b", &b)]);
+    let unit = a
+        .unit_collection(&variables)
+        .and(&b.unit_collection(&variables));
+
+    // a = 2 if b == 1; 1 if a == 0; 0 otherwise
+    let update_fn = UnprocessedVariableUpdateFn::new(
+        vec!["a".to_string(), "b".to_string()],
+        "a".to_string(),
+        vec![(2, eq("b", 1)), (1, eq("a", 0))],
+        0,
+    );
+    let compiled = VariableUpdateFn::from_update_fn(&update_fn, "a", &variables, &domains);
+
+    let bits = compiled.bit_answering_bdds();
+    assert_eq!(
+        bits.iter().map(|(bit, _)| *bit).collect::<Vec<_>>(),
+        a.raw_bdd_variables()
+    );
+    let from_bits = bits
+        .iter()
+        .zip(a_primed.raw_bdd_variables())
+        .fold(unit.clone(), |acc, ((_, bdd), primed_bit)| {
+            acc.and(&variables.mk_var(primed_bit).iff(bdd))
+        });
+
+    let from_values = (0..=2).fold(variables.mk_false(), |acc, value| {
+        acc.or(&condition_of(&compiled, value)
+            .and(&unit)
+            .and(&a_primed.encode_one(&variables, &value)))
+    });
+    // within the valid states, the bits always encode a valid value
+    assert_eq!(from_bits, from_values);
+    assert_eq!(
+        from_bits.and(&a_primed.unit_collection(&variables)),
+        from_values
+    );
+}
+
+/// The update function compiled from the recovered expressions has the same conditions.
+fn expressions_are_recovered<DO: SymbolicDomainOrd<u8>>() {
+    let mut builder = BddVariableSetBuilder::new();
+    let a = DO::new(&mut builder, "a", &2);
+    let b = DO::new(&mut builder, "b", &1);
+    let variables = builder.build();
+    let domains = HashMap::from([("a", &a), ("b", &b)]);
+    let unit = a
+        .unit_collection(&variables)
+        .and(&b.unit_collection(&variables));
+
+    // a = 2 if b == 1; 1 if a == 0 & b != 1; 0 otherwise
+    let update_fn = UnprocessedVariableUpdateFn::new(
+        vec!["a".to_string(), "b".to_string()],
+        "a".to_string(),
+        vec![(2, eq("b", 1)), (1, eq("a", 0))],
+        0,
+    );
+    let compiled = VariableUpdateFn::from_update_fn(&update_fn, "a", &variables, &domains);
+
+    let recovered = UnprocessedVariableUpdateFn::new(
+        vec!["a".to_string(), "b".to_string()],
+        "a".to_string(),
+        (1..=2)
+            .map(|value| (value, compiled.to_expression(&value, &variables, &domains)))
+            .collect(),
+        0,
+    );
+    let recompiled = VariableUpdateFn::from_update_fn(&recovered, "a", &variables, &domains);
+    for value in 0..=2 {
+        assert_eq!(
+            condition_of(&recompiled, value).and(&unit),
+            condition_of(&compiled, value).and(&unit)
+        );
+    }
+
+    // only `b` is constrained for the output `2`
+    let expression = compiled.to_expression(&2, &variables, &domains);
+    assert_eq!(
+        expression
+            .referenced_variables()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec!["b"]
+    );
+    assert_eq!(
+        compiled.to_expression(&3, &variables, &domains),
+        Expression::Or(Vec::new())
+    );
+}
+
+/// The condition of a `Neq` proposition only admits valid encodings of the compared variable.
+fn neq_stays_within_domain<DO: SymbolicDomainOrd<u8>>() {
+    let mut builder = BddVariableSetBuilder::new();
+    let a = DO::new(&mut builder, "a", &2);
+    let b = DO::new(&mut builder, "b", &1);
+    let variables = builder.build();
+    let domains = HashMap::from([("a", &a), ("b", &b)]);
+
+    // b = 1 if a != 1; 0 otherwise
+    let update_fn = UnprocessedVariableUpdateFn::new(
+        vec!["a".to_string()],
+        "b".to_string(),
+        vec![(
+            1,
+            Expression::Terminal(Proposition::new(
+                ComparisonOperator::Neq,
+                "a".to_string(),
+                1,
+            )),
+        )],
+        0,
+    );
+    let compiled = VariableUpdateFn::from_update_fn(&update_fn, "b", &variables, &domains);
+
+    let expected = a
+        .encode_one(&variables, &0)
+        .or(&a.encode_one(&variables, &2));
+    assert_eq!(condition_of(&compiled, 1), expected);
+    assert!(condition_of(&compiled, 1)
+        .imp(&a.unit_collection(&variables))
+        .is_true());
+}
+
+/// The conditions of wide (and nested) conjunctions and disjunctions match the plain
+/// left-to-right combination of their propositions.
+fn wide_clauses<DO: SymbolicDomainOrd<u8>>() {
+    let names = (0..9).map(|idx| format!("x{}", idx)).collect::<Vec<_>>();
+    let mut builder = BddVariableSetBuilder::new();
+    let domains = names
+        .iter()
+        .map(|name| DO::new(&mut builder, name, &2))
+        .collect::<Vec<_>>();
+    let variables = builder.build();
+    let named_domains = names
+        .iter()
+        .map(String::as_str)
+        .zip(domains.iter())
+        .collect::<HashMap<_, _>>();
+    let unit = domains.iter().fold(variables.mk_true(), |acc, domain| {
+        acc.and(&domain.unit_collection(&variables))
+    });
+
+    let propositions = names.iter().map(|name| eq(name, 1)).collect::<Vec<_>>();
+    let encoded = domains
+        .iter()
+        .map(|domain| domain.encode_one(&variables, &1))
+        .collect::<Vec<_>>();
+
+    let nested_and = Expression::And(vec![
+        Expression::And(propositions[..4].to_vec()),
+        Expression::And(propositions[4..].to_vec()),
+    ]);
+    let expressions = [
+        (
+            Expression::And(propositions.clone()),
+            encoded
+                .iter()
+                .fold(variables.mk_true(), |acc, it| acc.and(it)),
+        ),
+        (
+            nested_and,
+            encoded
+                .iter()
+                .fold(variables.mk_true(), |acc, it| acc.and(it)),
+        ),
+        (
+            Expression::Or(propositions.clone()),
+            encoded
+                .iter()
+                .fold(variables.mk_false(), |acc, it| acc.or(it)),
+        ),
+    ];
+
+    for (expression, expected) in expressions {
+        let update_fn = UnprocessedVariableUpdateFn::new(
+            names.clone(),
+            "x0".to_string(),
+            vec![(1, expression)],
+            0,
+        );
+        let compiled =
+            VariableUpdateFn::from_update_fn(&update_fn, "x0", &variables, &named_domains);
+        assert_eq!(condition_of(&compiled, 1).and(&unit), expected.and(&unit));
+    }
+}
+
+/// The shortcut for Boolean target variables yields exactly the same function as the
+/// general construction, including for overlapping terms.
+fn boolean_shortcut_matches_general<DO: SymbolicDomainOrd<u8>>() {
+    let mut builder = BddVariableSetBuilder::new();
+    let a = DO::new(&mut builder, "a", &1);
+    let b = DO::new(&mut builder, "b", &1);
+    let c = DO::new(&mut builder, "c", &2);
+    let variables = builder.build();
+    let domains = HashMap::from([("a", &a), ("b", &b), ("c", &c)]);
+
+    let overlapping = vec![
+        (1, eq("b", 1)),
+        (0, Expression::Or(vec![eq("b", 1), eq("c", 2)])),
+        (1, eq("c", 0)),
+    ];
+    let cases = [
+        (overlapping.clone(), 0),
+        (overlapping, 1),
+        (vec![(1, eq("b", 1)), (1, eq("c", 1))], 1),
+        (vec![(0, eq("b", 0)), (0, eq("c", 1))], 0),
+        (vec![(0, eq("a", 1))], 1),
+        (vec![], 1),
+    ];
+
+    for (terms, default) in cases {
+        let update_fn = UnprocessedVariableUpdateFn::new(
+            vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            "a".to_string(),
+            terms,
+            default,
+        );
+        let shortcut = VariableUpdateFn::from_update_fn(&update_fn, "a", &variables, &domains);
+        let general =
+            VariableUpdateFn::from_update_fn_general(&update_fn, "a", &variables, &domains);
+
+        assert_eq!(shortcut.bit_answering_bdds(), general.bit_answering_bdds());
+        for value in 0..=1 {
+            assert_eq!(
+                shortcut.condition_for_value(&value),
+                general.condition_for_value(&value)
+            );
+        }
+    }
+}
+
+/// Overlapping terms are only rejected when disjoint terms are required.
+fn disjoint_terms_are_enforced<DO: SymbolicDomainOrd<u8>>() {
+    let mut builder = BddVariableSetBuilder::new();
+    let a = DO::new(&mut builder, "a", &2);
+    let b = DO::new(&mut builder, "b", &2);
+    let variables = builder.build();
+    let domains = HashMap::from([("a", &a), ("b", &b)]);
+
+    let ge = |variable: &str, value: u8| {
+        Expression::Terminal(Proposition::new(
+            ComparisonOperator::Geq,
+            variable.to_string(),
+            value,
+        ))
+    };
+    let compile = |terms: Vec<(u8, Expression<u8>)>, semantics: TermSemantics| {
+        let update_fn =
+            UnprocessedVariableUpdateFn::new(vec!["b".to_string()], "a".to_string(), terms, 0);
+        VariableUpdateFn::try_from_update_fn(&update_fn, "a", &variables, &domains, semantics)
+    };
+
+    // a = 2 if b == 0; 1 if b >= 1; 2 if b >= 2 (the last two terms overlap)
+    let overlapping = vec![(2, eq("b", 0)), (1, ge("b", 1)), (2, ge("b", 2))];
+    let permissive = compile(overlapping.clone(), TermSemantics::FirstWins)
+        .expect("first match wins, so overlaps are fine");
+    let reference = VariableUpdateFn::from_update_fn(
+        &UnprocessedVariableUpdateFn::new(
+            vec!["b".to_string()],
+            "a".to_string(),
+            overlapping.clone(),
+            0,
+        ),
+        "a",
+        &variables,
+        &domains,
+    );
+    for value in 0..=2 {
+        assert_eq!(
+            permissive.condition_for_value(&value),
+            reference.condition_for_value(&value)
+        );
+    }
+    assert_eq!(
+        compile(overlapping, TermSemantics::RequireDisjoint).err(),
+        Some(OverlappingTerms {
+            target: "a".to_string(),
+            first: 1,
+            second: 2,
+        })
+    );
+
+    // a = 2 if b == 0; 1 if b == 1; 2 if b >= 2
+    let disjoint = vec![(2, eq("b", 0)), (1, eq("b", 1)), (2, ge("b", 2))];
+    assert!(compile(disjoint, TermSemantics::RequireDisjoint).is_ok());
+}
+
+fn structural_equality<DO: SymbolicDomainOrd<u8>>() {
+    let mut builder = BddVariableSetBuilder::new();
+    let a = DO::new(&mut builder, "a", &2);
+    let b = DO::new(&mut builder, "b", &1);
+    let variables = builder.build();
+    let domains = HashMap::from([("a", &a), ("b", &b)]);
+    let compile = |terms: Vec<(u8, Expression<u8>)>| {
+        let update_fn = UnprocessedVariableUpdateFn::new(
+            vec!["a".to_string(), "b".to_string()],
+            "a".to_string(),
+            terms,
+            0,
+        );
+        VariableUpdateFn::from_update_fn(&update_fn, "a", &variables, &domains)
+    };
+
+    let compiled = compile(vec![(2, Expression::Or(vec![eq("a", 1), eq("b", 1)]))]);
+    // the same function, written differently
+    let equivalent = compile(vec![
+        (2, eq("b", 1)),
+        (2, Expression::Or(vec![eq("a", 1), eq("b", 1)])),
+    ]);
+    let different = compile(vec![(2, eq("b", 1))]);
+
+    assert_eq!(compiled, equivalent);
+    assert_ne!(compiled, different);
+    assert_eq!(compiled.clone(), compiled);
+}
+
+test_all_encodings!(
+    conditions_of_output_values,
+    bit_answering_bdds_form_the_relation,
+    expressions_are_recovered,
+    neq_stays_within_domain,
+    wide_clauses,
+    boolean_shortcut_matches_general,
+    disjoint_terms_are_enforced,
+    structural_equality,
+);
@@ -0,0 +1,74 @@
+use biodivine_lib_logical_models::prelude::symbolic_domain::{
+    SymbolicDomainOrd, UnaryIntegerDomain,
+};
+
+mod common;
+use common::{load, load_dumb, test_all_encodings};
+
+/// Restricting the target values to all of them (either at once, or one by one) gives
+/// the unrestricted predecessors.
+fn union_over_values_matches_predecessors<DO: SymbolicDomainOrd<u8>>() {
+    std::fs::read_dir("data/manual")
+        .expect("could not read dir")
+        .for_each(|dirent| {
+            let tmp = dirent.expect("could not read dir entry").path();
+            let filepath = tmp.to_str().unwrap();
+            // the smart system is only used for enumerating the variables & their values
+            let (system, smart) = (load_dumb::<DO>(filepath), load::<DO>(filepath));
+
+            for (name, domain) in smart.standard_variables_names_and_domains() {
+                let values = domain.get_all_possible_values();
+                for source_value in &values {
+                    let source = system.encode_one(name, source_value);
+                    let expected = system.predecessors_async(name, &source);
+
+                    assert_eq!(
+                        system.predecessors_async_to_values(name, &values, &source),
+                        expected,
+                        "{} {}",
+                        filepath,
+                        name
+                    );
+                    let union = values
+                        .iter()
+                        .map(|value| {
+                            let predecessors =
+                                system.predecessors_async_to_values(name, &[*value], &source);
+                            assert!(predecessors.imp(&expected).is_true());
+                            predecessors
+                        })
+                        .reduce(|acc, predecessors| acc.or(&predecessors))
+                        .expect("every domain has some value");
+                    assert_eq!(union, expected, "{} {}", filepath, name);
+
+                    assert!(system
+                        .predecessors_async_to_values(name, &[], &source)
+                        .is_false());
+                    // values outside of the domain are ignored
+                    assert!(system
+                        .predecessors_async_to_values(name, &[u8::MAX], &source)
+                        .is_false());
+                }
+            }
+        });
+}
+
+test_all_encodings!(union_over_values_matches_predecessors);
+
+#[test]
+fn predecessors_setting_a_specific_value() {
+    let system = load_dumb::<UnaryIntegerDomain>("data/manual/three_level_variable.sbml");
+
+    // the states that can update `a` to 2 (from anywhere, since any state with `a == 2`
+    // is a valid target)
+    let a_is_two = system.encode_one("a", &2);
+    let predecessors = system.predecessors_async_to_values("a", &[2], &a_is_two);
+    assert!(!predecessors.is_false());
+    assert!(predecessors
+        .imp(&system.predecessors_async("a", &a_is_two))
+        .is_true());
+    // no state with `a == 2` can be reached by setting `a` to 0 or 1
+    assert!(system
+        .predecessors_async_to_values("a", &[0, 1], &a_is_two)
+        .is_false());
+}